@@ -0,0 +1,180 @@
+/*
+    对外接口
+    1. AssistantEvent / EventHub：唤醒、对话状态、意图等事件广播给订阅者（IPC 等）
+    2. ControlRequest：外部进程注入的控制请求，由事件循环统一处理并应答
+*/
+
+use crate::dialog::DialogState;
+use crate::profile::Preferences;
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use voice::array::Seat;
+use voice::power::PowerMode;
+use voice::sound::SoundKind;
+
+/// 助手事件
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AssistantEvent {
+    Wake {
+        speaker: Option<String>,
+        score: Option<f32>, // 唤醒词得分
+        seat: Option<Seat>,
+    },
+    #[serde(rename = "dialog_state")]
+    Dialog(DialogState),
+    Intent {
+        intent: String,
+        slots: HashMap<String, String>,
+        confidence: f32,
+        transcript: String,
+    },
+    Response {
+        text: String,
+        succeeded: bool,
+    },
+    Muted {
+        muted: bool,
+    },
+    Power {
+        mode: PowerMode,
+    },
+    Sound {
+        kind: SoundKind,
+        confidence: f32,
+    }, // 车内声音事件（警笛、喇叭等）
+}
+
+impl AssistantEvent {
+    /// 事件类型名（用于订阅过滤）
+    pub fn kind(&self) -> &'static str {
+        match self {
+            AssistantEvent::Wake { .. } => "wake",
+            AssistantEvent::Dialog(_) => "dialog_state",
+            AssistantEvent::Intent { .. } => "intent",
+            AssistantEvent::Response { .. } => "response",
+            AssistantEvent::Muted { .. } => "muted",
+            AssistantEvent::Power { .. } => "power",
+            AssistantEvent::Sound { .. } => "sound",
+        }
+    }
+}
+
+/// 事件广播
+#[derive(Clone, Default)]
+pub struct EventHub {
+    subscribers: Arc<Mutex<Vec<Sender<AssistantEvent>>>>,
+}
+
+impl EventHub {
+    pub fn subscribe(&self) -> Receiver<AssistantEvent> {
+        let (sender, receiver) = unbounded();
+        self.subscribers.lock().unwrap().push(sender);
+        receiver
+    }
+
+    /// 发布事件，移除已断开的订阅者
+    pub fn publish(&self, event: AssistantEvent) {
+        self.subscribers
+            .lock()
+            .unwrap()
+            .retain(|subscriber| subscriber.send(event.clone()).is_ok());
+    }
+}
+
+/// 控制请求
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "method", content = "params", rename_all = "snake_case")]
+pub enum ControlRequest {
+    Status,
+    StartListening,
+    Cancel,
+    Mute {
+        muted: bool,
+    }, // 隐私模式：关闭麦克风并丢弃缓存的音频
+    Speak {
+        text: String,
+    },
+    Execute {
+        text: String,
+    }, // 按识别文本执行
+    Profiles, // 列出用户档案
+    SelectProfile {
+        profile: Option<String>,
+    },
+    UpdateProfile {
+        profile: String,
+        preferences: Preferences,
+    },
+    DeleteProfile {
+        profile: String,
+    },
+    EnrollVoice {
+        profile: String,
+    }, // 以最近一次唤醒的语音注册声纹
+}
+
+impl ControlRequest {
+    /// 所有方法名（即序列化后的 method）
+    pub const METHODS: [&'static str; 11] = [
+        "status",
+        "start_listening",
+        "cancel",
+        "mute",
+        "speak",
+        "execute",
+        "profiles",
+        "select_profile",
+        "update_profile",
+        "delete_profile",
+        "enroll_voice",
+    ];
+}
+
+/// 待处理的控制请求及应答通道
+pub struct Control {
+    pub request: ControlRequest,
+    pub reply: Sender<Result<Value, String>>,
+}
+
+impl Control {
+    /// 提交请求并等待事件循环应答
+    pub fn call(controls: &Sender<Control>, request: ControlRequest) -> Result<Value, String> {
+        let (reply, response) = unbounded();
+        controls
+            .send(Control { request, reply })
+            .map_err(|_| "assistant is shutting down".to_string())?;
+        response
+            .recv()
+            .map_err(|_| "assistant is shutting down".to_string())?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn sound_event_reaches_subscribers() {
+        let hub = EventHub::default();
+        let events = hub.subscribe();
+        let closed = hub.subscribe();
+        drop(closed);
+
+        hub.publish(AssistantEvent::Sound {
+            kind: SoundKind::Siren,
+            confidence: 0.75,
+        });
+        let event = events.try_recv().unwrap();
+        assert_eq!(event.kind(), "sound");
+        assert_eq!(
+            serde_json::to_value(&event).unwrap(),
+            json!({ "type": "sound", "kind": "siren", "confidence": 0.75 })
+        );
+        assert_eq!(hub.subscribers.lock().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,369 @@
+/*
+    命令行
+    1. run：启动助手（GUI 或无界面模式）
+    2. devices：列出音频输入设备
+    3. enroll / eval：由 WAV 文件注册声纹、评估唤醒检测
+    4. replay：回放事件日志
+    5. config check：检查配置
+    全局选项（配置文件、日志级别、音频输入）对所有子命令生效
+*/
+
+use crate::intent::IntentParser;
+use crate::mqtt::MqttConfig;
+use crate::profile::{ProfileStore, VoicePrint};
+use crate::skill;
+use crate::tts::command::CommandSynthesizer;
+use crate::tts::resample;
+use crate::vehicle::dbc::Dbc;
+use anyhow::{anyhow, Context};
+use clap::{Parser, Subcommand};
+use cpal::traits::{DeviceTrait, HostTrait};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use voice::array::ArrayFrontEnd;
+use voice::audio::{input_device, wav::read_wav, CAPTURE_RATE};
+use voice::config::Settings;
+use voice::speaker::verifier::SpeakerVerifier;
+use voice::speaker::SpeakerId;
+use voice::wakeword::detector::WakeDetector;
+use voice::wakeword::model::WakeModel;
+
+/// 离线注册与评估的采样率：与麦克风输入一致（运行时检测器与声纹按音频流的采样率构建），
+/// 检测器、声纹与读入的音频都使用这一采样率
+const OFFLINE_RATE: u32 = CAPTURE_RATE;
+
+/// 车载语音助手
+#[derive(Debug, Parser)]
+#[command(version)]
+pub struct Cli {
+    /// 配置文件（TOML），未指定的项使用默认值
+    #[arg(short, long, global = true, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+
+    /// 日志级别（可被 RUST_LOG 覆盖）
+    #[arg(long, global = true, value_name = "LEVEL")]
+    pub log_level: Option<String>,
+
+    /// 音频输入：设备名称，或代替麦克风的 WAV 文件
+    #[arg(short, long, global = true, value_name = "DEVICE|FILE")]
+    pub audio: Option<String>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// 启动助手（默认）
+    Run {
+        /// 不启动 GUI，状态输出到日志
+        #[arg(long)]
+        headless: bool,
+    },
+    /// 列出音频输入设备
+    Devices,
+    /// 由 WAV 文件注册声纹并保存到档案
+    Enroll {
+        /// 档案（说话人）标识
+        profile: String,
+        /// 注册语音，未指定时使用 --audio 文件
+        files: Vec<PathBuf>,
+    },
+    /// 在 WAV 文件上运行唤醒检测，输出唤醒时刻与得分
+    Eval {
+        /// 测试音频，未指定时使用 --audio 文件
+        files: Vec<PathBuf>,
+    },
+    /// 按虚拟时间回放事件日志并校验状态切换
+    Replay {
+        /// 事件日志的会话目录
+        directory: PathBuf,
+    },
+    /// 配置文件
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ConfigCommand {
+    /// 检查配置（模型、语法、技能、地址等）
+    Check,
+}
+
+impl Cli {
+    /// 配置文件加上命令行选项
+    pub fn settings(&self) -> Result<Settings, anyhow::Error> {
+        let mut settings = match &self.config {
+            Some(path) => Settings::from_file(path)?,
+            None => Settings::default(),
+        };
+        if let Some(level) = &self.log_level {
+            settings.log_level = level.clone();
+        }
+        // 存在同名文件时视为音频文件，否则为设备名称
+        if let Some(audio) = &self.audio {
+            if Path::new(audio).is_file() {
+                settings.audio_file = Some(audio.clone());
+            } else {
+                settings.audio_device = Some(audio.clone());
+                settings.audio_file = None;
+            }
+        }
+        Ok(settings)
+    }
+}
+
+/// 列出音频输入设备及支持的格式（* 为默认设备）
+pub fn devices() -> Result<(), anyhow::Error> {
+    let host = cpal::default_host();
+    let default = host.default_input_device().and_then(|d| d.name().ok());
+    for device in host.input_devices()? {
+        let name = device.name()?;
+        let marker = if default.as_ref() == Some(&name) {
+            "*"
+        } else {
+            " "
+        };
+        println!("{} {}", marker, name);
+        for config in device.supported_input_configs()? {
+            println!(
+                "    {} ch, {}-{} Hz, {:?}",
+                config.channels(),
+                config.min_sample_rate().0,
+                config.max_sample_rate().0,
+                config.sample_format()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// 由 WAV 文件注册声纹，累计到档案中已有的声纹
+pub fn enroll(settings: &Settings, profile: &str, files: &[PathBuf]) -> Result<(), anyhow::Error> {
+    let files = audio_files(settings, files)?;
+    let path = settings
+        .profile_path
+        .as_ref()
+        .ok_or_else(|| anyhow!("profile_path is not configured"))?;
+    let mut store = ProfileStore::open(path)?;
+
+    let id = SpeakerId::from(profile);
+    let mut verifier = SpeakerVerifier::new(OFFLINE_RATE);
+    if let Some(speaker) = store.get(profile).and_then(|p| p.speaker_profile()) {
+        verifier.insert_profile(speaker);
+    }
+    for file in &files {
+        let audio = read_mono(file, OFFLINE_RATE)?;
+        verifier
+            .enroll(id.clone(), &audio)
+            .with_context(|| format!("failed to enroll {}", file.display()))?;
+    }
+
+    let speaker = verifier
+        .profiles()
+        .iter()
+        .find(|p| p.id == id)
+        .expect("speaker was just enrolled");
+    let voice = VoicePrint {
+        centroid: speaker.centroid.to_vec(),
+        enrollments: speaker.enrollments,
+    };
+    let enrollments = voice.enrollments;
+    store.update(profile, |p| p.voice = Some(voice))?;
+    println!("{}: {} enrollment(s)", profile, enrollments);
+    Ok(())
+}
+
+/// 逐个文件运行唤醒检测
+pub fn eval(settings: &Settings, files: &[PathBuf]) -> Result<(), anyhow::Error> {
+    let files = audio_files(settings, files)?;
+    let mut detector = WakeDetector::new(OFFLINE_RATE)?;
+    let frame = OFFLINE_RATE as usize / 100;
+
+    let mut total = 0;
+    for file in &files {
+        let audio = read_mono(file, OFFLINE_RATE)?;
+        detector.reset();
+        let mut wakes = Vec::new();
+        let mut rejects = 0;
+        let mut best: Option<f32> = None;
+        for (index, chunk) in audio.chunks(frame).enumerate() {
+            let woke = detector.process(chunk);
+            if let Some(score) = detector.last_score().filter(|s| s.is_finite()) {
+                best = Some(best.map_or(score, |best| best.max(score)));
+            }
+            if woke {
+                let seconds = ((index + 1) * frame) as f32 / OFFLINE_RATE as f32;
+                wakes.push(format!("{:.2}s", seconds));
+            } else if detector.is_rejected() {
+                rejects += 1;
+            }
+        }
+        println!(
+            "{}: {} wake(s) [{}], {} reject(s), best score {}",
+            file.display(),
+            wakes.len(),
+            wakes.join(", "),
+            rejects,
+            best.map_or("-".to_string(), |score| format!("{:.3}", score))
+        );
+        total += wakes.len();
+    }
+    println!(
+        "{} file(s), {} wake(s), threshold {}",
+        files.len(),
+        total,
+        detector.threshold()
+    );
+    Ok(())
+}
+
+/// 检查配置，列出所有问题
+pub fn check_config(settings: &Settings) -> Result<(), anyhow::Error> {
+    let mut problems = Vec::new();
+    let mut check = |result: Result<(), anyhow::Error>| {
+        if let Err(e) = result {
+            problems.push(format!("{:#}", e));
+        }
+    };
+
+    check(WakeModel::load(settings, OFFLINE_RATE).map(|_| ()));
+    if let Some(path) = &settings.audio_file {
+        check(read_wav(path).and_then(|wav| check_array(settings, wav.channels, wav.sample_rate)));
+    } else {
+        if settings.audio_device.is_some() {
+            check(input_device(settings.audio_device.as_deref()).map(|_| ()));
+        }
+        check(check_array(settings, settings.channels, CAPTURE_RATE));
+    }
+    if let Some(dir) = &settings.grammar_dir {
+        check(IntentParser::from_dir(dir).map(|_| ()));
+    }
+    for name in &settings.skills {
+        if skill::builtin(name).is_none() {
+            check(Err(anyhow!("unknown skill: {}", name)));
+        }
+    }
+    if let Some(path) = &settings.dbc_path {
+        check(Dbc::load(path).map(|_| ()));
+    }
+    if let Some(asr) = settings.asr.clone() {
+        check(asr.build().map(|_| ()));
+    }
+    if let Some(command) = &settings.tts_command {
+        check(CommandSynthesizer::parse(command).map(|_| ()));
+    }
+    if let Some(address) = &settings.metrics_addr {
+        check(
+            address
+                .parse::<SocketAddr>()
+                .map(|_| ())
+                .with_context(|| format!("invalid metrics_addr: {}", address)),
+        );
+    }
+    if let Some(Err(e)) = MqttConfig::from_settings(settings) {
+        check(Err(e));
+    }
+    if settings
+        .wakeword_reload_secs
+        .is_some_and(|secs| secs <= 0.0)
+    {
+        check(Err(anyhow!("wakeword_reload_secs must be positive")));
+    }
+    if settings.low_power_hold_secs < 0.0 {
+        check(Err(anyhow!("low_power_hold_secs must not be negative")));
+    }
+
+    if problems.is_empty() {
+        println!("configuration OK");
+        return Ok(());
+    }
+    for problem in &problems {
+        println!("error: {}", problem);
+    }
+    Err(anyhow!("{} configuration problem(s)", problems.len()))
+}
+
+/// 多声道输入经阵列前端处理，麦克风坐标须与声道数一致
+fn check_array(settings: &Settings, channels: u16, sample_rate: u32) -> Result<(), anyhow::Error> {
+    if channels > 1 {
+        ArrayFrontEnd::new(settings.array_config(sample_rate), channels as usize)?;
+    }
+    Ok(())
+}
+
+/// 命令行给出的音频文件，未给出时使用 --audio 文件
+fn audio_files(settings: &Settings, files: &[PathBuf]) -> Result<Vec<PathBuf>, anyhow::Error> {
+    if !files.is_empty() {
+        return Ok(files.to_vec());
+    }
+    settings
+        .audio_file
+        .as_ref()
+        .map(|file| vec![PathBuf::from(file)])
+        .ok_or_else(|| anyhow!("no audio files given (pass WAV files or --audio <FILE>)"))
+}
+
+/// 读取 WAV 的第一个声道并重采样到 sample_rate（检测器与声纹构建时的采样率）
+fn read_mono(path: &Path, sample_rate: u32) -> Result<Vec<f32>, anyhow::Error> {
+    let wav = read_wav(path)?;
+    Ok(resample(&wav.channel(0), wav.sample_rate, sample_rate))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+    use clap::CommandFactory;
+    use voice::audio::wav::{write_wav, WavData};
+
+    #[test]
+    fn command_line_is_valid() {
+        Cli::command().debug_assert();
+        let cli =
+            Cli::try_parse_from(["core", "--audio", "no-such-device", "eval", "a.wav"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::Eval { ref files }) if files.len() == 1));
+        let settings = cli.settings().unwrap();
+        assert_eq!(settings.audio_device.as_deref(), Some("no-such-device"));
+        assert!(audio_files(&settings, &[]).is_err());
+    }
+
+    #[test]
+    fn array_must_match_channels() {
+        let settings = Settings {
+            mic_positions: vec![0.1, -0.1],
+            ..Settings::default()
+        };
+        assert!(check_array(&settings, 1, CAPTURE_RATE).is_ok());
+        assert!(check_array(&settings, 2, CAPTURE_RATE).is_ok());
+        let error = check_array(&settings, 4, CAPTURE_RATE).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "mic_positions has 2 entries, audio input has 4 channels"
+        );
+    }
+
+    #[test]
+    fn files_are_resampled_to_offline_rate() {
+        let path = testing::temp_dir("cli-read-mono").join("stereo.wav");
+        write_wav(
+            &path,
+            &WavData {
+                sample_rate: 16000,
+                channels: 2,
+                samples: [0.5, -0.5].repeat(48000),
+            },
+        )
+        .unwrap();
+
+        let audio = read_mono(&path, OFFLINE_RATE).unwrap();
+        assert_eq!(audio.len(), OFFLINE_RATE as usize * 3);
+        assert!(audio.iter().all(|&s| s > 0.0));
+        assert_eq!(read_mono(&path, 8000).unwrap().len(), 24000);
+
+        // 检测器按同一采样率构建，缓存满 2 秒后开始打分
+        eval(&testing::settings(), &[path]).unwrap();
+    }
+}
@@ -0,0 +1,906 @@
+/*
+    对话状态机
+    Idle → Listening → Recognizing → Executing → Responding → (Listening 多轮追问 | Idle)
+    各阶段均有超时，任意阶段可取消回到 Idle，每次状态切换都推送给 GUI
+*/
+
+use crate::api::{AssistantEvent, EventHub};
+use crate::intent::{IntentParser, VehicleCommand};
+use crate::metrics::{self, metrics};
+use crate::profile::ProfileStore;
+use crate::skill::{AudioSink, SkillContext, SkillRegistry};
+use crate::timer::{self, Scheduler, Timer};
+use crate::tts::{self, SpeechQueue};
+use crate::vehicle::codec::Codec;
+use crate::vehicle::mock::MockBus;
+use crate::vehicle::VehicleController;
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use gui::status::WakeStatus;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::thread;
+use std::time::{Duration, Instant};
+use tracing::{debug, error, field, info, info_span, warn, Span};
+use voice::array::Seat;
+use voice::asr::{AsrBackend, AsrConfig, Transcript};
+use voice::config::Settings;
+use voice::event::wake_event::WakeInfo;
+use voice::privacy::PrivacySwitch;
+use voice::speaker::policy::SpeakerPolicy;
+use voice::speaker::Verification;
+use voice::vad::{Endpoint, Endpointer, EnergyVad};
+
+/// 取消当前对话的口令
+const CANCEL_WORDS: [&str; 6] = [
+    "cancel",
+    "never mind",
+    "stop listening",
+    "取消",
+    "算了",
+    "不用了",
+];
+
+/// 对话状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum DialogState {
+    Idle,
+    Listening { follow_up: bool },
+    Recognizing,
+    Executing,
+    Responding,
+}
+
+/// 对话参数
+#[derive(Debug, Clone)]
+pub struct DialogConfig {
+    pub listen_timeout: Duration,    // 唤醒后等待开口的最长时间
+    pub follow_up_timeout: Duration, // 多轮追问时等待开口的最长时间
+    pub trailing_silence: Duration,  // 判定说完所需的尾部静音
+    pub max_utterance: Duration,     // 单条语音最大时长
+    pub recognize_timeout: Duration, // 语音识别最长耗时
+    pub response_time: Duration,     // 回复展示时长
+    pub max_follow_ups: usize,       // 单次唤醒最多追问轮数
+    pub vad_threshold: f32,          // 语音能量阈值（均方根）
+}
+
+impl Default for DialogConfig {
+    fn default() -> Self {
+        Self {
+            listen_timeout: Duration::from_secs(5),
+            follow_up_timeout: Duration::from_secs(3),
+            trailing_silence: Duration::from_millis(800),
+            max_utterance: Duration::from_secs(10),
+            recognize_timeout: Duration::from_secs(8),
+            response_time: Duration::from_secs(3),
+            max_follow_ups: 2,
+            vad_threshold: 0.01,
+        }
+    }
+}
+
+/// 待执行的指令（携带说话人身份与座位）
+#[derive(Debug, Clone)]
+pub struct CommandRequest {
+    pub intent: String,
+    pub slots: HashMap<String, String>,
+    pub command: Option<VehicleCommand>,
+    pub speaker: Option<Verification>,
+    pub seat: Option<Seat>,
+    #[allow(dead_code)] // 识别的原文，供自行解析文本的技能使用
+    pub transcript: String,
+}
+
+/// 后台识别线程返回的结果
+pub struct Recognition {
+    pub session: u64,
+    pub backend: Box<dyn AsrBackend>,
+    pub transcripts: Result<Vec<Transcript>, anyhow::Error>,
+}
+
+pub struct Dialog {
+    state: DialogState,
+    entered: Instant,
+    config: DialogConfig,
+    sample_rate: u32,
+    endpointer: Endpointer,
+    utterance: Vec<f32>,
+    wake: WakeInfo,
+    asr: Option<Box<dyn AsrBackend>>,
+    session: u64, // 每次识别递增，用于丢弃已取消会话的结果
+    results_tx: Sender<Recognition>,
+    results_rx: Receiver<Recognition>,
+    parser: IntentParser,
+    policy: SpeakerPolicy,
+    skills: SkillRegistry,
+    vehicle: VehicleController,
+    pub profiles: ProfileStore,
+    pub timers: Scheduler,
+    privacy: PrivacySwitch,
+    speech: SpeechQueue, // 语音播报与提示音
+    settings: Settings,
+    gui_sender: Sender<WakeStatus>,
+    hub: EventHub,
+    follow_ups: usize,
+    last_succeeded: bool,
+    intent: String,   // 当前执行的意图名
+    response: String, // 当前回复内容
+    span: Span,       // 当前对话周期（唤醒 → 采集 → 识别 → 意图 → 执行）
+    cycle_started: Instant,
+    speech_ended: Option<Instant>, // 语音结束（或收到文本指令）的时间，用于统计回复延迟
+}
+
+/// 技能操作的车辆、用户档案、定时器与隐私开关
+pub struct Services {
+    pub vehicle: VehicleController,
+    pub profiles: ProfileStore,
+    pub timers: Scheduler,
+    pub privacy: PrivacySwitch,
+}
+
+impl Services {
+    /// 模拟总线与内存中的档案、定时器（回放与测试）
+    pub fn in_memory(now: Instant) -> Self {
+        Self {
+            vehicle: VehicleController::new(Box::new(MockBus::new()), Codec::default()),
+            profiles: ProfileStore::in_memory(),
+            timers: Scheduler::in_memory(now),
+            privacy: PrivacySwitch::default(),
+        }
+    }
+}
+
+impl Dialog {
+    pub fn new(
+        gui_sender: Sender<WakeStatus>,
+        hub: EventHub,
+        services: Services,
+        sample_rate: u32,
+        now: Instant,
+    ) -> Self {
+        let Services {
+            vehicle,
+            profiles,
+            timers,
+            privacy,
+        } = services;
+        let config = DialogConfig::default();
+        let settings = Settings::load();
+        let asr = AsrConfig::load().and_then(|config| match config.build() {
+            Ok(backend) => Some(backend),
+            Err(e) => {
+                error!("failed to create recognizer: {:?}", e);
+                None
+            }
+        });
+        let (results_tx, results_rx) = unbounded();
+
+        // 内置语法 + 技能自带语法
+        let mut parser = IntentParser::default();
+        if let Some(dir) = &settings.grammar_dir {
+            match IntentParser::from_dir(dir) {
+                Ok(extra) => parser.merge(extra),
+                Err(e) => error!("failed to load grammars: {:?}", e),
+            }
+        }
+
+        Self {
+            state: DialogState::Idle,
+            entered: now,
+            endpointer: new_endpointer(&config, sample_rate, config.listen_timeout),
+            config,
+            sample_rate,
+            utterance: Vec::new(),
+            wake: WakeInfo::default(),
+            asr,
+            session: 0,
+            results_tx,
+            results_rx,
+            parser,
+            // 车窗等敏感指令仅限已验证的说话人
+            policy: settings
+                .restricted_intents
+                .iter()
+                .fold(SpeakerPolicy::new(), |policy, intent| {
+                    policy.restrict(intent.as_str())
+                }),
+            skills: SkillRegistry::from_names(&settings.skills),
+            vehicle,
+            profiles,
+            timers,
+            privacy,
+            speech: SpeechQueue::from_settings(&settings),
+            settings,
+            gui_sender,
+            hub,
+            follow_ups: 0,
+            last_succeeded: false,
+            intent: String::new(),
+            response: String::new(),
+            span: Span::none(),
+            cycle_started: now,
+            speech_ended: None,
+        }
+    }
+
+    pub fn state(&self) -> DialogState {
+        self.state
+    }
+
+    pub fn vehicle(&self) -> &VehicleController {
+        &self.vehicle
+    }
+
+    /// 是否配置了识别后端
+    pub fn has_recognizer(&self) -> bool {
+        self.asr.is_some()
+    }
+
+    /// 替换播报输出（回放时不播报）
+    pub fn set_speech(&mut self, speech: SpeechQueue) {
+        self.speech = speech;
+    }
+
+    /// 替换识别后端（回放时使用不产生结果的后端，结果由日志提供）
+    pub fn set_recognizer(&mut self, backend: Option<Box<dyn AsrBackend>>) {
+        self.asr = backend;
+    }
+
+    /// 识别结果通道（供事件循环监听）
+    pub fn results(&self) -> Receiver<Recognition> {
+        self.results_rx.clone()
+    }
+
+    /// 唤醒：空闲或回复中（打断）时开始新一轮对话
+    pub fn on_wake(&mut self, info: WakeInfo, now: Instant) {
+        match self.state {
+            DialogState::Idle | DialogState::Responding => {
+                self.wake = info;
+                self.follow_ups = 0;
+                self.listen(false, now);
+            }
+            _ => debug!(state = ?self.state, "Wake ignored"),
+        }
+    }
+
+    /// 输入音频帧：采集语音并检测端点
+    pub fn on_audio(&mut self, frame: &[f32], now: Instant) {
+        if !matches!(self.state, DialogState::Listening { .. }) {
+            return;
+        }
+        let _span = self.span.clone().entered();
+        self.utterance.extend_from_slice(frame);
+
+        match self.endpointer.push(frame) {
+            Endpoint::Ended => self.recognize(now),
+            Endpoint::NoSpeech => {
+                info!("No speech detected, back to idle");
+                self.transition(DialogState::Idle, now);
+            }
+            Endpoint::Waiting | Endpoint::Speaking => {}
+        }
+    }
+
+    /// 后台识别完成
+    pub fn on_recognition(&mut self, result: Recognition, now: Instant) {
+        let Recognition {
+            session,
+            backend,
+            transcripts,
+        } = result;
+        self.asr = Some(backend);
+        let _span = self.span.clone().entered();
+
+        if session != self.session || self.state != DialogState::Recognizing {
+            // 已取消或超时的会话
+            return;
+        }
+
+        match transcripts {
+            Ok(transcripts) => {
+                // 优先取最终结果，没有则取最后一条中间结果
+                let text = transcripts
+                    .iter()
+                    .rev()
+                    .find(|t| t.is_final)
+                    .or(transcripts.last())
+                    .map(|t| t.text.clone())
+                    .unwrap_or_default();
+                info!(transcript = %text, "Transcript");
+                self.execute(&text, now);
+            }
+            Err(e) => {
+                warn!("recognition failed: {:?}", e);
+                self.respond("Sorry, I could not hear that", false, now);
+            }
+        }
+    }
+
+    /// 超时检查
+    pub fn tick(&mut self, now: Instant) {
+        let _span = self.span.clone().entered();
+        if let Err(e) = self.vehicle.poll() {
+            warn!("failed to read vehicle bus: {:?}", e);
+        }
+
+        let elapsed = now.saturating_duration_since(self.entered);
+        match self.state {
+            DialogState::Listening { follow_up } => {
+                // 音频流中断时的兜底超时
+                let limit = if follow_up {
+                    self.config.follow_up_timeout
+                } else {
+                    self.config.listen_timeout
+                } + self.config.max_utterance;
+                if elapsed > limit {
+                    self.transition(DialogState::Idle, now);
+                }
+            }
+            DialogState::Recognizing if elapsed > self.config.recognize_timeout => {
+                warn!("Recognition timed out");
+                self.session += 1;
+                self.respond("Sorry, that took too long", false, now);
+            }
+            DialogState::Responding if elapsed > self.config.response_time => {
+                // 上一轮成功时继续追问，否则结束对话（隐私模式下听不到追问）
+                if self.last_succeeded
+                    && self.follow_ups < self.config.max_follow_ups
+                    && !self.privacy.is_muted()
+                {
+                    self.follow_ups += 1;
+                    self.listen(true, now);
+                } else {
+                    self.transition(DialogState::Idle, now);
+                }
+            }
+            DialogState::Idle => {
+                // 空闲时播报到期的提醒，对话中到期的等结束后再播报
+                let due = self.timers.take_due(now);
+                if !due.is_empty() {
+                    self.remind(&due, now);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// 提示音 + 提醒文本
+    fn remind(&mut self, due: &[Timer], now: Instant) {
+        info!(count = due.len(), "Timers due");
+        if let Err(e) = self
+            .speech
+            .play(&timer::chime(self.sample_rate), self.sample_rate)
+        {
+            warn!("failed to play chime: {:?}", e);
+        }
+        let text: Vec<String> = due.iter().map(Timer::announcement).collect();
+        let text = text.join(". ");
+        // 提醒为关键提示，合成引擎不可用时也要播报
+        self.speech.speak(&text, true);
+        self.show(&text, false, now);
+    }
+
+    /// 取消当前对话
+    pub fn cancel(&mut self, now: Instant) {
+        if self.state != DialogState::Idle {
+            let _span = self.span.clone().entered();
+            info!(state = ?self.state, "Dialog cancelled");
+            self.speech.clear();
+            self.session += 1;
+            self.utterance.clear();
+            self.transition(DialogState::Idle, now);
+        }
+    }
+
+    /// 直接执行文本指令（外部注入，跳过语音采集与识别）
+    pub fn submit_text(&mut self, text: &str, now: Instant) -> Result<(), anyhow::Error> {
+        if self.state == DialogState::Recognizing {
+            return Err(anyhow::anyhow!("busy recognizing"));
+        }
+        self.session += 1;
+        self.wake = WakeInfo::default();
+        self.follow_ups = 0;
+        self.begin_cycle("text", now);
+        self.speech_ended = Some(now);
+        let _span = self.span.clone().entered();
+        self.execute(text, now);
+        Ok(())
+    }
+
+    /// 播报外部进程提供的文本，不进入多轮追问
+    pub fn speak(&mut self, text: &str, now: Instant) -> Result<(), anyhow::Error> {
+        match self.state {
+            DialogState::Idle | DialogState::Responding => {
+                self.respond(text, false, now);
+                Ok(())
+            }
+            state => Err(anyhow::anyhow!("busy in state {:?}", state)),
+        }
+    }
+
+    fn listen(&mut self, follow_up: bool, now: Instant) {
+        let timeout = if follow_up {
+            self.config.follow_up_timeout
+        } else {
+            self.config.listen_timeout
+        };
+        self.endpointer = new_endpointer(&self.config, self.sample_rate, timeout);
+        self.utterance.clear();
+        self.begin_cycle(if follow_up { "follow_up" } else { "wake" }, now);
+        self.transition(DialogState::Listening { follow_up }, now);
+    }
+
+    /// 将采集到的语音交给后台线程识别
+    fn recognize(&mut self, now: Instant) {
+        let Some(mut backend) = self.asr.take() else {
+            self.respond("Speech recognition is not configured", false, now);
+            return;
+        };
+
+        self.session += 1;
+        self.speech_ended = Some(now);
+        self.transition(DialogState::Recognizing, now);
+
+        let session = self.session;
+        let sample_rate = self.sample_rate;
+        let utterance = std::mem::take(&mut self.utterance);
+        let results = self.results_tx.clone();
+        thread::spawn(move || {
+            let transcripts = backend.transcribe(&utterance, sample_rate);
+            let _ = results.send(Recognition {
+                session,
+                backend,
+                transcripts,
+            });
+        });
+    }
+
+    /// 解析意图、校验授权并执行
+    fn execute(&mut self, transcript: &str, now: Instant) {
+        if is_cancel(transcript) {
+            self.cancel(now);
+            return;
+        }
+
+        let Some(intent) = self.parser.parse(transcript) else {
+            metrics::record_intent("unknown", false);
+            self.respond("Sorry, I didn't understand", false, now);
+            return;
+        };
+        info!(
+            intent = %intent.intent,
+            confidence = intent.confidence,
+            remainder = %intent.remainder,
+            "Intent"
+        );
+        self.span.record("transcript", transcript);
+        self.span.record("intent", intent.intent.as_str());
+        self.span.record("confidence", intent.confidence);
+        self.hub.publish(AssistantEvent::Intent {
+            intent: intent.intent.clone(),
+            slots: intent.slots.clone(),
+            confidence: intent.confidence,
+            transcript: transcript.to_string(),
+        });
+        self.intent = intent.intent.clone();
+        self.transition(DialogState::Executing, now);
+
+        let request = CommandRequest {
+            intent: intent.intent,
+            slots: intent.slots,
+            command: intent.command,
+            speaker: self.wake.speaker.clone(),
+            seat: self.wake.seat,
+            transcript: transcript.to_string(),
+        };
+        if !self
+            .policy
+            .authorize(&request.intent, request.speaker.as_ref())
+        {
+            metrics::record_intent(&request.intent, false);
+            self.respond("Only a verified driver can do that", false, now);
+            return;
+        }
+
+        let (response, succeeded) = match self.perform(&request, now) {
+            Some(Ok(response)) => (response, true),
+            Some(Err(e)) => {
+                error!("failed to execute {}: {:?}", request.intent, e);
+                ("Sorry, something went wrong".to_string(), false)
+            }
+            None => ("Sorry, I can't do that yet".to_string(), false),
+        };
+        metrics::record_intent(&request.intent, succeeded);
+        self.respond(&response, succeeded, now);
+    }
+
+    /// 交给注册的技能执行
+    fn perform(
+        &mut self,
+        request: &CommandRequest,
+        now: Instant,
+    ) -> Option<Result<String, anyhow::Error>> {
+        debug!(?request, "Executing");
+        let profile = self.active_profile();
+        let mut context = SkillContext {
+            vehicle: &mut self.vehicle,
+            audio: &mut self.speech,
+            settings: &self.settings,
+            profiles: &mut self.profiles,
+            profile: profile.as_deref(),
+            timers: &mut self.timers,
+            privacy: &self.privacy,
+            now,
+        };
+        self.skills.dispatch(request, &mut context)
+    }
+
+    /// 当前档案：已验证的说话人优先，否则取手动选择的档案
+    pub fn active_profile(&self) -> Option<String> {
+        match &self.wake.speaker {
+            Some(verification) => Some(verification.speaker.to_string()),
+            None => self.profiles.selected().map(|profile| profile.id.clone()),
+        }
+    }
+
+    /// 播报并展示回复
+    fn respond(&mut self, text: &str, succeeded: bool, now: Instant) {
+        self.speech.speak(text, false);
+        self.show(text, succeeded, now);
+    }
+
+    /// 展示回复（去掉停顿等标记）并推送给订阅者
+    fn show(&mut self, text: &str, succeeded: bool, now: Instant) {
+        let text = &tts::plain_text(text);
+        info!(text, succeeded, "Response");
+        self.span.record("succeeded", succeeded);
+        self.last_succeeded = succeeded;
+        self.response = text.to_string();
+        if let Some(ended) = self.speech_ended.take() {
+            metrics()
+                .latency
+                .observe(now.saturating_duration_since(ended).as_secs_f64());
+        }
+        self.hub.publish(AssistantEvent::Response {
+            text: text.to_string(),
+            succeeded,
+        });
+        self.transition(DialogState::Responding, now);
+    }
+
+    /// 切换状态并推送到 GUI 与订阅者
+    fn transition(&mut self, state: DialogState, now: Instant) {
+        info!(from = ?self.state, to = ?state, "Dialog transition");
+        // 记录各阶段耗时
+        let elapsed = millis(now.saturating_duration_since(self.entered));
+        match self.state {
+            DialogState::Listening { .. } if state == DialogState::Recognizing => {
+                self.span.record("capture_ms", elapsed);
+            }
+            DialogState::Recognizing => {
+                self.span.record("recognize_ms", elapsed);
+            }
+            DialogState::Executing => {
+                self.span.record("execute_ms", elapsed);
+            }
+            _ => {}
+        }
+        self.state = state;
+        self.entered = now;
+        self.hub.publish(AssistantEvent::Dialog(state));
+
+        let status = match state {
+            DialogState::Idle => WakeStatus::Idle,
+            DialogState::Listening { follow_up } => WakeStatus::Listening { follow_up },
+            DialogState::Recognizing => WakeStatus::Recognizing,
+            DialogState::Executing => WakeStatus::Executing {
+                intent: self.intent.clone(),
+            },
+            DialogState::Responding => WakeStatus::Responding {
+                text: self.response.clone(),
+            },
+        };
+        let _ = self.gui_sender.send(status);
+
+        if state == DialogState::Idle {
+            self.end_cycle(now);
+        }
+    }
+
+    /// 开始新的对话周期（结束上一个），span 关闭时输出各阶段耗时
+    fn begin_cycle(&mut self, source: &'static str, now: Instant) {
+        self.end_cycle(now);
+        // 每个周期为独立的根 span，不挂在上一个周期下
+        self.span = info_span!(
+            parent: None,
+            "utterance",
+            source,
+            speaker = ?self.wake.speaker.as_ref().map(|v| &v.speaker.0),
+            seat = ?self.wake.seat,
+            transcript = field::Empty,
+            intent = field::Empty,
+            confidence = field::Empty,
+            succeeded = field::Empty,
+            capture_ms = field::Empty,
+            recognize_ms = field::Empty,
+            execute_ms = field::Empty,
+            total_ms = field::Empty,
+        );
+        self.cycle_started = now;
+    }
+
+    fn end_cycle(&mut self, now: Instant) {
+        let total = millis(now.saturating_duration_since(self.cycle_started));
+        self.span.record("total_ms", total);
+        self.span = Span::none();
+        self.speech_ended = None;
+    }
+}
+
+/// 整句（去掉首尾空白与标点）是取消口令时才取消，"取消会议提醒"等包含口令的指令照常执行
+fn is_cancel(transcript: &str) -> bool {
+    let text = transcript
+        .trim_matches(|c: char| {
+            c.is_whitespace() || c.is_ascii_punctuation() || "，。！？、".contains(c)
+        })
+        .to_lowercase();
+    CANCEL_WORDS.contains(&text.as_str())
+}
+
+fn millis(duration: Duration) -> u64 {
+    duration.as_millis() as u64
+}
+
+fn new_endpointer(config: &DialogConfig, sample_rate: u32, timeout: Duration) -> Endpointer {
+    Endpointer::new(
+        EnergyVad::new(config.vad_threshold),
+        sample_rate,
+        timeout.as_millis() as u32,
+        config.trailing_silence.as_millis() as u32,
+        config.max_utterance.as_millis() as u32,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{self, SAMPLE_RATE};
+    use voice::asr::Transcript;
+
+    /// 测试用识别后端：每条语音都识别为同一句话
+    struct FixedRecognizer(&'static str);
+
+    impl AsrBackend for FixedRecognizer {
+        fn start(&mut self, _sample_rate: u32) -> Result<(), anyhow::Error> {
+            Ok(())
+        }
+
+        fn feed(&mut self, _pcm: &[f32]) -> Result<Vec<Transcript>, anyhow::Error> {
+            Ok(Vec::new())
+        }
+
+        fn finish(&mut self) -> Result<Vec<Transcript>, anyhow::Error> {
+            Ok(vec![Transcript {
+                text: self.0.to_string(),
+                is_final: true,
+                ..Default::default()
+            }])
+        }
+    }
+
+    /// 测试用对话：模拟总线，内存中的档案与定时器，不播报
+    pub(crate) fn dialog(now: Instant) -> (Dialog, Receiver<WakeStatus>) {
+        testing::settings();
+        let (gui_sender, statuses) = unbounded();
+        let mut dialog = Dialog::new(
+            gui_sender,
+            EventHub::default(),
+            Services::in_memory(now),
+            SAMPLE_RATE,
+            now,
+        );
+        dialog.set_speech(SpeechQueue::disabled());
+        (dialog, statuses)
+    }
+
+    /// 送入 0.3 秒语音与随后的静音，直到进入识别，返回此时的时间
+    fn utter(dialog: &mut Dialog, mut now: Instant) -> Instant {
+        let frame = SAMPLE_RATE as usize / 100;
+        for i in 0..500 {
+            let level = if i < 30 { 0.5 } else { 0.0 };
+            now += Duration::from_millis(10);
+            dialog.on_audio(&vec![level; frame], now);
+            if !matches!(dialog.state(), DialogState::Listening { .. }) {
+                break;
+            }
+        }
+        assert_eq!(dialog.state(), DialogState::Recognizing);
+        now
+    }
+
+    #[test]
+    fn cancel_words() {
+        for text in [
+            "cancel",
+            " Cancel. ",
+            "never mind",
+            "取消",
+            "算了。",
+            "不用了！",
+        ] {
+            assert!(is_cancel(text), "{:?}", text);
+        }
+        for text in [
+            "remind me to cancel the meeting",
+            "取消会议提醒",
+            "cancelled",
+            "",
+        ] {
+            assert!(!is_cancel(text), "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn cancel_only_on_whole_utterance() {
+        let now = Instant::now();
+        let (mut dialog, statuses) = dialog(now);
+
+        dialog.submit_text("取消会议提醒", now).unwrap();
+        assert!(statuses
+            .try_iter()
+            .any(|s| matches!(s, WakeStatus::Responding { .. })));
+
+        dialog.submit_text("算了", now).unwrap();
+        assert_eq!(dialog.state(), DialogState::Idle);
+        assert_eq!(statuses.try_iter().last(), Some(WakeStatus::Idle));
+    }
+
+    #[test]
+    fn restricted_intents_need_a_verified_speaker() {
+        let now = Instant::now();
+        let (mut dialog, statuses) = dialog(now);
+        assert!(testing::settings()
+            .restricted_intents
+            .contains(&"window".to_string()));
+
+        dialog.submit_text("打开左前车窗", now).unwrap();
+        assert!(statuses.try_iter().any(|s| s
+            == WakeStatus::Responding {
+                text: "Only a verified driver can do that".into()
+            }));
+        assert_eq!(dialog.vehicle().state().windows, [0; 4]);
+
+        // 未限制的意图不受影响
+        dialog.submit_text("空调二十二度", now).unwrap();
+        assert!(statuses.try_iter().any(|s| s
+            == WakeStatus::Responding {
+                text: "Setting cabin temperature to 22 degrees".into()
+            }));
+    }
+
+    #[test]
+    fn listening_times_out() {
+        let now = Instant::now();
+        let (mut dialog, statuses) = dialog(now);
+        let config = DialogConfig::default();
+
+        // 没有音频时由 tick 兜底：等待开口的时间加最大语音时长
+        dialog.on_wake(WakeInfo::default(), now);
+        let limit = now + config.listen_timeout + config.max_utterance;
+        dialog.tick(limit);
+        assert_eq!(dialog.state(), DialogState::Listening { follow_up: false });
+        dialog.tick(limit + Duration::from_millis(1));
+        assert_eq!(dialog.state(), DialogState::Idle);
+        assert_eq!(statuses.try_iter().last(), Some(WakeStatus::Idle));
+
+        // 追问等待时间更短
+        dialog.submit_text("空调二十二度", now).unwrap();
+        let listening = now + config.response_time + Duration::from_millis(1);
+        dialog.tick(listening);
+        assert_eq!(dialog.state(), DialogState::Listening { follow_up: true });
+        let limit = listening + config.follow_up_timeout + config.max_utterance;
+        dialog.tick(limit);
+        assert_eq!(dialog.state(), DialogState::Listening { follow_up: true });
+        dialog.tick(limit + Duration::from_millis(1));
+        assert_eq!(dialog.state(), DialogState::Idle);
+    }
+
+    #[test]
+    fn recognition_times_out() {
+        let now = Instant::now();
+        let (mut dialog, statuses) = dialog(now);
+        let config = DialogConfig::default();
+        dialog.set_recognizer(Some(Box::new(FixedRecognizer("空调二十二度"))));
+        let results = dialog.results();
+
+        dialog.on_wake(WakeInfo::default(), now);
+        let recognizing = utter(&mut dialog, now);
+        dialog.tick(recognizing + config.recognize_timeout);
+        assert_eq!(dialog.state(), DialogState::Recognizing);
+        let timed_out = recognizing + config.recognize_timeout + Duration::from_millis(1);
+        dialog.tick(timed_out);
+        assert_eq!(dialog.state(), DialogState::Responding);
+        assert_eq!(
+            statuses.try_iter().last(),
+            Some(WakeStatus::Responding {
+                text: "Sorry, that took too long".into()
+            })
+        );
+
+        // 超时后才到达的结果被丢弃，失败的一轮不追问
+        let late = results.recv_timeout(Duration::from_secs(5)).unwrap();
+        dialog.on_recognition(late, timed_out);
+        assert_eq!(dialog.state(), DialogState::Responding);
+        assert!(statuses.try_iter().next().is_none());
+        assert!(dialog.has_recognizer());
+        dialog.tick(timed_out + config.response_time + Duration::from_millis(1));
+        assert_eq!(dialog.state(), DialogState::Idle);
+    }
+
+    #[test]
+    fn follows_up_after_success() {
+        let now = Instant::now();
+        let (mut dialog, statuses) = dialog(now);
+        let config = DialogConfig::default();
+        dialog.set_recognizer(Some(Box::new(FixedRecognizer("空调二十二度"))));
+        let results = dialog.results();
+
+        dialog.on_wake(WakeInfo::default(), now);
+        let mut now = now;
+        let mut follow_ups = 0;
+        loop {
+            now = utter(&mut dialog, now);
+            let result = results.recv_timeout(Duration::from_secs(5)).unwrap();
+            dialog.on_recognition(result, now);
+            assert_eq!(
+                statuses.try_iter().last(),
+                Some(WakeStatus::Responding {
+                    text: "Setting cabin temperature to 22 degrees".into()
+                })
+            );
+
+            now += config.response_time + Duration::from_millis(1);
+            dialog.tick(now);
+            if follow_ups == config.max_follow_ups {
+                break;
+            }
+            follow_ups += 1;
+            assert_eq!(dialog.state(), DialogState::Listening { follow_up: true });
+        }
+        // 追问轮数用完后结束对话
+        assert_eq!(dialog.state(), DialogState::Idle);
+
+        // 新的唤醒重新计数
+        dialog.on_wake(WakeInfo::default(), now);
+        assert_eq!(dialog.state(), DialogState::Listening { follow_up: false });
+    }
+
+    #[test]
+    fn cancel_mid_dialog() {
+        let now = Instant::now();
+        let (mut dialog, statuses) = dialog(now);
+        dialog.set_recognizer(Some(Box::new(FixedRecognizer("空调二十二度"))));
+        let results = dialog.results();
+
+        dialog.on_wake(WakeInfo::default(), now);
+        dialog.cancel(now);
+        assert_eq!(dialog.state(), DialogState::Idle);
+
+        // 识别中取消：之后到达的结果不再执行
+        dialog.on_wake(WakeInfo::default(), now);
+        let recognizing = utter(&mut dialog, now);
+        dialog.cancel(recognizing);
+        assert_eq!(dialog.state(), DialogState::Idle);
+        assert_eq!(statuses.try_iter().last(), Some(WakeStatus::Idle));
+        let late = results.recv_timeout(Duration::from_secs(5)).unwrap();
+        dialog.on_recognition(late, recognizing);
+        assert_eq!(dialog.state(), DialogState::Idle);
+        assert!(statuses.try_iter().next().is_none());
+
+        // 回复中取消后不追问
+        dialog.submit_text("空调二十二度", now).unwrap();
+        dialog.cancel(now);
+        dialog.tick(now + DialogConfig::default().response_time * 2);
+        assert_eq!(dialog.state(), DialogState::Idle);
+    }
+}
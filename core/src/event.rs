@@ -1,33 +1,554 @@
-use crossbeam_channel::{Receiver, Sender};
+use crate::api::{AssistantEvent, Control, ControlRequest, EventHub};
+use crate::dialog::{Dialog, DialogState, Services};
+use crate::profile::{ProfileStore, VoicePrint};
+use crate::replay::log::EventLog;
+use crate::timer::Scheduler;
+use crate::vehicle::obd;
+use crate::vehicle::VehicleController;
+use anyhow::Context;
+use crossbeam_channel::{select, unbounded, Receiver, Sender};
 use gui::status::WakeStatus;
-use voice::event::wake_event::WakeEvent;
+use serde_json::{json, Value};
+use std::time::{Duration, Instant};
+use tracing::{error, info};
+use voice::array::DirectionOfArrival;
+use voice::config::Settings;
+use voice::event::wake_event::{WakeEvent, WakeInfo};
+use voice::power::{DutyCycle, PowerMode};
+use voice::privacy::PrivacySwitch;
+use voice::recorder::{CaptureScores, Recorder, RecorderConfig, Trigger};
+use voice::sound::classifier::SoundClassifier;
+use voice::speaker::verifier::SpeakerVerifier;
+use voice::speaker::SpeakerId;
 use voice::wakeword::detector::WakeDetector;
 
-pub async fn event_loop(mut rx: Receiver<WakeEvent>, gui_sender: Sender<WakeStatus>) {
-    let mut detector = WakeDetector::new();
+/// 对话超时检查周期
+pub const TICK: Duration = Duration::from_millis(100);
+
+/// 按配置创建事件处理器（唤醒模型无效等启动错误在此返回，而不是在事件循环中）
+/// GUI 状态经返回的通道由事件循环转发，以便写入事件日志
+pub fn open_handler(
+    hub: EventHub,
+    privacy: PrivacySwitch,
+    sample_rate: u32,
+) -> Result<(EventHandler, Receiver<WakeStatus>), anyhow::Error> {
+    let settings = Settings::load();
+    let (status_sender, statuses) = unbounded();
+    let services = Services {
+        vehicle: open_vehicle(&settings)?,
+        profiles: open_profiles(&settings),
+        timers: open_timers(&settings),
+        privacy,
+    };
+    let handler = EventHandler::new(
+        status_sender,
+        hub,
+        services,
+        open_recorder(sample_rate),
+        sample_rate,
+    )?;
+    Ok((handler, statuses))
+}
+
+pub fn event_loop(
+    mut handler: EventHandler,
+    statuses: Receiver<WakeStatus>,
+    rx: Receiver<WakeEvent>,
+    gui_sender: Sender<WakeStatus>,
+    controls: Receiver<Control>,
+    sample_rate: u32,
+) {
+    let settings = Settings::load();
+    let recognitions = handler.dialog.results();
+
+    // 事件日志（可选，用于回放复现）
+    let mut log = settings.event_log_dir.as_ref().and_then(|dir| {
+        match EventLog::create(dir, sample_rate, handler.dialog.has_recognizer()) {
+            Ok(log) => {
+                info!("Recording events to {}", log.directory().display());
+                Some(log)
+            }
+            Err(e) => {
+                error!("failed to create event log: {:?}", e);
+                None
+            }
+        }
+    });
+
     loop {
         // 同步阻塞接受（非异步）
-        match rx.recv() {
-            Ok(event) => {
-                match event {
-                    WakeEvent::AudioFrame(data) => {
-                        // 处理音频帧
-                        if detector.process(&data) {
-                            println!("Received audio frame");
-                            // 触发控制指令（车控、UI）
-                        }
+        select! {
+            recv(rx) -> event => match event {
+                // 开启隐私模式前已进入通道的音频也不再记录和处理
+                Ok(event) if !handler.admits(&event) => {}
+                Ok(event) => {
+                    let now = Instant::now();
+                    if let Some(log) = log.as_mut() {
+                        log.wake_event(now, &event);
+                    }
+                    handler.handle(event, now);
+                }
+                Err(crossbeam_channel::RecvError) => {
+                    // 通道关闭时退出循环
+                    info!("Audio channel closed, existing event loop");
+                    break;
+                }
+            },
+            recv(recognitions) -> result => {
+                if let Ok(result) = result {
+                    let now = Instant::now();
+                    if let Some(log) = log.as_mut() {
+                        log.recognition(now, &result);
                     }
-                    WakeEvent::WakeDetected => {
-                        // 处于已唤醒状态
-                        println!("Wake word detected!");
+                    handler.dialog.on_recognition(result, now);
+                }
+            },
+            recv(controls) -> control => {
+                if let Ok(control) = control {
+                    let now = Instant::now();
+                    if let Some(log) = log.as_mut() {
+                        log.control(now, &control.request);
                     }
+                    let reply = handler.control(control.request, now);
+                    let _ = control.reply.send(reply);
                 }
+            },
+            default(TICK) => {}
+        }
+        let now = Instant::now();
+        handler.tick(now);
+        forward(&statuses, &gui_sender, log.as_mut(), now);
+    }
+    // 退出前结束进行中的对话
+    let now = Instant::now();
+    if let Some(log) = log.as_mut() {
+        log.shutdown(now);
+    }
+    handler.dialog.cancel(now);
+    forward(&statuses, &gui_sender, log.as_mut(), now);
+}
+
+/// 转发 GUI 状态并记录
+fn forward(
+    statuses: &Receiver<WakeStatus>,
+    gui_sender: &Sender<WakeStatus>,
+    mut log: Option<&mut EventLog>,
+    now: Instant,
+) {
+    for status in statuses.try_iter() {
+        if let Some(log) = log.as_mut() {
+            log.status(now, &status);
+        }
+        let _ = gui_sender.send(status);
+    }
+}
+
+/// 黑匣子录音（可选）
+fn open_recorder(sample_rate: u32) -> Option<Recorder> {
+    RecorderConfig::load(sample_rate).and_then(|config| match Recorder::new(config) {
+        Ok(recorder) => Some(recorder),
+        Err(e) => {
+            error!("failed to start recorder: {:?}", e);
+            None
+        }
+    })
+}
+
+/// 用户档案，打开失败时仅保存在内存中
+fn open_profiles(settings: &Settings) -> ProfileStore {
+    match &settings.profile_path {
+        Some(path) => ProfileStore::open(path).unwrap_or_else(|e| {
+            error!("failed to open profile store: {:?}", e);
+            ProfileStore::in_memory()
+        }),
+        None => ProfileStore::in_memory(),
+    }
+}
+
+/// 定时提醒，恢复失败时从空开始
+fn open_timers(settings: &Settings) -> Scheduler {
+    let now = Instant::now();
+    match &settings.timer_path {
+        Some(path) => Scheduler::open(path, now).unwrap_or_else(|e| {
+            error!("failed to restore timers: {:?}", e);
+            Scheduler::in_memory(now)
+        }),
+        None => Scheduler::in_memory(now),
+    }
+}
+
+/// 车辆总线（未配置接口时使用模拟总线）；配置了的总线或 DBC 打不开时作为启动错误返回，
+/// 不能悄悄换成模拟总线让车控指令看似成功
+fn open_vehicle(settings: &Settings) -> Result<VehicleController, anyhow::Error> {
+    let mut vehicle = VehicleController::open(
+        settings.can_interface.as_deref(),
+        settings.dbc_path.as_deref(),
+    )
+    .context("failed to open vehicle bus")?;
+    if let Some(port) = &settings.obd_port {
+        vehicle.attach_obd(obd::spawn_monitor(port.clone(), settings.obd_baud_rate));
+    }
+    Ok(vehicle)
+}
+
+/// 事件处理：唤醒检测、声音事件、录音与对话状态机
+pub struct EventHandler {
+    detector: WakeDetector,
+    power: DutyCycle,
+    verifier: SpeakerVerifier,
+    classifier: SoundClassifier,
+    recorder: Option<Recorder>,
+    last_direction: Option<DirectionOfArrival>,
+    pub dialog: Dialog,
+    hub: EventHub,
+    gui_sender: Sender<WakeStatus>,
+    privacy: PrivacySwitch,
+    muted: bool, // 已生效的隐私模式状态
+}
+
+impl EventHandler {
+    pub fn new(
+        gui_sender: Sender<WakeStatus>,
+        hub: EventHub,
+        services: Services,
+        recorder: Option<Recorder>,
+        sample_rate: u32,
+    ) -> Result<Self, anyhow::Error> {
+        // 档案中注册的声纹
+        let mut verifier = SpeakerVerifier::new(sample_rate);
+        for profile in services.profiles.profiles() {
+            if let Some(speaker) = profile.speaker_profile() {
+                verifier.insert_profile(speaker);
             }
-            Err(crossbeam_channel::RecvError) => {
-                // 通道关闭时退出循环
-                println!("Audio channel closed, existing event loop");
-                break;
+        }
+        let mut handler = Self {
+            detector: WakeDetector::new(sample_rate)?,
+            power: DutyCycle::new(&Settings::load(), sample_rate),
+            verifier,
+            classifier: SoundClassifier::new(sample_rate),
+            recorder,
+            last_direction: None,
+            privacy: services.privacy.clone(),
+            dialog: Dialog::new(
+                gui_sender.clone(),
+                hub.clone(),
+                services,
+                sample_rate,
+                Instant::now(),
+            ),
+            hub,
+            gui_sender,
+            muted: false,
+        };
+        handler.apply_profile();
+        Ok(handler)
+    }
+
+    /// 隐私模式下拒绝音频帧与由音频得到的方位
+    pub fn admits(&self, event: &WakeEvent) -> bool {
+        !(self.privacy.is_muted()
+            && matches!(event, WakeEvent::AudioFrame(_) | WakeEvent::Direction(_)))
+    }
+
+    pub fn handle(&mut self, event: WakeEvent, now: Instant) {
+        self.sync_privacy(now);
+        if !self.admits(&event) {
+            return;
+        }
+        match event {
+            WakeEvent::AudioFrame(data) => {
+                // 低功耗监听时只在检测到声音活动后做分类与唤醒检测
+                let active = self.power.process(&data);
+
+                // 非语音声音事件（警笛、喇叭等），提醒驾驶员并通知订阅者
+                if active {
+                    for sound in self.classifier.process(&data) {
+                        info!(kind = %sound.kind, confidence = sound.confidence, "Sound event");
+                        let _ = self.gui_sender.send(WakeStatus::Sound {
+                            kind: sound.kind.to_string(),
+                        });
+                        self.hub.publish(AssistantEvent::Sound {
+                            kind: sound.kind,
+                            confidence: sound.confidence,
+                        });
+                    }
+                }
+
+                // 处理音频帧
+                let woke = if active {
+                    self.detector.process(&data)
+                } else {
+                    self.detector.push(&data);
+                    false
+                };
+                if let Some(recorder) = self.recorder.as_mut() {
+                    match recorder.push(&data) {
+                        Ok(Some(path)) => info!("Recorded: {}", path.display()),
+                        Ok(None) => {}
+                        Err(e) => error!("failed to write recording: {:?}", e),
+                    }
+                }
+
+                if woke {
+                    // 声纹验证，指令携带说话人身份
+                    let info = WakeInfo {
+                        speaker: self.verifier.verify(self.detector.last_window()),
+                        seat: self.last_direction.as_ref().and_then(|d| d.seat),
+                    };
+                    self.handle(WakeEvent::WakeDetected(info), now);
+                } else if self.detector.is_rejected() {
+                    self.capture(Trigger::Reject, &WakeInfo::default());
+                }
+
+                // 对话采集语音
+                self.dialog.on_audio(&data, now);
+            }
+            WakeEvent::WakeDetected(info) => {
+                // 处于已唤醒状态
+                info!(
+                    speaker = ?info.speaker.as_ref().map(|v| &v.speaker.0),
+                    seat = ?info.seat,
+                    score = ?self.detector.last_score(),
+                    "Wake word detected"
+                );
+                self.capture(Trigger::Wake, &info);
+                self.hub.publish(AssistantEvent::Wake {
+                    speaker: info.speaker.as_ref().map(|v| v.speaker.to_string()),
+                    score: self.detector.last_score(),
+                    seat: info.seat,
+                });
+                self.dialog.on_wake(info, now);
+            }
+            WakeEvent::Direction(direction) => {
+                // 记录最近一次声源方位
+                self.last_direction = Some(direction);
+            }
+            WakeEvent::StreamError(message) => {
+                self.capture(Trigger::Error(message), &WakeInfo::default());
+            }
+        }
+    }
+
+    /// 处理外部控制请求
+    pub fn control(&mut self, request: ControlRequest, now: Instant) -> Result<Value, String> {
+        match request {
+            ControlRequest::Status => {}
+            ControlRequest::StartListening if self.muted => {
+                return Err("privacy mode is on".to_string());
+            }
+            ControlRequest::StartListening => {
+                self.handle(WakeEvent::WakeDetected(WakeInfo::default()), now)
+            }
+            ControlRequest::Cancel => self.dialog.cancel(now),
+            ControlRequest::Mute { muted } => {
+                self.privacy.set(muted);
+                self.sync_privacy(now);
+            }
+            ControlRequest::Speak { text } => {
+                self.dialog.speak(&text, now).map_err(|e| e.to_string())?
+            }
+            ControlRequest::Execute { text } => self
+                .dialog
+                .submit_text(&text, now)
+                .map_err(|e| e.to_string())?,
+            ControlRequest::Profiles => {
+                let profiles: Vec<_> = self.dialog.profiles.profiles().collect();
+                return Ok(json!({
+                    "profiles": profiles,
+                    "selected": self.dialog.profiles.selected().map(|p| &p.id),
+                }));
+            }
+            ControlRequest::SelectProfile { profile } => {
+                self.dialog
+                    .profiles
+                    .select(profile.as_deref())
+                    .map_err(|e| e.to_string())?;
+                self.apply_profile();
+            }
+            ControlRequest::UpdateProfile {
+                profile,
+                preferences,
+            } => {
+                let profile = self
+                    .dialog
+                    .profiles
+                    .set_preferences(&profile, preferences)
+                    .map_err(|e| e.to_string())?
+                    .clone();
+                self.apply_profile();
+                return Ok(json!(profile));
+            }
+            ControlRequest::DeleteProfile { profile } => {
+                let removed = self
+                    .dialog
+                    .profiles
+                    .remove(&profile)
+                    .map_err(|e| e.to_string())?;
+                if !removed {
+                    return Err(format!("unknown profile: {}", profile));
+                }
+                self.verifier.remove(&SpeakerId(profile));
+                self.apply_profile();
+            }
+            ControlRequest::EnrollVoice { profile } => {
+                return self.enroll(&profile).map_err(|e| e.to_string());
             }
         }
+        Ok(json!({
+            "dialog": self.dialog.state(),
+            "muted": self.muted,
+            "power": self.power.mode(),
+            "profile": self.dialog.active_profile(),
+        }))
+    }
+
+    /// 超时检查，并应用对话中（语音指令）切换的隐私模式与车辆状态对应的功耗模式
+    pub fn tick(&mut self, now: Instant) {
+        self.dialog.tick(now);
+        self.sync_privacy(now);
+        self.sync_power();
+    }
+
+    /// 点火关闭（驻车）时进入低功耗监听
+    fn sync_power(&mut self) {
+        let mode = if self.dialog.vehicle().state().ignition_on {
+            PowerMode::Normal
+        } else {
+            PowerMode::LowPower
+        };
+        if self.power.set_mode(mode) {
+            info!(?mode, "Power mode");
+            self.hub.publish(AssistantEvent::Power { mode });
+        }
+    }
+
+    /// 隐私模式切换后生效：开启时清空所有缓存的音频并中止正在采集、识别的对话，
+    /// 通知 GUI 与订阅者
+    fn sync_privacy(&mut self, now: Instant) {
+        let muted = self.privacy.is_muted();
+        if muted == self.muted {
+            return;
+        }
+        self.muted = muted;
+        info!(muted, "Privacy mode");
+        if muted {
+            self.detector.reset();
+            self.classifier.reset();
+            if let Some(recorder) = self.recorder.as_mut() {
+                recorder.clear();
+            }
+            self.last_direction = None;
+            // 保留开启隐私模式的语音指令的回复
+            if self.dialog.state() != DialogState::Responding {
+                self.dialog.cancel(now);
+            }
+        }
+        let _ = self.gui_sender.send(WakeStatus::Privacy { enabled: muted });
+        self.hub.publish(AssistantEvent::Muted { muted });
+    }
+
+    /// 以最近一次唤醒的语音注册声纹并保存到档案
+    fn enroll(&mut self, profile: &str) -> Result<Value, anyhow::Error> {
+        let window = self.detector.last_window();
+        if window.is_empty() {
+            return Err(anyhow::anyhow!("no wake word utterance to enroll yet"));
+        }
+        let id = SpeakerId::from(profile);
+        self.verifier.enroll(id.clone(), window)?;
+        let speaker = self
+            .verifier
+            .profiles()
+            .iter()
+            .find(|p| p.id == id)
+            .expect("speaker was just enrolled");
+        let voice = VoicePrint {
+            centroid: speaker.centroid.to_vec(),
+            enrollments: speaker.enrollments,
+        };
+        let enrollments = voice.enrollments;
+        self.dialog
+            .profiles
+            .update(profile, |p| p.voice = Some(voice))?;
+        info!(profile, enrollments, "Voice enrolled");
+        Ok(json!({ "profile": profile, "enrollments": enrollments }))
+    }
+
+    /// 应用手动选择的档案中的唤醒灵敏度（唤醒时尚未验证说话人）
+    fn apply_profile(&mut self) {
+        let threshold = self
+            .dialog
+            .profiles
+            .selected()
+            .and_then(|profile| profile.preferences.wake_threshold);
+        self.detector.set_threshold(threshold);
+    }
+
+    /// 触发黑匣子录音
+    fn capture(&mut self, trigger: Trigger, info: &WakeInfo) {
+        if let Some(recorder) = self.recorder.as_mut() {
+            let scores = CaptureScores {
+                wake_score: self.detector.last_score(),
+                wake_threshold: self.detector.threshold(),
+                speaker: info.speaker.as_ref().map(|v| v.speaker.to_string()),
+                speaker_score: info.speaker.as_ref().map(|v| v.score),
+            };
+            recorder.trigger(trigger, scores);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    #[test]
+    fn privacy_mode_drops_audio_and_notifies() {
+        testing::settings();
+        let hub = EventHub::default();
+        let events = hub.subscribe();
+        let privacy = PrivacySwitch::default();
+        let (mut handler, statuses) =
+            open_handler(hub, privacy.clone(), testing::SAMPLE_RATE).unwrap();
+        let now = Instant::now();
+
+        handler
+            .control(ControlRequest::Mute { muted: true }, now)
+            .unwrap();
+        assert!(privacy.is_muted());
+        assert!(statuses
+            .try_iter()
+            .any(|s| matches!(s, WakeStatus::Privacy { enabled: true })));
+        assert!(events
+            .try_iter()
+            .any(|e| matches!(e, AssistantEvent::Muted { muted: true })));
+        assert!(!handler.admits(&WakeEvent::AudioFrame(vec![0.0; 160])));
+        assert!(handler.admits(&WakeEvent::StreamError("closed".into())));
+        assert_eq!(
+            handler.control(ControlRequest::StartListening, now),
+            Err("privacy mode is on".to_string())
+        );
+
+        // 经其他途径（屏幕、语音技能）切换的状态在下一个事件时生效
+        privacy.set(false);
+        handler.handle(WakeEvent::AudioFrame(vec![0.0; 160]), now);
+        assert!(statuses
+            .try_iter()
+            .any(|s| matches!(s, WakeStatus::Privacy { enabled: false })));
+        assert!(handler.admits(&WakeEvent::AudioFrame(vec![0.0; 160])));
+    }
+
+    #[test]
+    fn vehicle_bus_errors_are_startup_errors() {
+        let mut settings = testing::settings();
+        assert!(open_vehicle(&settings).is_ok());
+
+        settings.dbc_path = Some("/nonexistent/vehicle.dbc".into());
+        assert!(open_vehicle(&settings).is_err());
+
+        settings.dbc_path = None;
+        settings.can_interface = Some("nocan9".into());
+        let error = open_vehicle(&settings).err().unwrap();
+        assert!(format!("{:#}", error).contains("nocan9"));
     }
 }
@@ -0,0 +1,232 @@
+use super::grammar::parse_number;
+use anyhow::anyhow;
+use serde::de::value::{Error as ValueError, StrDeserializer};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// 空调温区
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Zone {
+    Driver,
+    Passenger,
+    Rear,
+    All,
+}
+
+impl Zone {
+    /// 回复中使用的名称
+    pub fn label(self) -> &'static str {
+        match self {
+            Zone::Driver => "driver",
+            Zone::Passenger => "passenger",
+            Zone::Rear => "rear",
+            Zone::All => "cabin",
+        }
+    }
+}
+
+/// 车窗位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowPosition {
+    FrontLeft,
+    FrontRight,
+    RearLeft,
+    RearRight,
+    All,
+}
+
+impl WindowPosition {
+    pub fn label(self) -> &'static str {
+        match self {
+            WindowPosition::FrontLeft => "front left",
+            WindowPosition::FrontRight => "front right",
+            WindowPosition::RearLeft => "rear left",
+            WindowPosition::RearRight => "rear right",
+            WindowPosition::All => "all",
+        }
+    }
+}
+
+/// 车窗动作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowAction {
+    Open,
+    Close,
+}
+
+/// 媒体动作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MediaAction {
+    Play,
+    Pause,
+    Next,
+    Previous,
+    VolumeUp,
+    VolumeDown,
+    Mute,
+}
+
+impl MediaAction {
+    pub fn label(self) -> &'static str {
+        match self {
+            MediaAction::Play => "play",
+            MediaAction::Pause => "pause",
+            MediaAction::Next => "next track",
+            MediaAction::Previous => "previous track",
+            MediaAction::VolumeUp => "volume up",
+            MediaAction::VolumeDown => "volume down",
+            MediaAction::Mute => "mute",
+        }
+    }
+}
+
+/// 车况查询
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VehicleQuery {
+    FuelLevel,
+    Speed,
+    CoolantTemperature,
+    EngineWarnings,
+}
+
+/// 车控指令
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum VehicleCommand {
+    SetTemperature {
+        zone: Zone,
+        celsius: f32,
+    },
+    Window {
+        which: WindowPosition,
+        action: WindowAction,
+    },
+    Media {
+        action: MediaAction,
+    },
+    Query {
+        item: VehicleQuery,
+    },
+}
+
+impl VehicleCommand {
+    /// 由意图名与槽位（规范值）构造指令，非车控意图返回 None
+    pub fn from_slots(
+        intent: &str,
+        slots: &HashMap<String, String>,
+    ) -> Result<Option<Self>, anyhow::Error> {
+        let command = match intent {
+            "set_temperature" => {
+                let celsius = slots
+                    .get("celsius")
+                    .and_then(|v| parse_number(v))
+                    .ok_or_else(|| anyhow!("missing temperature"))?;
+                if !(16.0..=32.0).contains(&celsius) {
+                    return Err(anyhow!("temperature out of range: {}", celsius));
+                }
+                VehicleCommand::SetTemperature {
+                    zone: slot_or(slots, "zone", Zone::All)?,
+                    celsius,
+                }
+            }
+            "window" => VehicleCommand::Window {
+                which: slot_or(slots, "which", WindowPosition::All)?,
+                action: slot(slots, "action")?,
+            },
+            "media" => VehicleCommand::Media {
+                action: slot(slots, "action")?,
+            },
+            "query" => VehicleCommand::Query {
+                item: slot(slots, "item")?,
+            },
+            _ => return Ok(None),
+        };
+        Ok(Some(command))
+    }
+}
+
+/// 读取必填槽位
+fn slot<'de, T: Deserialize<'de>>(
+    slots: &'de HashMap<String, String>,
+    name: &str,
+) -> Result<T, anyhow::Error> {
+    let value = slots
+        .get(name)
+        .ok_or_else(|| anyhow!("missing slot: {}", name))?;
+    T::deserialize(StrDeserializer::<ValueError>::new(value))
+        .map_err(|e| anyhow!("invalid value {:?} for slot {}: {}", value, name, e))
+}
+
+/// 读取可选槽位，缺省时使用默认值
+fn slot_or<'de, T: Deserialize<'de>>(
+    slots: &'de HashMap<String, String>,
+    name: &str,
+    default: T,
+) -> Result<T, anyhow::Error> {
+    match slots.get(name) {
+        Some(_) => slot(slots, name),
+        None => Ok(default),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command(intent: &str, slots: &[(&str, &str)]) -> Result<Option<VehicleCommand>, String> {
+        let slots = slots
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        VehicleCommand::from_slots(intent, &slots).map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn builds_commands_from_slots() {
+        assert_eq!(
+            command("set_temperature", &[("celsius", "21.5")]),
+            Ok(Some(VehicleCommand::SetTemperature {
+                zone: Zone::All,
+                celsius: 21.5
+            }))
+        );
+        assert_eq!(
+            command("window", &[("which", "rear_left"), ("action", "close")]),
+            Ok(Some(VehicleCommand::Window {
+                which: WindowPosition::RearLeft,
+                action: WindowAction::Close
+            }))
+        );
+        assert_eq!(
+            command("query", &[("item", "fuel_level")]),
+            Ok(Some(VehicleCommand::Query {
+                item: VehicleQuery::FuelLevel
+            }))
+        );
+        assert_eq!(command("set_timer", &[("seconds", "60")]), Ok(None));
+    }
+
+    #[test]
+    fn rejects_invalid_slots() {
+        assert_eq!(
+            command("set_temperature", &[]),
+            Err("missing temperature".into())
+        );
+        assert_eq!(
+            command("set_temperature", &[("celsius", "40")]),
+            Err("temperature out of range: 40".into())
+        );
+        assert_eq!(command("media", &[]), Err("missing slot: action".into()));
+        let error = command("window", &[("which", "roof"), ("action", "open")]).unwrap_err();
+        assert!(
+            error.starts_with("invalid value \"roof\" for slot which"),
+            "{}",
+            error
+        );
+    }
+}
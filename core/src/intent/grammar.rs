@@ -0,0 +1,362 @@
+use anyhow::{anyhow, Context};
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Range;
+
+/// 内置数字槽位：阿拉伯数字或中文数字
+const NUMBER_PATTERN: &str =
+    r"-?\d+(?:\.\d+)?|负?[零〇一二两三四五六七八九十百]+(?:点[零〇一二三四五六七八九]+)?";
+
+/// 内置文本槽位：任意非空文本（尽量长，如 “in the car in 5 minutes” 取到最后一个 “in” 之前）
+const TEXT_PATTERN: &str = r".+";
+
+/// 语法文件
+#[derive(Debug, Deserialize)]
+pub(super) struct Grammar {
+    #[serde(default)]
+    slot_types: HashMap<String, BTreeMap<String, Vec<String>>>,
+    #[serde(default, rename = "rule")]
+    rules: Vec<Rule>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Rule {
+    intent: String,
+    patterns: Vec<String>,
+    #[serde(default)]
+    slots: HashMap<String, String>,
+    #[serde(default)]
+    values: HashMap<String, String>,
+}
+
+/// 编译后的规则
+pub(super) struct CompiledRule {
+    pub intent: String,
+    patterns: Vec<Regex>,
+    slot_types: HashMap<String, String>,
+    synonyms: HashMap<String, HashMap<String, String>>, // 类型 → 同义词 → 规范值
+    values: HashMap<String, String>,
+}
+
+impl Grammar {
+    pub fn compile(self) -> Result<Vec<CompiledRule>, anyhow::Error> {
+        // 同义词 → 规范值
+        let synonyms: HashMap<String, HashMap<String, String>> = self
+            .slot_types
+            .iter()
+            .map(|(name, values)| {
+                let lookup = values
+                    .iter()
+                    .flat_map(|(value, words)| {
+                        words.iter().map(move |w| (normalize(w), value.clone()))
+                    })
+                    .collect();
+                (name.clone(), lookup)
+            })
+            .collect();
+
+        self.rules
+            .into_iter()
+            .map(|rule| {
+                let patterns = rule
+                    .patterns
+                    .iter()
+                    .map(|pattern| {
+                        compile_pattern(pattern, &rule.slots, &synonyms).with_context(|| {
+                            format!("invalid pattern {:?} in intent {}", pattern, rule.intent)
+                        })
+                    })
+                    .collect::<Result<_, _>>()?;
+                Ok(CompiledRule {
+                    intent: rule.intent,
+                    patterns,
+                    slot_types: rule.slots,
+                    synonyms: synonyms.clone(),
+                    values: rule.values,
+                })
+            })
+            .collect()
+    }
+}
+
+impl CompiledRule {
+    /// 匹配文本，返回槽位（规范值）与匹配区间；多个模式取覆盖最长者
+    pub fn matches(&self, text: &str) -> Option<(HashMap<String, String>, Range<usize>)> {
+        let captures = self
+            .patterns
+            .iter()
+            .filter_map(|pattern| {
+                pattern
+                    .captures_iter(text)
+                    .find(|c| c.get(0).is_some_and(|m| on_word_boundary(text, m.range())))
+            })
+            .max_by_key(|c| c.get(0).map(|m| m.len()).unwrap_or(0))?;
+        let span = captures.get(0)?.range();
+
+        let mut slots = self.values.clone();
+        for (slot, slot_type) in &self.slot_types {
+            let Some(value) = captures.name(slot) else {
+                continue;
+            };
+            let value = normalize(value.as_str());
+            let value = match self.synonyms.get(slot_type) {
+                Some(lookup) => lookup.get(&value)?.clone(),
+                None => value,
+            };
+            slots.insert(slot.clone(), value);
+        }
+        Some((slots, span))
+    }
+}
+
+/// 将模式编译为正则表达式
+fn compile_pattern(
+    pattern: &str,
+    slots: &HashMap<String, String>,
+    synonyms: &HashMap<String, HashMap<String, String>>,
+) -> Result<Regex, anyhow::Error> {
+    let mut regex = String::new();
+    let mut chars = normalize(pattern).chars().collect::<Vec<_>>().into_iter();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '[' => regex.push_str("(?:"),
+            ']' => regex.push_str(")?"),
+            '(' => regex.push_str("(?:"),
+            ')' => regex.push(')'),
+            '|' => regex.push('|'),
+            ' ' => regex.push_str(r"\s*"),
+            '{' => {
+                let name: String = chars.by_ref().take_while(|c| *c != '}').collect();
+                let slot_type = slots
+                    .get(&name)
+                    .ok_or_else(|| anyhow!("slot {{{}}} has no declared type", name))?;
+                let alternatives = match slot_type.as_str() {
+                    "number" => NUMBER_PATTERN.to_string(),
+                    "text" => TEXT_PATTERN.to_string(),
+                    _ => {
+                        let lookup = synonyms
+                            .get(slot_type)
+                            .ok_or_else(|| anyhow!("unknown slot type: {}", slot_type))?;
+                        // 长同义词优先，避免“关”抢先匹配“关闭”
+                        let mut words: Vec<&String> = lookup.keys().collect();
+                        words.sort_by_key(|w| std::cmp::Reverse(w.chars().count()));
+                        words
+                            .iter()
+                            .map(|w| regex::escape(w).replace(' ', r"\s*"))
+                            .collect::<Vec<_>>()
+                            .join("|")
+                    }
+                };
+                regex.push_str(&format!("(?P<{}>{})", name, alternatives));
+            }
+            _ => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+
+    Ok(Regex::new(&regex)?)
+}
+
+/// 英文单词不能从中间截断（如 “display” 不应匹配 “play”），中文不受影响
+fn on_word_boundary(text: &str, span: Range<usize>) -> bool {
+    let is_word = |c: Option<char>| c.is_some_and(|c| c.is_ascii_alphanumeric());
+    let before = text[..span.start].chars().next_back();
+    let first = text[span.clone()].chars().next();
+    let last = text[span.clone()].chars().next_back();
+    let after = text[span.end..].chars().next();
+
+    !(is_word(before) && is_word(first) || is_word(last) && is_word(after))
+}
+
+/// 统一小写并合并空白
+pub(super) fn normalize(text: &str) -> String {
+    text.to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// 不计空白与标点的字符数
+pub(super) fn content_len(text: &str) -> usize {
+    text.chars().filter(|c| c.is_alphanumeric()).count()
+}
+
+/// 解析阿拉伯数字或中文数字（如“二十二点五”）
+pub(crate) fn parse_number(text: &str) -> Option<f32> {
+    if let Ok(value) = text.parse::<f32>() {
+        return Some(value);
+    }
+
+    let (negative, text) = match text.strip_prefix('负') {
+        Some(rest) => (true, rest),
+        None => (false, text),
+    };
+    let (integer, fraction) = match text.split_once('点') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (text, None),
+    };
+    if integer.is_empty() && fraction.is_none_or(str::is_empty) {
+        return None;
+    }
+
+    let digit = |c: char| -> Option<u32> {
+        Some(match c {
+            '零' | '〇' => 0,
+            '一' => 1,
+            '二' | '两' => 2,
+            '三' => 3,
+            '四' => 4,
+            '五' => 5,
+            '六' => 6,
+            '七' => 7,
+            '八' => 8,
+            '九' => 9,
+            _ => return None,
+        })
+    };
+
+    // 整数部分：按“百”“十”分段累加
+    let mut value = 0u32;
+    let mut current = 0u32;
+    for c in integer.chars() {
+        match c {
+            '百' => {
+                value += current.max(1) * 100;
+                current = 0;
+            }
+            '十' => {
+                value += current.max(1) * 10;
+                current = 0;
+            }
+            _ => current = current * 10 + digit(c)?,
+        }
+    }
+    let mut result = (value + current) as f32;
+
+    // 小数部分逐位读出
+    if let Some(fraction) = fraction {
+        let mut scale = 0.1;
+        for c in fraction.chars() {
+            result += digit(c)? as f32 * scale;
+            scale /= 10.0;
+        }
+    }
+
+    Some(if negative { -result } else { result })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers() {
+        let cases = [
+            ("22", Some(22.0)),
+            ("-3.5", Some(-3.5)),
+            ("十", Some(10.0)),
+            ("十二", Some(12.0)),
+            ("二十", Some(20.0)),
+            ("二十五", Some(25.0)),
+            ("两百", Some(200.0)),
+            ("一百零五", Some(105.0)),
+            ("一百一十", Some(110.0)),
+            ("二十二点五", Some(22.5)),
+            ("零点零五", Some(0.05)),
+            ("点五", Some(0.5)),
+            ("负十", Some(-10.0)),
+            // 逐位读出的数字
+            ("二零二五", Some(2025.0)),
+            ("一〇八", Some(108.0)),
+            ("", None),
+            ("负", None),
+            ("点", None),
+            ("一千", None),
+            ("二十x", None),
+            ("2十", None),
+            ("二点五x", None),
+        ];
+        for (text, expected) in cases {
+            let value = parse_number(text);
+            assert!(
+                match (value, expected) {
+                    (Some(value), Some(expected)) => (value - expected).abs() < 1e-4,
+                    (value, expected) => value == expected,
+                },
+                "{:?}: {:?} != {:?}",
+                text,
+                value,
+                expected
+            );
+        }
+    }
+
+    fn rules(source: &str) -> Result<Vec<CompiledRule>, anyhow::Error> {
+        toml::from_str::<Grammar>(source)?.compile()
+    }
+
+    const GRAMMAR: &str = r#"
+[slot_types.color]
+red = ["red", "红色", "红"]
+blue = ["blue", "蓝色"]
+
+[[rule]]
+intent = "light"
+patterns = ["[turn] [the] light[s] {color}", "灯光(调|换)成{color}"]
+slots = { color = "color" }
+values = { target = "light" }
+
+[[rule]]
+intent = "play"
+patterns = ["play {title}", "播放{title}"]
+slots = { title = "text" }
+"#;
+
+    #[test]
+    fn matches_patterns() {
+        let rules = rules(GRAMMAR).unwrap();
+        let light = &rules[0];
+        let play = &rules[1];
+
+        let cases = [
+            ("turn the lights red", Some("red"), 0..19),
+            ("light    blue", Some("blue"), 0..10),
+            ("请把灯光换成红色吧", Some("red"), 6..24),
+            ("lights green", None, 0..0),
+            ("flight red", None, 0..0),
+        ];
+        for (text, color, span) in cases {
+            let text = normalize(text);
+            match (light.matches(&text), color) {
+                (Some((slots, range)), Some(color)) => {
+                    assert_eq!(slots["color"], color, "{}", text);
+                    assert_eq!(slots["target"], "light");
+                    assert_eq!(range, span, "{}", text);
+                }
+                (None, None) => {}
+                (result, _) => panic!("{}: unexpected {:?}", text, result),
+            }
+        }
+
+        let (slots, _) = play.matches("播放周杰伦的歌").unwrap();
+        assert_eq!(slots["title"], "周杰伦的歌");
+        // 英文单词不能从中间截断
+        assert!(play.matches("display settings").is_none());
+    }
+
+    #[test]
+    fn rejects_invalid_grammars() {
+        let invalid = [
+            // 槽位未声明类型
+            "[[rule]]\nintent = \"a\"\npatterns = [\"{x}\"]",
+            // 未知槽位类型
+            "[[rule]]\nintent = \"a\"\npatterns = [\"{x}\"]\nslots = { x = \"size\" }",
+            // 括号不配对
+            "[[rule]]\nintent = \"a\"\npatterns = [\"(a|b\"]",
+        ];
+        for source in invalid {
+            assert!(rules(source).is_err(), "{}", source);
+        }
+    }
+}
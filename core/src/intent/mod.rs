@@ -0,0 +1,219 @@
+/*
+    意图识别
+    将识别文本（中文 / 英文）按声明式语法规则解析为意图与槽位，车控意图同时给出强类型指令，
+    结果包含置信度（规则覆盖的文本比例）与未匹配的剩余文本
+*/
+
+pub mod command;
+mod grammar;
+
+use anyhow::Context;
+pub use command::VehicleCommand;
+pub(crate) use grammar::parse_number;
+use grammar::{CompiledRule, Grammar};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use tracing::debug;
+
+/// 内置语法：车控、定时提醒
+const DEFAULT_GRAMMARS: [&str; 3] = [
+    include_str!("../../grammars/vehicle.toml"),
+    include_str!("../../grammars/timer.toml"),
+    include_str!("../../grammars/privacy.toml"),
+];
+
+/// 意图解析结果
+#[derive(Debug, Clone)]
+pub struct IntentMatch {
+    pub intent: String,
+    pub slots: HashMap<String, String>,  // 槽位规范值
+    pub command: Option<VehicleCommand>, // 非车控意图（如技能自带语法）为空
+    pub confidence: f32,                 // 规则匹配部分占全部文本的比例（0~1）
+    pub remainder: String,
+}
+
+/// 意图解析器
+pub struct IntentParser {
+    rules: Vec<CompiledRule>,
+}
+
+impl IntentParser {
+    /// 从语法文本创建
+    pub fn from_str(source: &str) -> Result<Self, anyhow::Error> {
+        let grammar: Grammar = toml::from_str(source).context("invalid grammar file")?;
+        Ok(Self {
+            rules: grammar.compile()?,
+        })
+    }
+
+    /// 加载目录下所有 `.toml` 语法文件
+    pub fn from_dir(dir: impl AsRef<Path>) -> Result<Self, anyhow::Error> {
+        let dir = dir.as_ref();
+        let mut rules = Vec::new();
+        let mut paths: Vec<_> = fs::read_dir(dir)
+            .with_context(|| format!("failed to read grammar directory: {}", dir.display()))?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            let source = fs::read_to_string(&path)?;
+            let parser = Self::from_str(&source)
+                .with_context(|| format!("failed to load grammar: {}", path.display()))?;
+            rules.extend(parser.rules);
+        }
+        Ok(Self { rules })
+    }
+
+    /// 合并另一解析器的规则
+    pub fn merge(&mut self, other: IntentParser) {
+        self.rules.extend(other.rules);
+    }
+
+    /// 解析文本，返回置信度最高的匹配
+    pub fn parse(&self, transcript: &str) -> Option<IntentMatch> {
+        let text = grammar::normalize(transcript);
+        let total = grammar::content_len(&text);
+        if total == 0 {
+            return None;
+        }
+
+        let mut best: Option<IntentMatch> = None;
+        for rule in &self.rules {
+            let Some((slots, span)) = rule.matches(&text) else {
+                continue;
+            };
+            let command = match VehicleCommand::from_slots(&rule.intent, &slots) {
+                Ok(command) => command,
+                Err(e) => {
+                    debug!("intent {} rejected: {}", rule.intent, e);
+                    continue;
+                }
+            };
+
+            let matched = grammar::content_len(&text[span.clone()]);
+            let confidence = matched as f32 / total as f32;
+            if best.as_ref().is_some_and(|b| b.confidence >= confidence) {
+                continue;
+            }
+
+            let remainder = format!("{} {}", &text[..span.start], &text[span.end..]);
+            best = Some(IntentMatch {
+                intent: rule.intent.clone(),
+                slots,
+                command,
+                confidence,
+                remainder: grammar::normalize(&remainder),
+            });
+        }
+        best
+    }
+}
+
+impl Default for IntentParser {
+    fn default() -> Self {
+        let mut parser = Self { rules: Vec::new() };
+        for source in DEFAULT_GRAMMARS {
+            parser.merge(Self::from_str(source).expect("built-in grammar must be valid"));
+        }
+        parser
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use command::{MediaAction, VehicleQuery, WindowAction, WindowPosition, Zone};
+
+    #[test]
+    fn built_in_grammar() {
+        let parser = IntentParser::default();
+        let cases = [
+            (
+                "把主驾温度调到二十二度",
+                "set_temperature",
+                Some(VehicleCommand::SetTemperature {
+                    zone: Zone::Driver,
+                    celsius: 22.0,
+                }),
+            ),
+            (
+                "Set the passenger temperature to 21.5 degrees",
+                "set_temperature",
+                Some(VehicleCommand::SetTemperature {
+                    zone: Zone::Passenger,
+                    celsius: 21.5,
+                }),
+            ),
+            (
+                "空调二十五度",
+                "set_temperature",
+                Some(VehicleCommand::SetTemperature {
+                    zone: Zone::All,
+                    celsius: 25.0,
+                }),
+            ),
+            (
+                "打开左后车窗",
+                "window",
+                Some(VehicleCommand::Window {
+                    which: WindowPosition::RearLeft,
+                    action: WindowAction::Open,
+                }),
+            ),
+            (
+                "roll up all the windows",
+                "window",
+                Some(VehicleCommand::Window {
+                    which: WindowPosition::All,
+                    action: WindowAction::Close,
+                }),
+            ),
+            (
+                "下一首",
+                "media",
+                Some(VehicleCommand::Media {
+                    action: MediaAction::Next,
+                }),
+            ),
+            (
+                "还剩多少油",
+                "query",
+                Some(VehicleCommand::Query {
+                    item: VehicleQuery::FuelLevel,
+                }),
+            ),
+            ("十分钟后提醒我加油", "set_reminder", None),
+            ("set a timer for 5 minutes", "set_timer", None),
+            ("打开隐私模式", "privacy_mode", None),
+        ];
+        for (text, intent, command) in cases {
+            let parsed = parser
+                .parse(text)
+                .unwrap_or_else(|| panic!("no match: {}", text));
+            assert_eq!(parsed.intent, intent, "{}", text);
+            assert_eq!(parsed.command, command, "{}", text);
+        }
+
+        assert!(parser.parse("").is_none());
+        assert!(parser.parse("今天天气怎么样").is_none());
+    }
+
+    #[test]
+    fn slots_confidence_and_remainder() {
+        let parser = IntentParser::default();
+        let parsed = parser.parse("提醒我一百零五分钟后去接孩子").unwrap();
+        assert_eq!(parsed.intent, "set_reminder");
+        assert_eq!(parsed.slots["amount"], "一百零五");
+        assert_eq!(parsed.slots["unit"], "minute");
+        assert_eq!(parsed.slots["message"], "去接孩子");
+        assert_eq!(parsed.confidence, 1.0);
+
+        let parsed = parser.parse("please mute thanks").unwrap();
+        assert_eq!(parsed.intent, "media");
+        assert_eq!(parsed.remainder, "please thanks");
+        assert!(parsed.confidence < 0.5);
+    }
+}
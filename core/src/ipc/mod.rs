@@ -0,0 +1,319 @@
+/*
+    本地 IPC 接口
+    在 Unix 域套接字上提供 JSON-RPC 2.0 服务（每行一个 JSON 对象），供导航、媒体等进程：
+    1. subscribe：订阅唤醒、对话状态、意图等事件（以 "event" 通知推送）
+    2. status / start_listening / cancel / mute / speak / execute：控制请求
+    3. profiles / select_profile / update_profile / delete_profile / enroll_voice：用户档案
+*/
+
+pub(crate) mod protocol;
+
+use crate::api::{Control, EventHub};
+use anyhow::{anyhow, Context};
+use crossbeam_channel::Sender;
+use protocol::{Request, Response};
+use serde_json::{json, Value};
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::fs::PermissionsExt;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use tracing::{error, info, warn};
+
+/// IPC 服务（释放时删除套接字文件）
+pub struct IpcServer {
+    path: PathBuf,
+}
+
+impl IpcServer {
+    pub fn start(
+        path: impl AsRef<Path>,
+        controls: Sender<Control>,
+        hub: EventHub,
+    ) -> Result<Self, anyhow::Error> {
+        let path = path.as_ref().to_path_buf();
+        if path.exists() {
+            // 残留的套接字文件可以删除，仍有进程监听时报错
+            if UnixStream::connect(&path).is_ok() {
+                return Err(anyhow!("IPC socket already in use: {}", path.display()));
+            }
+            fs::remove_file(&path)?;
+        }
+        let listener = UnixListener::bind(&path)
+            .with_context(|| format!("failed to bind IPC socket: {}", path.display()))?;
+        // 控制请求可以关闭隐私模式、注册声纹，只允许本用户连接
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600))
+            .with_context(|| format!("failed to restrict IPC socket: {}", path.display()))?;
+        info!("IPC listening on {}", path.display());
+
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                match stream {
+                    Ok(stream) => {
+                        let controls = controls.clone();
+                        let hub = hub.clone();
+                        thread::spawn(move || {
+                            if let Err(e) = serve(stream, controls, hub) {
+                                warn!("IPC client error: {:?}", e);
+                            }
+                        });
+                    }
+                    Err(e) => error!("IPC accept failed: {:?}", e),
+                }
+            }
+        });
+
+        Ok(Self { path })
+    }
+}
+
+impl Drop for IpcServer {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// 处理单个客户端连接
+fn serve(
+    stream: UnixStream,
+    controls: Sender<Control>,
+    hub: EventHub,
+) -> Result<(), anyhow::Error> {
+    let writer = Arc::new(Mutex::new(stream.try_clone()?));
+    let reader = BufReader::new(stream);
+
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = handle(&line, &controls, &hub, &writer) {
+            send(&writer, &serde_json::to_value(response)?)?;
+        }
+    }
+    Ok(())
+}
+
+/// 处理一条请求，通知（无 id）不应答
+fn handle(
+    line: &str,
+    controls: &Sender<Control>,
+    hub: &EventHub,
+    writer: &Arc<Mutex<UnixStream>>,
+) -> Option<Response> {
+    let request: Request = match serde_json::from_str(line) {
+        Ok(request) => request,
+        Err(e) => {
+            return Some(Response::error(
+                Value::Null,
+                protocol::PARSE_ERROR,
+                e.to_string(),
+            ))
+        }
+    };
+    let id = request.id.clone();
+    if request.jsonrpc.as_deref().is_some_and(|v| v != "2.0") {
+        return id.map(|id| {
+            Response::error(id, protocol::INVALID_REQUEST, "unsupported jsonrpc version")
+        });
+    }
+
+    let result = match request.method.as_str() {
+        "subscribe" => subscribe(&request.params, hub, writer),
+        method => match protocol::control_request(method, &request.params) {
+            Ok(control) => Control::call(controls, control)
+                .map_err(|message| (protocol::INTERNAL_ERROR, message)),
+            Err(error) => Err(error),
+        },
+    };
+
+    let id = id?;
+    Some(match result {
+        Ok(result) => Response::result(id, result),
+        Err((code, message)) => Response::error(id, code, message),
+    })
+}
+
+/// 订阅事件，可按类型过滤：{"events": ["wake", "intent"]}
+fn subscribe(
+    params: &Value,
+    hub: &EventHub,
+    writer: &Arc<Mutex<UnixStream>>,
+) -> Result<Value, (i64, String)> {
+    let filter: Option<Vec<String>> = match params.get("events") {
+        Some(events) => Some(
+            serde_json::from_value(events.clone())
+                .map_err(|e| (protocol::INVALID_PARAMS, e.to_string()))?,
+        ),
+        None => None,
+    };
+
+    let events = hub.subscribe();
+    let writer = writer.clone();
+    let kinds = filter.clone();
+    thread::spawn(move || {
+        for event in events {
+            if kinds
+                .as_ref()
+                .is_some_and(|kinds| !kinds.iter().any(|k| k == event.kind()))
+            {
+                continue;
+            }
+            // 客户端断开后结束转发
+            if send(&writer, &protocol::notification(&event)).is_err() {
+                break;
+            }
+        }
+    });
+
+    Ok(json!({ "subscribed": filter.unwrap_or_else(|| vec!["*".to_string()]) }))
+}
+
+fn send(writer: &Arc<Mutex<UnixStream>>, message: &Value) -> Result<(), anyhow::Error> {
+    let mut line = serde_json::to_vec(message)?;
+    line.push(b'\n');
+    writer.lock().unwrap().write_all(&line)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{AssistantEvent, ControlRequest};
+    use crate::testing;
+    use crossbeam_channel::unbounded;
+    use std::time::Duration;
+
+    struct Client {
+        stream: UnixStream,
+        reader: BufReader<UnixStream>,
+    }
+
+    impl Client {
+        fn connect(path: &Path) -> Self {
+            let stream = UnixStream::connect(path).unwrap();
+            stream
+                .set_read_timeout(Some(Duration::from_secs(5)))
+                .unwrap();
+            let reader = BufReader::new(stream.try_clone().unwrap());
+            Self { stream, reader }
+        }
+
+        fn call(&mut self, line: &str) -> Value {
+            self.stream.write_all(line.as_bytes()).unwrap();
+            self.stream.write_all(b"\n").unwrap();
+            self.read()
+        }
+
+        fn read(&mut self) -> Value {
+            let mut line = String::new();
+            self.reader.read_line(&mut line).unwrap();
+            serde_json::from_str(&line).unwrap()
+        }
+    }
+
+    /// 启动服务，控制请求原样回显
+    fn server(name: &str) -> (IpcServer, PathBuf, EventHub) {
+        let path = testing::temp_dir(name).join("ipc.sock");
+        let (controls, requests) = unbounded::<Control>();
+        thread::spawn(move || {
+            for control in requests {
+                let reply = match control.request {
+                    ControlRequest::Speak { text } if text.is_empty() => {
+                        Err("nothing to say".to_string())
+                    }
+                    request => Ok(json!(request)),
+                };
+                let _ = control.reply.send(reply);
+            }
+        });
+        let hub = EventHub::default();
+        let server = IpcServer::start(&path, controls, hub.clone()).unwrap();
+        (server, path, hub)
+    }
+
+    #[test]
+    fn answers_requests() {
+        let (_server, path, _hub) = server("ipc-requests");
+        let mut client = Client::connect(&path);
+
+        assert_eq!(
+            client.call(r#"{"jsonrpc": "2.0", "id": 1, "method": "status", "params": {}}"#),
+            json!({ "jsonrpc": "2.0", "id": 1, "result": { "method": "status" } })
+        );
+        assert_eq!(
+            client.call(r#"{"id": 2, "method": "speak", "params": {"text": ""}}"#)["error"],
+            json!({ "code": protocol::INTERNAL_ERROR, "message": "nothing to say" })
+        );
+        assert_eq!(
+            client.call(r#"{"id": 3, "method": "reboot"}"#)["error"]["code"],
+            protocol::METHOD_NOT_FOUND
+        );
+        assert_eq!(
+            client.call(r#"{"id": 4, "method": "mute", "params": {}}"#)["error"]["code"],
+            protocol::INVALID_PARAMS
+        );
+        assert_eq!(
+            client.call(r#"{"jsonrpc": "1.0", "id": 5, "method": "status"}"#)["error"]["code"],
+            protocol::INVALID_REQUEST
+        );
+        let error = client.call("{not json");
+        assert_eq!(error["id"], Value::Null);
+        assert_eq!(error["error"]["code"], protocol::PARSE_ERROR);
+
+        // 通知不应答，下一条应答属于随后的请求
+        client
+            .stream
+            .write_all(b"{\"method\": \"cancel\"}\n\n")
+            .unwrap();
+        assert_eq!(client.call(r#"{"id": 6, "method": "cancel"}"#)["id"], 6);
+    }
+
+    #[test]
+    fn forwards_subscribed_events() {
+        let (_server, path, hub) = server("ipc-subscribe");
+        let mut client = Client::connect(&path);
+        assert_eq!(
+            client.call(r#"{"id": 1, "method": "subscribe", "params": {"events": ["muted"]}}"#)
+                ["result"],
+            json!({ "subscribed": ["muted"] })
+        );
+        assert_eq!(
+            client.call(r#"{"id": 2, "method": "subscribe", "params": {"events": "all"}}"#)
+                ["error"]["code"],
+            protocol::INVALID_PARAMS
+        );
+
+        hub.publish(AssistantEvent::Response {
+            text: "filtered".into(),
+            succeeded: true,
+        });
+        hub.publish(AssistantEvent::Muted { muted: true });
+        assert_eq!(
+            client.read(),
+            protocol::notification(&AssistantEvent::Muted { muted: true })
+        );
+    }
+
+    #[test]
+    fn replaces_stale_socket() {
+        let (server, path, _hub) = server("ipc-stale");
+        // 仍在监听时拒绝
+        let (controls, _requests) = unbounded();
+        assert!(IpcServer::start(&path, controls.clone(), EventHub::default()).is_err());
+        drop(server);
+        assert!(!path.exists());
+
+        // 残留的套接字文件被替换
+        let stale = UnixListener::bind(&path).unwrap();
+        drop(stale);
+        assert!(path.exists());
+        let _server = IpcServer::start(&path, controls, EventHub::default()).unwrap();
+        Client::connect(&path);
+        // 只有本用户可以连接
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
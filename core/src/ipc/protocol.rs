@@ -0,0 +1,188 @@
+/*
+    JSON-RPC 2.0 报文（每行一个 JSON 对象）
+*/
+
+use crate::api::{AssistantEvent, ControlRequest};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+pub const INTERNAL_ERROR: i64 = -32603;
+
+/// 请求
+#[derive(Debug, Deserialize)]
+pub struct Request {
+    pub jsonrpc: Option<String>,
+    pub id: Option<Value>, // 为空时为通知，不应答
+    pub method: String,
+    #[serde(default)]
+    pub params: Value,
+}
+
+/// 应答
+#[derive(Debug, Serialize)]
+pub struct Response {
+    pub jsonrpc: &'static str,
+    pub id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<RpcError>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+impl Response {
+    pub fn result(id: Value, result: Value) -> Self {
+        Self {
+            jsonrpc: "2.0",
+            id,
+            result: Some(result),
+            error: None,
+        }
+    }
+
+    pub fn error(id: Value, code: i64, message: impl Into<String>) -> Self {
+        Self {
+            jsonrpc: "2.0",
+            id,
+            result: None,
+            error: Some(RpcError {
+                code,
+                message: message.into(),
+            }),
+        }
+    }
+}
+
+/// 事件通知
+pub fn notification(event: &AssistantEvent) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "event",
+        "params": event,
+    })
+}
+
+/// 方法名与参数转换为控制请求
+pub fn control_request(method: &str, params: &Value) -> Result<ControlRequest, (i64, String)> {
+    if !ControlRequest::METHODS.contains(&method) {
+        return Err((METHOD_NOT_FOUND, format!("method not found: {}", method)));
+    }
+    let empty = match params {
+        Value::Null => true,
+        Value::Object(map) => map.is_empty(),
+        Value::Array(items) => items.is_empty(),
+        _ => false,
+    };
+    let request = if empty {
+        json!({ "method": method })
+    } else {
+        json!({ "method": method, "params": params })
+    };
+
+    serde_json::from_value(request).map_err(|e| (INVALID_PARAMS, e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_methods_to_requests() {
+        for params in [Value::Null, json!({}), json!([])] {
+            assert!(matches!(
+                control_request("cancel", &params),
+                Ok(ControlRequest::Cancel)
+            ));
+        }
+        assert!(matches!(
+            control_request("speak", &json!({ "text": "hello" })),
+            Ok(ControlRequest::Speak { text }) if text == "hello"
+        ));
+        assert!(matches!(
+            control_request("select_profile", &json!({ "profile": null })),
+            Ok(ControlRequest::SelectProfile { profile: None })
+        ));
+
+        let (code, message) = control_request("reboot", &Value::Null).unwrap_err();
+        assert_eq!(code, METHOD_NOT_FOUND);
+        assert_eq!(message, "method not found: reboot");
+        assert_eq!(
+            control_request("mute", &Value::Null).unwrap_err().0,
+            INVALID_PARAMS
+        );
+        assert_eq!(
+            control_request("mute", &json!({ "muted": "yes" }))
+                .unwrap_err()
+                .0,
+            INVALID_PARAMS
+        );
+    }
+
+    #[test]
+    fn method_names_match_requests() {
+        let requests = [
+            ControlRequest::Status,
+            ControlRequest::StartListening,
+            ControlRequest::Cancel,
+            ControlRequest::Mute { muted: true },
+            ControlRequest::Speak { text: "hi".into() },
+            ControlRequest::Execute { text: "hi".into() },
+            ControlRequest::Profiles,
+            ControlRequest::SelectProfile { profile: None },
+            ControlRequest::UpdateProfile {
+                profile: "anna".into(),
+                preferences: Default::default(),
+            },
+            ControlRequest::DeleteProfile {
+                profile: "anna".into(),
+            },
+            ControlRequest::EnrollVoice {
+                profile: "anna".into(),
+            },
+        ];
+        let methods: Vec<Value> = requests
+            .iter()
+            .map(|request| serde_json::to_value(request).unwrap()["method"].clone())
+            .collect();
+        assert_eq!(methods, ControlRequest::METHODS.map(Value::from));
+
+        // 参数有误的已知方法不会被当作未知方法
+        for method in ControlRequest::METHODS {
+            let result = control_request(method, &json!({ "unexpected": [1] }));
+            assert!(!matches!(result, Err((METHOD_NOT_FOUND, _))), "{}", method);
+        }
+    }
+
+    #[test]
+    fn serializes_messages() {
+        assert_eq!(
+            serde_json::to_value(Response::result(json!(7), json!({ "ok": true }))).unwrap(),
+            json!({ "jsonrpc": "2.0", "id": 7, "result": { "ok": true } })
+        );
+        assert_eq!(
+            serde_json::to_value(Response::error(Value::Null, PARSE_ERROR, "bad")).unwrap(),
+            json!({ "jsonrpc": "2.0", "id": null, "error": { "code": -32700, "message": "bad" } })
+        );
+        assert_eq!(
+            notification(&AssistantEvent::Muted { muted: true }),
+            json!({
+                "jsonrpc": "2.0",
+                "method": "event",
+                "params": { "type": "muted", "muted": true },
+            })
+        );
+
+        let request: Request = serde_json::from_str(r#"{"id": "a", "method": "status"}"#).unwrap();
+        assert_eq!(request.id, Some(json!("a")));
+        assert_eq!(request.params, Value::Null);
+    }
+}
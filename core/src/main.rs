@@ -1,28 +1,175 @@
+use api::{Control, ControlRequest, EventHub};
+use clap::Parser;
+use cli::{Cli, Command, ConfigCommand};
 use crossbeam_channel::unbounded;
+use eframe::egui::ViewportCommand;
 use gui::WakeUI;
+use std::thread;
+use tokio::signal::unix::{signal, SignalKind};
+use tracing::{error, info};
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::EnvFilter;
+use voice::config::{LogFormat, Settings};
+use voice::privacy::PrivacySwitch;
 use voice::VoiceServer;
+mod api;
+mod cli;
+mod dialog;
 mod event;
+mod intent;
+mod ipc;
+mod metrics;
+mod mqtt;
+mod profile;
+mod replay;
+mod skill;
+#[cfg(test)]
+mod testing;
+mod timer;
+mod tts;
+mod vehicle;
 
 #[tokio::main]
 async fn main() -> Result<(), anyhow::Error> {
+    let cli = Cli::parse();
+    let settings = cli.settings()?;
+    init_tracing(&settings);
+    Settings::init(settings.clone());
+
+    match cli.command.unwrap_or(Command::Run { headless: false }) {
+        Command::Run { headless } => run(settings, headless).await,
+        Command::Devices => cli::devices(),
+        Command::Enroll { profile, files } => cli::enroll(&settings, &profile, &files),
+        Command::Eval { files } => cli::eval(&settings, &files),
+        // 回放模式：按虚拟时间重放事件日志并校验状态切换
+        Command::Replay { directory } => replay::run(&directory),
+        Command::Config {
+            command: ConfigCommand::Check,
+        } => cli::check_config(&settings),
+    }
+}
+
+/// 启动助手，headless 时不启动 GUI，状态输出到日志
+async fn run(settings: Settings, headless: bool) -> Result<(), anyhow::Error> {
     // 创建事件通道
     let (audio_sender, event_rx) = unbounded();
     let (gui_sender, gui_rx) = unbounded();
+    let (control_sender, control_rx) = unbounded();
+    let hub = EventHub::default();
+    let privacy = PrivacySwitch::default();
+
+    // 本地 IPC 服务
+    let ipc_server = settings.ipc_socket.as_ref().and_then(|path| {
+        match ipc::IpcServer::start(path, control_sender.clone(), hub.clone()) {
+            Ok(server) => Some(server),
+            Err(e) => {
+                error!("failed to start IPC server: {:?}", e);
+                None
+            }
+        }
+    });
+
+    // Prometheus 指标
+    if let Some(address) = &settings.metrics_addr {
+        if let Err(e) = metrics::start_server(address) {
+            error!("failed to start metrics endpoint: {:?}", e);
+        }
+    }
+
+    // MQTT 桥接
+    let mqtt_bridge = match mqtt::MqttConfig::from_settings(&settings) {
+        Some(config) => {
+            match config.and_then(|config| {
+                mqtt::MqttBridge::start(config, control_sender.clone(), hub.clone())
+            }) {
+                Ok(bridge) => Some(bridge),
+                Err(e) => {
+                    error!("failed to start MQTT bridge: {:?}", e);
+                    None
+                }
+            }
+        }
+        None => None,
+    };
 
     // 启动音频服务
-    let voice_server = VoiceServer::new(audio_sender).expect("failed to boot voice server");
-    voice_server.audio_stream.start();
+    let voice_server =
+        VoiceServer::start(audio_sender, privacy.clone()).expect("failed to boot voice server");
 
-    // 启动事件循环
-    tokio::spawn(event::event_loop(event_rx, gui_sender));
+    // 启动事件循环（阻塞接收，运行在阻塞线程池中，不占用异步工作线程）
+    let sample_rate = voice_server.sample_rate();
+    let (handler, statuses) = event::open_handler(hub, privacy, sample_rate)?;
+    let event_task = tokio::task::spawn_blocking(move || {
+        event::event_loop(
+            handler,
+            statuses,
+            event_rx,
+            gui_sender,
+            control_rx,
+            sample_rate,
+        )
+    });
 
-    // 启动 GUI
-    println!("Voice Assistant Booting...");
-    let _ = eframe::run_native(
-        "Voice Assistant",
-        eframe::NativeOptions::default(),
-        Box::new(|_| Ok(Box::new(WakeUI::new(gui_rx)))),
-    );
+    if headless {
+        info!("Voice Assistant Booting (headless)...");
+        thread::spawn(move || {
+            for status in gui_rx {
+                info!(?status, "GUI status");
+            }
+        });
+        shutdown_signal().await;
+    } else {
+        // 启动 GUI，收到退出信号时关闭窗口
+        info!("Voice Assistant Booting...");
+        // 隐私模式开关与外部接口一样经控制请求切换
+        let controls = control_sender.clone();
+        let set_privacy = move |muted| {
+            if let Err(e) = Control::call(&controls, ControlRequest::Mute { muted }) {
+                error!("failed to switch privacy mode: {}", e);
+            }
+        };
+        let _ = eframe::run_native(
+            "Voice Assistant",
+            eframe::NativeOptions::default(),
+            Box::new(|cc| {
+                let ctx = cc.egui_ctx.clone();
+                tokio::spawn(async move {
+                    shutdown_signal().await;
+                    ctx.send_viewport_cmd(ViewportCommand::Close);
+                });
+                Ok(Box::new(WakeUI::new(gui_rx, set_privacy)))
+            }),
+        );
+    }
+
+    // 停止采集并释放音频流（关闭事件通道），等待事件循环处理完剩余事件
+    info!("Shutting down...");
+    drop(voice_server);
+    event_task.await?;
+    drop(ipc_server);
+    drop(mqtt_bridge);
 
     Ok(())
 }
+
+/// 等待 SIGINT / SIGTERM
+async fn shutdown_signal() {
+    let mut terminate = signal(SignalKind::terminate()).expect("failed to listen for SIGTERM");
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => info!("Received SIGINT"),
+        _ = terminate.recv() => info!("Received SIGTERM"),
+    }
+}
+
+/// 初始化日志：级别取 RUST_LOG（未设置时用配置），对话周期的 span 结束时输出耗时
+fn init_tracing(settings: &Settings) {
+    let filter =
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(&settings.log_level));
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_span_events(FmtSpan::CLOSE);
+    match settings.log_format {
+        LogFormat::Text => builder.init(),
+        LogFormat::Json => builder.json().with_current_span(true).init(),
+    }
+}
@@ -0,0 +1,151 @@
+/*
+    运行指标
+    1. 意图执行结果与端到端延迟（语音结束 → 回复）
+    2. 在本地 HTTP 端口的 /metrics 路径以 Prometheus 文本格式导出（含 voice 注册的采集与检测指标）
+*/
+
+use anyhow::Context;
+use prometheus::{
+    register_histogram, register_int_counter_vec, Encoder, Histogram, IntCounterVec, TextEncoder,
+};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::LazyLock;
+use std::thread;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// 对话指标
+pub struct DialogMetrics {
+    pub intents: IntCounterVec, // 按意图与结果统计
+    pub latency: Histogram,     // 语音结束到回复的耗时
+}
+
+static METRICS: LazyLock<DialogMetrics> = LazyLock::new(|| DialogMetrics {
+    intents: register_int_counter_vec!(
+        "assistant_intents_total",
+        "Number of handled intents by outcome",
+        &["intent", "result"]
+    )
+    .unwrap(),
+    latency: register_histogram!(
+        "assistant_response_latency_seconds",
+        "Time from end of speech (or text submission) to response",
+        vec![0.1, 0.25, 0.5, 1.0, 1.5, 2.0, 3.0, 5.0, 8.0]
+    )
+    .unwrap(),
+});
+
+pub fn metrics() -> &'static DialogMetrics {
+    &METRICS
+}
+
+/// 记录意图执行结果（未识别的意图记为 unknown）
+pub fn record_intent(intent: &str, succeeded: bool) {
+    let result = if succeeded { "success" } else { "failure" };
+    metrics().intents.with_label_values(&[intent, result]).inc();
+}
+
+/// 启动 /metrics HTTP 服务
+pub fn start_server(address: &str) -> Result<(), anyhow::Error> {
+    let listener = TcpListener::bind(address)
+        .with_context(|| format!("failed to bind metrics endpoint: {}", address))?;
+    // 提前注册，未发生过的事件也导出
+    metrics();
+    voice::metrics::metrics();
+    info!(
+        "Metrics available at http://{}/metrics",
+        listener.local_addr()?
+    );
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    if let Err(e) = serve(stream) {
+                        warn!("metrics request failed: {:?}", e);
+                    }
+                }
+                Err(e) => warn!("metrics accept failed: {:?}", e),
+            }
+        }
+    });
+    Ok(())
+}
+
+/// 处理一次请求（不保持连接）
+fn serve(stream: TcpStream) -> Result<(), anyhow::Error> {
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request = String::new();
+    reader.read_line(&mut request)?;
+    // 跳过请求头
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+
+    let mut parts = request.split_whitespace();
+    let (status, content_type, body) = match (parts.next(), parts.next()) {
+        (Some("GET"), Some("/metrics")) => {
+            let encoder = TextEncoder::new();
+            let mut body = Vec::new();
+            encoder.encode(&prometheus::gather(), &mut body)?;
+            ("200 OK", encoder.format_type().to_string(), body)
+        }
+        (Some("GET"), Some(_)) => (
+            "404 Not Found",
+            "text/plain".into(),
+            b"not found\n".to_vec(),
+        ),
+        _ => (
+            "405 Method Not Allowed",
+            "text/plain".into(),
+            b"method not allowed\n".to_vec(),
+        ),
+    };
+
+    let mut stream = stream;
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        status,
+        content_type,
+        body.len()
+    )?;
+    stream.write_all(&body)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    /// 经本地连接发送一次请求，返回完整应答
+    fn request(line: &str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (stream, _) = listener.accept().unwrap();
+        client.write_all(line.as_bytes()).unwrap();
+        client.write_all(b"Host: localhost\r\n\r\n").unwrap();
+        serve(stream).unwrap();
+
+        let mut response = String::new();
+        client.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn exports_metrics() {
+        record_intent("window", true);
+        let response = request("GET /metrics HTTP/1.1\r\n");
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains(r#"assistant_intents_total{intent="window",result="success"}"#));
+
+        assert!(request("GET / HTTP/1.1\r\n").starts_with("HTTP/1.1 404 Not Found\r\n"));
+        assert!(request("POST /metrics HTTP/1.1\r\n")
+            .starts_with("HTTP/1.1 405 Method Not Allowed\r\n"));
+        assert!(start_server("not an address").is_err());
+    }
+}
@@ -0,0 +1,561 @@
+/*
+    MQTT 桥接
+    1. 将唤醒、意图事件与周期性健康状态发布到配置的主题
+    2. 订阅命令主题，远程触发控制请求（格式同 IPC：{"method": ..., "params": ...}），结果发布到应答主题
+    3. 断线自动重连，离线期间的消息暂存在有界队列中，重连后按序补发
+*/
+
+use crate::api::{AssistantEvent, Control, ControlRequest, EventHub};
+use crate::ipc::protocol;
+use crossbeam_channel::{select, Receiver, Sender};
+use rumqttc::{Client, Connection, Event, LastWill, MqttOptions, Packet, QoS};
+use serde_json::{json, Value};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tracing::{error, info, warn};
+use voice::config::Settings;
+
+/// 离线队列上限，超出时丢弃最早的消息
+const QUEUE_CAPACITY: usize = 1000;
+/// 重连退避上限
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// MQTT 配置
+#[derive(Debug, Clone)]
+pub struct MqttConfig {
+    pub host: String,
+    pub port: u16,
+    pub client_id: String,
+    pub wake_topic: String,
+    pub intent_topic: String,
+    pub sound_topic: String,
+    pub health_topic: String,
+    pub command_topic: String,
+    pub response_topic: String,
+    pub health_interval: Duration,
+    pub keep_alive: Duration,
+}
+
+impl MqttConfig {
+    /// 由设置生成配置（未配置代理地址时不启用）
+    pub fn from_settings(settings: &Settings) -> Option<Result<Self, anyhow::Error>> {
+        let broker = settings.mqtt_broker.as_ref()?;
+        Some(parse_broker(broker).map(|(host, port)| {
+            let topic = |name: &str| format!("{}/{}", settings.mqtt_topic_prefix, name);
+            Self {
+                host,
+                port,
+                client_id: settings.mqtt_client_id.clone(),
+                wake_topic: topic("wake"),
+                intent_topic: topic("intent"),
+                sound_topic: topic("sound"),
+                health_topic: topic("health"),
+                command_topic: topic("command"),
+                response_topic: topic("response"),
+                health_interval: Duration::from_secs_f32(settings.mqtt_health_secs),
+                keep_alive: Duration::from_secs(10),
+            }
+        }))
+    }
+}
+
+/// host[:port]，默认端口 1883
+fn parse_broker(broker: &str) -> Result<(String, u16), anyhow::Error> {
+    match broker.rsplit_once(':') {
+        Some((host, port)) => Ok((
+            host.to_string(),
+            port.parse()
+                .map_err(|_| anyhow::anyhow!("invalid MQTT broker port: {}", broker))?,
+        )),
+        None => Ok((broker.to_string(), 1883)),
+    }
+}
+
+/// 待发布的消息
+struct Message {
+    topic: String,
+    payload: Vec<u8>,
+    retain: bool,
+}
+
+/// 发件箱：在线时直接发布，离线时排队
+struct Outbox {
+    client: Client,
+    connected: bool,
+    queue: VecDeque<Message>,
+    dropped: usize,
+}
+
+impl Outbox {
+    fn publish(&mut self, message: Message) {
+        if self.connected {
+            self.flush();
+        }
+        if self.connected && self.queue.is_empty() {
+            match self.send(&message) {
+                Ok(()) => return,
+                Err(e) => warn!("MQTT publish failed, queued: {:?}", e),
+            }
+        }
+        if self.queue.len() >= QUEUE_CAPACITY {
+            self.queue.pop_front();
+            self.dropped += 1;
+        }
+        self.queue.push_back(message);
+    }
+
+    /// 按序补发排队的消息（请求通道已满时留待下次）
+    fn flush(&mut self) {
+        while let Some(message) = self.queue.pop_front() {
+            if self.send(&message).is_err() {
+                self.queue.push_front(message);
+                break;
+            }
+        }
+    }
+
+    fn send(&self, message: &Message) -> Result<(), rumqttc::ClientError> {
+        self.client.try_publish(
+            message.topic.as_str(),
+            QoS::AtLeastOnce,
+            message.retain,
+            message.payload.clone(),
+        )
+    }
+}
+
+/// 连接统计（用于健康状态）
+#[derive(Default)]
+struct Stats {
+    reconnects: usize,
+    commands: usize,
+}
+
+/// MQTT 桥接（释放时断开连接）
+pub struct MqttBridge {
+    outbox: Arc<Mutex<Outbox>>,
+}
+
+impl MqttBridge {
+    pub fn start(
+        config: MqttConfig,
+        controls: Sender<Control>,
+        hub: EventHub,
+    ) -> Result<Self, anyhow::Error> {
+        let mut options = MqttOptions::new(&config.client_id, &config.host, config.port);
+        options
+            .set_keep_alive(config.keep_alive)
+            .set_clean_session(true)
+            .set_last_will(LastWill::new(
+                &config.health_topic,
+                json!({ "online": false }).to_string(),
+                QoS::AtLeastOnce,
+                true,
+            ));
+        let (client, connection) = Client::new(options, 64);
+        info!("MQTT bridge connecting to {}:{}", config.host, config.port);
+
+        let outbox = Arc::new(Mutex::new(Outbox {
+            client,
+            connected: false,
+            queue: VecDeque::new(),
+            dropped: 0,
+        }));
+        let stats = Arc::new(Mutex::new(Stats::default()));
+
+        let (commands, command_rx) = crossbeam_channel::unbounded();
+        thread::spawn({
+            let config = config.clone();
+            let outbox = outbox.clone();
+            let stats = stats.clone();
+            move || run_connection(connection, config, outbox, stats, commands)
+        });
+
+        // 远程命令在单独线程中执行，避免阻塞连接
+        thread::spawn({
+            let config = config.clone();
+            let outbox = outbox.clone();
+            let controls = controls.clone();
+            move || {
+                for payload in command_rx {
+                    let response = execute(&payload, &controls);
+                    outbox.lock().unwrap().publish(Message {
+                        topic: config.response_topic.clone(),
+                        payload: response.to_string().into_bytes(),
+                        retain: false,
+                    });
+                }
+            }
+        });
+
+        let events = hub.subscribe();
+        thread::spawn({
+            let outbox = outbox.clone();
+            move || run_publisher(config, outbox, stats, controls, events)
+        });
+
+        Ok(Self { outbox })
+    }
+}
+
+impl Drop for MqttBridge {
+    fn drop(&mut self) {
+        let _ = self.outbox.lock().unwrap().client.try_disconnect();
+    }
+}
+
+/// 驱动连接：处理连接确认、命令消息与断线重连
+fn run_connection(
+    mut connection: Connection,
+    config: MqttConfig,
+    outbox: Arc<Mutex<Outbox>>,
+    stats: Arc<Mutex<Stats>>,
+    commands: Sender<Vec<u8>>,
+) {
+    let mut backoff = Duration::from_secs(1);
+    let mut connected_once = false;
+    for event in connection.iter() {
+        match event {
+            Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                info!("MQTT connected");
+                backoff = Duration::from_secs(1);
+                if connected_once {
+                    stats.lock().unwrap().reconnects += 1;
+                }
+                connected_once = true;
+
+                let mut outbox = outbox.lock().unwrap();
+                if let Err(e) = outbox
+                    .client
+                    .try_subscribe(config.command_topic.as_str(), QoS::AtLeastOnce)
+                {
+                    error!("MQTT subscribe failed: {:?}", e);
+                }
+                outbox.connected = true;
+                outbox.flush();
+            }
+            Ok(Event::Incoming(Packet::Publish(publish))) => {
+                if publish.topic == config.command_topic {
+                    stats.lock().unwrap().commands += 1;
+                    let _ = commands.send(publish.payload.to_vec());
+                }
+            }
+            Ok(_) => {}
+            Err(e) => {
+                let was_connected = std::mem::replace(&mut outbox.lock().unwrap().connected, false);
+                if was_connected {
+                    warn!("MQTT connection lost: {}", e);
+                } else {
+                    warn!("MQTT connection failed: {}", e);
+                }
+                // 下一次迭代即重连
+                thread::sleep(backoff);
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+            }
+        }
+    }
+}
+
+/// 转发助手事件并定期发布健康状态
+fn run_publisher(
+    config: MqttConfig,
+    outbox: Arc<Mutex<Outbox>>,
+    stats: Arc<Mutex<Stats>>,
+    controls: Sender<Control>,
+    events: Receiver<AssistantEvent>,
+) {
+    let started = Instant::now();
+    let mut next_health = Instant::now();
+    loop {
+        let timeout = next_health.saturating_duration_since(Instant::now());
+        select! {
+            recv(events) -> event => {
+                let Ok(event) = event else { return };
+                let topic = match event {
+                    AssistantEvent::Wake { .. } => &config.wake_topic,
+                    AssistantEvent::Intent { .. } => &config.intent_topic,
+                    AssistantEvent::Sound { .. } => &config.sound_topic,
+                    _ => continue,
+                };
+                let payload = serde_json::to_vec(&event).expect("event is serializable");
+                outbox.lock().unwrap().publish(Message {
+                    topic: topic.clone(),
+                    payload,
+                    retain: false,
+                });
+            },
+            default(timeout) => {
+                let status = Control::call(&controls, ControlRequest::Status)
+                    .unwrap_or_else(|e| json!({ "error": e }));
+                let mut outbox = outbox.lock().unwrap();
+                let health = {
+                    let stats = stats.lock().unwrap();
+                    json!({
+                        "online": true,
+                        "uptime_secs": started.elapsed().as_secs(),
+                        "reconnects": stats.reconnects,
+                        "commands": stats.commands,
+                        "queued": outbox.queue.len(),
+                        "dropped": outbox.dropped,
+                        "status": status,
+                    })
+                };
+                outbox.publish(Message {
+                    topic: config.health_topic.clone(),
+                    payload: health.to_string().into_bytes(),
+                    retain: true,
+                });
+                next_health = Instant::now() + config.health_interval;
+            },
+        }
+    }
+}
+
+/// 执行远程命令，返回应答（带回请求中的 id）
+/// 方法名与参数的解析同 IPC，空参数（null、{}、[]）等同于未给出
+/// 能向代理发布消息的任何人都可以发送命令，因此不能经 MQTT 关闭隐私模式（只能开启）
+fn execute(payload: &[u8], controls: &Sender<Control>) -> Value {
+    let command: Value = match serde_json::from_slice(payload) {
+        Ok(command) => command,
+        Err(e) => return json!({ "error": format!("invalid command: {}", e) }),
+    };
+    let id = command.get("id").cloned().unwrap_or(Value::Null);
+    let Some(method) = command.get("method").and_then(Value::as_str) else {
+        return json!({ "id": id, "error": "invalid command: missing method" });
+    };
+    let params = command.get("params").unwrap_or(&Value::Null);
+    let result = protocol::control_request(method, params)
+        .map_err(|(_, message)| message)
+        .and_then(|request| match request {
+            ControlRequest::Mute { muted: false } => {
+                Err("privacy mode can't be turned off over MQTT".to_string())
+            }
+            request => Control::call(controls, request),
+        });
+    match result {
+        Ok(result) => json!({ "id": id, "result": result }),
+        Err(error) => json!({ "id": id, "error": error }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossbeam_channel::unbounded;
+
+    /// 需要本机 MQTT 代理，如 `mosquitto -p 1883`
+    const BROKER: &str = "localhost:1883";
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    fn message(topic: &str) -> Message {
+        Message {
+            topic: topic.to_string(),
+            payload: Vec::new(),
+            retain: false,
+        }
+    }
+
+    fn queued(outbox: &Outbox) -> Vec<&str> {
+        outbox.queue.iter().map(|m| m.topic.as_str()).collect()
+    }
+
+    /// 请求通道容量为 2 的发件箱（连接不运行，通道满后发送失败）
+    fn outbox() -> (Outbox, Connection) {
+        let (client, connection) = Client::new(MqttOptions::new("test", "localhost", 1883), 2);
+        let outbox = Outbox {
+            client,
+            connected: false,
+            queue: VecDeque::new(),
+            dropped: 0,
+        };
+        (outbox, connection)
+    }
+
+    #[test]
+    fn offline_queue_drops_oldest() {
+        let (mut outbox, _connection) = outbox();
+        for i in 0..QUEUE_CAPACITY + 3 {
+            outbox.publish(message(&i.to_string()));
+        }
+        assert_eq!(outbox.queue.len(), QUEUE_CAPACITY);
+        assert_eq!(outbox.dropped, 3);
+        assert_eq!(queued(&outbox)[0], "3");
+    }
+
+    #[test]
+    fn flush_keeps_order() {
+        let (mut outbox, _connection) = outbox();
+        for topic in ["a", "b", "c"] {
+            outbox.publish(message(topic));
+        }
+
+        // 重连后按序补发，通道满时剩余的留在队首
+        outbox.connected = true;
+        outbox.flush();
+        assert_eq!(queued(&outbox), ["c"]);
+
+        // 队列未清空时新消息排在后面
+        outbox.publish(message("d"));
+        assert_eq!(queued(&outbox), ["c", "d"]);
+        assert_eq!(outbox.dropped, 0);
+    }
+
+    #[test]
+    fn broker_address() {
+        assert_eq!(
+            parse_broker("localhost").unwrap(),
+            ("localhost".to_string(), 1883)
+        );
+        assert_eq!(
+            parse_broker("10.0.0.2:8883").unwrap(),
+            ("10.0.0.2".to_string(), 8883)
+        );
+        assert!(parse_broker("broker:mqtt").is_err());
+        assert!(parse_broker("broker:70000").is_err());
+    }
+
+    #[test]
+    fn executes_commands() {
+        let (controls, requests) = unbounded::<Control>();
+        let (seen, requests_seen) = unbounded();
+        thread::spawn(move || {
+            for control in requests {
+                if let ControlRequest::Mute { muted } = control.request {
+                    let _ = seen.send(muted);
+                }
+                let _ = control.reply.send(Ok(json!(control.request)));
+            }
+        });
+        let execute = |payload: &str| execute(payload.as_bytes(), &controls);
+
+        for payload in [
+            r#"{"id": 1, "method": "status"}"#,
+            r#"{"id": 1, "method": "status", "params": {}}"#,
+            r#"{"id": 1, "method": "status", "params": null}"#,
+            r#"{"id": 1, "method": "status", "params": []}"#,
+        ] {
+            assert_eq!(
+                execute(payload),
+                json!({ "id": 1, "result": { "method": "status" } }),
+                "{}",
+                payload
+            );
+        }
+        assert_eq!(
+            execute(r#"{"id": "m", "method": "mute", "params": {"muted": true}}"#),
+            json!({ "id": "m", "result": { "method": "mute", "params": { "muted": true } } })
+        );
+
+        // 隐私模式只能开启，关闭的请求不转发
+        assert_eq!(
+            execute(r#"{"id": "u", "method": "mute", "params": {"muted": false}}"#),
+            json!({ "id": "u", "error": "privacy mode can't be turned off over MQTT" })
+        );
+        assert!(requests_seen.try_iter().all(|muted| muted));
+
+        assert_eq!(
+            execute(r#"{"id": 2, "method": "reboot"}"#),
+            json!({ "id": 2, "error": "method not found: reboot" })
+        );
+        assert!(execute(r#"{"id": 3, "method": "mute"}"#)["error"].is_string());
+        assert!(execute(r#"{"id": 4}"#)["error"].is_string());
+        assert_eq!(execute("not json")["id"], Value::Null);
+        assert!(execute("not json")["error"].is_string());
+    }
+
+    #[test]
+    fn reports_shutdown() {
+        let (controls, requests) = unbounded::<Control>();
+        drop(requests);
+        assert_eq!(
+            execute(br#"{"method": "cancel"}"#, &controls),
+            json!({ "id": null, "error": "assistant is shutting down" })
+        );
+    }
+
+    #[test]
+    #[ignore = "requires an MQTT broker on localhost:1883"]
+    fn bridges_events_and_commands() {
+        let settings = Settings {
+            mqtt_broker: Some(BROKER.into()),
+            mqtt_client_id: format!("voice-assistant-test-{}", std::process::id()),
+            mqtt_topic_prefix: format!("test/voice-assistant-{}", std::process::id()),
+            ..Settings::default()
+        };
+        let config = MqttConfig::from_settings(&settings).unwrap().unwrap();
+
+        let (controls, requests) = unbounded::<Control>();
+        thread::spawn(move || {
+            for control in requests {
+                let _ = control.reply.send(Ok(json!(control.request)));
+            }
+        });
+        let hub = EventHub::default();
+        let _bridge = MqttBridge::start(config.clone(), controls, hub.clone()).unwrap();
+
+        // 另一个客户端订阅助手发布的主题，并发送命令
+        let (host, port) = parse_broker(BROKER).unwrap();
+        let options = MqttOptions::new(format!("{}-peer", settings.mqtt_client_id), host, port);
+        let (peer, mut connection) = Client::new(options, 16);
+        let (received, messages) = unbounded();
+        thread::spawn(move || {
+            for event in connection.iter() {
+                match event {
+                    Ok(Event::Incoming(Packet::Publish(publish))) => {
+                        let payload =
+                            serde_json::from_slice(&publish.payload).unwrap_or(Value::Null);
+                        let _ = received.send((publish.topic, payload));
+                    }
+                    Ok(_) => {}
+                    Err(_) => return,
+                }
+            }
+        });
+        for topic in [
+            &config.wake_topic,
+            &config.health_topic,
+            &config.response_topic,
+        ] {
+            peer.subscribe(topic.as_str(), QoS::AtLeastOnce).unwrap();
+        }
+        let next = |topic: &str| loop {
+            let (received, payload) = messages.recv_timeout(TIMEOUT).unwrap();
+            if received == topic {
+                return payload;
+            }
+        };
+
+        // 连接后发布健康状态（此时已订阅命令主题）
+        let health = next(&config.health_topic);
+        assert_eq!(health["online"], true);
+        assert_eq!(health["status"], json!({ "method": "status" }));
+
+        hub.publish(AssistantEvent::Wake {
+            speaker: None,
+            score: Some(0.9),
+            seat: None,
+        });
+        let wake = next(&config.wake_topic);
+        assert_eq!(wake["type"], "wake");
+        assert_eq!(wake["score"].as_f64().map(|s| s as f32), Some(0.9));
+
+        peer.publish(
+            config.command_topic.as_str(),
+            QoS::AtLeastOnce,
+            false,
+            r#"{"id": 7, "method": "cancel"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            next(&config.response_topic),
+            json!({ "id": 7, "result": { "method": "cancel" } })
+        );
+
+        // 清除保留的健康状态
+        peer.publish(config.health_topic.as_str(), QoS::AtLeastOnce, true, "")
+            .unwrap();
+        thread::sleep(Duration::from_millis(100));
+    }
+}
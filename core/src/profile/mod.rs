@@ -0,0 +1,75 @@
+/*
+    用户档案
+    按驾驶员保存偏好（空调温度、语言、唤醒灵敏度）与声纹注册，重启后保留：
+    1. store：追加写入的 JSON 行文件，每条记录带结构版本号，加载时逐版本迁移，过长时压缩重写
+    2. 当前档案取已验证的说话人（没有时新建），未验证时取手动选择的档案
+*/
+
+pub mod store;
+
+use serde::{Deserialize, Serialize};
+use voice::speaker::verifier::SpeakerProfile;
+use voice::speaker::SpeakerId;
+
+pub use store::ProfileStore;
+
+/// 偏好设置（未设置的项使用全局配置）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Preferences {
+    pub temperature: Option<f32>,    // 空调温度（摄氏度）
+    pub language: Option<String>,    // 语言，如 zh-CN、en-US
+    pub wake_threshold: Option<f32>, // 唤醒灵敏度（检测阈值，越低越灵敏）
+}
+
+impl Preferences {
+    /// 合并另一份偏好中已设置的项
+    pub fn merge(&mut self, other: Preferences) {
+        if other.temperature.is_some() {
+            self.temperature = other.temperature;
+        }
+        if other.language.is_some() {
+            self.language = other.language;
+        }
+        if other.wake_threshold.is_some() {
+            self.wake_threshold = other.wake_threshold;
+        }
+    }
+}
+
+/// 声纹注册
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VoicePrint {
+    pub centroid: Vec<f32>,
+    pub enrollments: usize,
+}
+
+/// 用户档案（以说话人标识为键）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Profile {
+    pub id: String,
+    #[serde(default)]
+    pub preferences: Preferences,
+    #[serde(default)]
+    pub voice: Option<VoicePrint>,
+}
+
+impl Profile {
+    pub fn new(id: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            preferences: Preferences::default(),
+            voice: None,
+        }
+    }
+
+    /// 供说话人验证器使用的声纹档案
+    pub fn speaker_profile(&self) -> Option<SpeakerProfile> {
+        self.voice.as_ref().map(|voice| {
+            SpeakerProfile::new(
+                SpeakerId(self.id.clone()),
+                voice.centroid.clone(),
+                voice.enrollments,
+            )
+        })
+    }
+}
@@ -0,0 +1,396 @@
+use super::{Preferences, Profile};
+use anyhow::{anyhow, Context};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+/// 单步结构迁移（就地修改一条 JSON 记录）
+type Migration = fn(&mut Value) -> Result<(), anyhow::Error>;
+
+/// 结构迁移：第 i 项把版本 i+1 的记录升级到版本 i+2
+/// 修改记录结构时在末尾追加迁移函数，旧文件加载时逐版本升级
+const MIGRATIONS: &[Migration] = &[];
+
+/// 当前结构版本
+const SCHEMA_VERSION: u32 = MIGRATIONS.len() as u32 + 1;
+
+/// 压缩前允许的冗余记录数
+const COMPACT_SLACK: usize = 64;
+
+/// 文件中的一行
+#[derive(Debug, Serialize, Deserialize)]
+struct Line {
+    version: u32,
+    #[serde(flatten)]
+    record: Record,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum Record {
+    /// 档案的完整内容（覆盖旧值）
+    Put {
+        profile: Profile,
+    },
+    Delete {
+        id: String,
+    },
+    /// 手动选择的档案
+    Select {
+        id: Option<String>,
+    },
+}
+
+/// 档案存储：内存中保存当前状态，每次修改追加一条记录
+pub struct ProfileStore {
+    path: Option<PathBuf>, // 为空时仅保存在内存中（回放等）
+    file: Option<File>,
+    profiles: BTreeMap<String, Profile>,
+    selected: Option<String>,
+    records: usize, // 文件中的记录数
+}
+
+impl ProfileStore {
+    /// 仅保存在内存中的存储
+    pub fn in_memory() -> Self {
+        Self {
+            path: None,
+            file: None,
+            profiles: BTreeMap::new(),
+            selected: None,
+            records: 0,
+        }
+    }
+
+    /// 打开存储文件（不存在时创建），冗余记录过多时压缩
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, anyhow::Error> {
+        let path = path.as_ref().to_path_buf();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create {}", parent.display()))?;
+        }
+
+        let mut store = Self::in_memory();
+        let truncated = path.exists() && store.load(&path)?;
+        store.path = Some(path.clone());
+        // 丢弃了不完整的末行时必须重写，否则新记录会接在残行后面
+        if truncated || store.records > store.profiles.len() + 1 + COMPACT_SLACK {
+            store.compact()?;
+        } else {
+            store.file = Some(
+                OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&path)
+                    .with_context(|| format!("failed to open {}", path.display()))?,
+            );
+        }
+        info!(
+            profiles = store.profiles.len(),
+            selected = ?store.selected,
+            "Loaded profiles from {}",
+            path.display()
+        );
+        Ok(store)
+    }
+
+    /// 加载记录，返回是否丢弃了不完整的末行
+    fn load(&mut self, path: &Path) -> Result<bool, anyhow::Error> {
+        let file =
+            File::open(path).with_context(|| format!("failed to open {}", path.display()))?;
+        let lines: Vec<String> = BufReader::new(file).lines().collect::<Result<_, _>>()?;
+        let count = lines.len();
+        for (number, line) in lines.into_iter().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            match parse(&line) {
+                Ok(record) => {
+                    self.apply(record);
+                    self.records += 1;
+                }
+                // 写入中途断电时最后一行可能不完整，丢弃即可
+                Err(e) if number + 1 == count => {
+                    warn!(
+                        "{}:{}: dropped truncated record: {}",
+                        path.display(),
+                        number + 1,
+                        e
+                    );
+                    return Ok(true);
+                }
+                Err(e) => return Err(e.context(format!("{}:{}", path.display(), number + 1))),
+            }
+        }
+        Ok(false)
+    }
+
+    fn apply(&mut self, record: Record) {
+        match record {
+            Record::Put { profile } => {
+                self.profiles.insert(profile.id.clone(), profile);
+            }
+            Record::Delete { id } => {
+                self.profiles.remove(&id);
+                if self.selected.as_ref() == Some(&id) {
+                    self.selected = None;
+                }
+            }
+            Record::Select { id } => self.selected = id,
+        }
+    }
+
+    /// 追加记录并更新内存状态
+    fn append(&mut self, record: Record) -> Result<(), anyhow::Error> {
+        if let Some(file) = self.file.as_mut() {
+            let line = Line {
+                version: SCHEMA_VERSION,
+                record,
+            };
+            let mut bytes = serde_json::to_vec(&line)?;
+            bytes.push(b'\n');
+            file.write_all(&bytes)?;
+            file.sync_data()?;
+            self.records += 1;
+            self.apply(line.record);
+        } else {
+            self.apply(record);
+        }
+        Ok(())
+    }
+
+    /// 以当前状态重写文件（先写临时文件再替换）
+    fn compact(&mut self) -> Result<(), anyhow::Error> {
+        let Some(path) = self.path.clone() else {
+            return Ok(());
+        };
+        let temporary = path.with_extension("tmp");
+        let mut file = File::create(&temporary)?;
+        let mut records: Vec<Record> = self
+            .profiles
+            .values()
+            .map(|profile| Record::Put {
+                profile: profile.clone(),
+            })
+            .collect();
+        if self.selected.is_some() {
+            records.push(Record::Select {
+                id: self.selected.clone(),
+            });
+        }
+        self.records = records.len();
+        for record in records {
+            serde_json::to_writer(
+                &mut file,
+                &Line {
+                    version: SCHEMA_VERSION,
+                    record,
+                },
+            )?;
+            file.write_all(b"\n")?;
+        }
+        file.sync_all()?;
+        fs::rename(&temporary, &path)?;
+        self.file = Some(OpenOptions::new().append(true).open(&path)?);
+        Ok(())
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Profile> {
+        self.profiles.get(id)
+    }
+
+    pub fn profiles(&self) -> impl Iterator<Item = &Profile> {
+        self.profiles.values()
+    }
+
+    /// 手动选择的档案
+    pub fn selected(&self) -> Option<&Profile> {
+        self.selected.as_ref().and_then(|id| self.profiles.get(id))
+    }
+
+    /// 修改档案（不存在时新建）
+    pub fn update(
+        &mut self,
+        id: &str,
+        change: impl FnOnce(&mut Profile),
+    ) -> Result<&Profile, anyhow::Error> {
+        let mut profile = self
+            .profiles
+            .get(id)
+            .cloned()
+            .unwrap_or_else(|| Profile::new(id));
+        change(&mut profile);
+        self.append(Record::Put { profile })?;
+        Ok(&self.profiles[id])
+    }
+
+    /// 合并偏好设置
+    pub fn set_preferences(
+        &mut self,
+        id: &str,
+        preferences: Preferences,
+    ) -> Result<&Profile, anyhow::Error> {
+        self.update(id, |profile| profile.preferences.merge(preferences))
+    }
+
+    pub fn remove(&mut self, id: &str) -> Result<bool, anyhow::Error> {
+        if !self.profiles.contains_key(id) {
+            return Ok(false);
+        }
+        self.append(Record::Delete { id: id.to_string() })?;
+        Ok(true)
+    }
+
+    /// 选择档案，None 表示取消选择
+    pub fn select(&mut self, id: Option<&str>) -> Result<(), anyhow::Error> {
+        if let Some(id) = id {
+            if !self.profiles.contains_key(id) {
+                return Err(anyhow!("unknown profile: {}", id));
+            }
+        }
+        self.append(Record::Select {
+            id: id.map(str::to_string),
+        })
+    }
+}
+
+/// 解析一行记录，旧版本记录逐版本迁移
+fn parse(line: &str) -> Result<Record, anyhow::Error> {
+    let mut value: Value = serde_json::from_str(line)?;
+    let version = value
+        .get("version")
+        .and_then(Value::as_u64)
+        .ok_or_else(|| anyhow!("missing schema version"))? as u32;
+    if version == 0 || version > SCHEMA_VERSION {
+        return Err(anyhow!(
+            "unsupported schema version {} (current is {})",
+            version,
+            SCHEMA_VERSION
+        ));
+    }
+    for migrate in &MIGRATIONS[version as usize - 1..] {
+        migrate(&mut value)?;
+    }
+    let line: Line = serde_json::from_value(value)?;
+    Ok(line.record)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn preferences(temperature: f32) -> Preferences {
+        Preferences {
+            temperature: Some(temperature),
+            ..Preferences::default()
+        }
+    }
+
+    #[test]
+    fn persists_across_reopen() {
+        let path = testing::temp_dir("profile-reopen").join("profiles.jsonl");
+        {
+            let mut store = ProfileStore::open(&path).unwrap();
+            store.set_preferences("alice", preferences(21.0)).unwrap();
+            store
+                .set_preferences(
+                    "alice",
+                    Preferences {
+                        language: Some("zh-CN".into()),
+                        ..Preferences::default()
+                    },
+                )
+                .unwrap();
+            store.set_preferences("bob", preferences(24.0)).unwrap();
+            store.select(Some("bob")).unwrap();
+            assert!(store.select(Some("carol")).is_err());
+        }
+
+        let mut store = ProfileStore::open(&path).unwrap();
+        let alice = &store.get("alice").unwrap().preferences;
+        assert_eq!(alice.temperature, Some(21.0));
+        assert_eq!(alice.language.as_deref(), Some("zh-CN"));
+        assert_eq!(store.selected().unwrap().id, "bob");
+
+        // 删除选中的档案同时取消选择
+        assert!(store.remove("bob").unwrap());
+        assert!(!store.remove("bob").unwrap());
+        drop(store);
+        let store = ProfileStore::open(&path).unwrap();
+        assert!(store.selected().is_none());
+        assert_eq!(store.profiles().count(), 1);
+    }
+
+    #[test]
+    fn drops_truncated_record() {
+        let path = testing::temp_dir("profile-truncated").join("profiles.jsonl");
+        let mut store = ProfileStore::open(&path).unwrap();
+        store.set_preferences("alice", preferences(20.0)).unwrap();
+        drop(store);
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(br#"{"version":1,"op":"put","prof"#).unwrap();
+        drop(file);
+
+        // 残行丢弃后文件重写，新记录可正常追加
+        let mut store = ProfileStore::open(&path).unwrap();
+        store.set_preferences("bob", preferences(23.0)).unwrap();
+        drop(store);
+        let store = ProfileStore::open(&path).unwrap();
+        assert_eq!(store.profiles().count(), 2);
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 2);
+    }
+
+    #[test]
+    fn compacts_redundant_records() {
+        let path = testing::temp_dir("profile-compact").join("profiles.jsonl");
+        let mut store = ProfileStore::open(&path).unwrap();
+        for i in 0..COMPACT_SLACK + 10 {
+            store
+                .set_preferences("alice", preferences(i as f32))
+                .unwrap();
+        }
+        drop(store);
+
+        let store = ProfileStore::open(&path).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 1);
+        assert_eq!(
+            store.get("alice").unwrap().preferences.temperature,
+            Some((COMPACT_SLACK + 9) as f32)
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_versions() {
+        let put = r#""op":"put","profile":{"id":"alice"}"#;
+        assert!(parse(&format!("{{\"version\":1,{}}}", put)).is_ok());
+        assert!(parse(&format!("{{\"version\":0,{}}}", put)).is_err());
+        assert!(parse(&format!("{{\"version\":{},{}}}", SCHEMA_VERSION + 1, put)).is_err());
+        assert!(parse(&format!("{{{}}}", put)).is_err());
+
+        let path = testing::temp_dir("profile-corrupt").join("profiles.jsonl");
+        fs::write(
+            &path,
+            "garbage\n{\"version\":1,\"op\":\"select\",\"id\":null}\n",
+        )
+        .unwrap();
+        assert!(ProfileStore::open(&path).is_err());
+    }
+
+    #[test]
+    fn in_memory_store() {
+        let mut store = ProfileStore::in_memory();
+        store
+            .update("alice", |p| p.preferences.wake_threshold = Some(0.4))
+            .unwrap();
+        store.select(Some("alice")).unwrap();
+        assert_eq!(
+            store.selected().unwrap().preferences.wake_threshold,
+            Some(0.4)
+        );
+    }
+}
@@ -0,0 +1,314 @@
+use crate::api::ControlRequest;
+use crate::dialog::Recognition;
+use anyhow::{anyhow, Context};
+use gui::status::WakeStatus;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use tracing::error;
+use voice::array::DirectionOfArrival;
+use voice::asr::Transcript;
+use voice::event::wake_event::{WakeEvent, WakeInfo};
+
+/// 事件日志文件名
+pub const EVENTS_FILE: &str = "events.jsonl";
+/// 音频文件名（32 位浮点小端 PCM，事件中按样本偏移引用）
+pub const AUDIO_FILE: &str = "audio.f32";
+
+/// 日志条目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Entry {
+    pub t: f64, // 相对会话开始的秒数
+    #[serde(flatten)]
+    pub record: Record,
+}
+
+/// 跨通道的事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Record {
+    /// 会话信息（首条）
+    Session {
+        sample_rate: u32,
+        recognizer: bool,
+    },
+    AudioFrame {
+        offset: u64,
+        samples: usize,
+    },
+    WakeDetected(WakeInfo),
+    Direction(DirectionOfArrival),
+    StreamError {
+        message: String,
+    },
+    Recognition {
+        session: u64,
+        transcripts: Vec<Transcript>,
+        error: Option<String>,
+    },
+    Control {
+        request: ControlRequest,
+    },
+    /// 推送给 GUI 的状态
+    Status {
+        status: WakeStatus,
+    },
+    /// 音频通道关闭，事件循环退出
+    Shutdown,
+}
+
+/// 事件日志记录器，写入失败后停止记录
+pub struct EventLog {
+    directory: PathBuf,
+    start: Instant,
+    events: BufWriter<File>,
+    audio: BufWriter<File>,
+    samples: u64,
+    failed: bool,
+}
+
+impl EventLog {
+    /// 在目录下新建会话子目录
+    pub fn create(
+        directory: impl AsRef<Path>,
+        sample_rate: u32,
+        recognizer: bool,
+    ) -> Result<Self, anyhow::Error> {
+        let stamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis();
+        let directory = directory.as_ref().join(format!("session-{}", stamp));
+        fs::create_dir_all(&directory)
+            .with_context(|| format!("failed to create {}", directory.display()))?;
+
+        let mut log = Self {
+            events: BufWriter::new(File::create(directory.join(EVENTS_FILE))?),
+            audio: BufWriter::new(File::create(directory.join(AUDIO_FILE))?),
+            directory,
+            start: Instant::now(),
+            samples: 0,
+            failed: false,
+        };
+        let now = log.start;
+        log.record(
+            now,
+            Record::Session {
+                sample_rate,
+                recognizer,
+            },
+        );
+        Ok(log)
+    }
+
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    pub fn wake_event(&mut self, now: Instant, event: &WakeEvent) {
+        let record = match event {
+            WakeEvent::AudioFrame(data) => {
+                let offset = self.samples;
+                for sample in data {
+                    if let Err(e) = self.audio.write_all(&sample.to_le_bytes()) {
+                        self.fail(e.into());
+                        return;
+                    }
+                }
+                self.samples += data.len() as u64;
+                Record::AudioFrame {
+                    offset,
+                    samples: data.len(),
+                }
+            }
+            WakeEvent::WakeDetected(info) => Record::WakeDetected(info.clone()),
+            WakeEvent::Direction(direction) => Record::Direction(direction.clone()),
+            WakeEvent::StreamError(message) => Record::StreamError {
+                message: message.clone(),
+            },
+        };
+        self.record(now, record);
+    }
+
+    pub fn recognition(&mut self, now: Instant, recognition: &Recognition) {
+        let (transcripts, error) = match &recognition.transcripts {
+            Ok(transcripts) => (transcripts.clone(), None),
+            Err(e) => (Vec::new(), Some(e.to_string())),
+        };
+        self.record(
+            now,
+            Record::Recognition {
+                session: recognition.session,
+                transcripts,
+                error,
+            },
+        );
+    }
+
+    pub fn control(&mut self, now: Instant, request: &ControlRequest) {
+        self.record(
+            now,
+            Record::Control {
+                request: request.clone(),
+            },
+        );
+    }
+
+    pub fn status(&mut self, now: Instant, status: &WakeStatus) {
+        self.record(
+            now,
+            Record::Status {
+                status: status.clone(),
+            },
+        );
+    }
+
+    pub fn shutdown(&mut self, now: Instant) {
+        self.record(now, Record::Shutdown);
+    }
+
+    fn record(&mut self, now: Instant, record: Record) {
+        if self.failed {
+            return;
+        }
+        // 音频帧频繁，只缓冲；其余事件立即落盘，便于复现崩溃前的现场
+        let flush = !matches!(record, Record::AudioFrame { .. });
+        let entry = Entry {
+            t: now.saturating_duration_since(self.start).as_secs_f64(),
+            record,
+        };
+        let result = (|| -> Result<(), anyhow::Error> {
+            serde_json::to_writer(&mut self.events, &entry)?;
+            self.events.write_all(b"\n")?;
+            if flush {
+                self.audio.flush()?;
+                self.events.flush()?;
+            }
+            Ok(())
+        })();
+        if let Err(e) = result {
+            self.fail(e);
+        }
+    }
+
+    fn fail(&mut self, error: anyhow::Error) {
+        error!("failed to write event log, recording stopped: {:?}", error);
+        self.failed = true;
+    }
+}
+
+impl Drop for EventLog {
+    fn drop(&mut self) {
+        let _ = self.audio.flush();
+        let _ = self.events.flush();
+    }
+}
+
+/// 读取事件日志
+pub fn read_entries(directory: &Path) -> Result<Vec<Entry>, anyhow::Error> {
+    let path = directory.join(EVENTS_FILE);
+    let file = File::open(&path).with_context(|| format!("failed to open {}", path.display()))?;
+    let mut entries = Vec::new();
+    for (number, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let entry = serde_json::from_str(&line)
+            .with_context(|| format!("{}:{}: invalid entry", path.display(), number + 1))?;
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+/// 按样本偏移读取音频帧
+pub struct AudioReader {
+    file: BufReader<File>,
+    position: u64, // 当前样本位置，顺序读取时无需定位
+}
+
+impl AudioReader {
+    pub fn open(directory: &Path) -> Result<Self, anyhow::Error> {
+        let path = directory.join(AUDIO_FILE);
+        let file =
+            File::open(&path).with_context(|| format!("failed to open {}", path.display()))?;
+        Ok(Self {
+            file: BufReader::new(file),
+            position: 0,
+        })
+    }
+
+    pub fn read(&mut self, offset: u64, samples: usize) -> Result<Vec<f32>, anyhow::Error> {
+        if offset != self.position {
+            self.file.seek(SeekFrom::Start(offset * 4))?;
+        }
+        let mut bytes = vec![0u8; samples * 4];
+        self.file
+            .read_exact(&mut bytes)
+            .map_err(|_| anyhow!("audio frame at {} is truncated", offset))?;
+        self.position = offset + samples as u64;
+        Ok(bytes
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    #[test]
+    fn audio_is_read_by_offset() {
+        let directory = testing::temp_dir("replay-log-audio");
+        let start = Instant::now();
+        let session = {
+            let mut log = EventLog::create(&directory, 16000, false).unwrap();
+            log.wake_event(start, &WakeEvent::AudioFrame(vec![0.1, 0.2]));
+            log.wake_event(start, &WakeEvent::AudioFrame(vec![0.3, 0.4, 0.5]));
+            log.shutdown(start);
+            log.directory().to_path_buf()
+        };
+
+        let entries = read_entries(&session).unwrap();
+        assert!(matches!(
+            entries[0].record,
+            Record::Session {
+                sample_rate: 16000,
+                recognizer: false
+            }
+        ));
+        assert!(matches!(
+            entries[2].record,
+            Record::AudioFrame {
+                offset: 2,
+                samples: 3
+            }
+        ));
+        assert!(matches!(entries[3].record, Record::Shutdown));
+
+        let mut audio = AudioReader::open(&session).unwrap();
+        assert_eq!(audio.read(2, 3).unwrap(), [0.3, 0.4, 0.5]);
+        assert_eq!(audio.read(0, 2).unwrap(), [0.1, 0.2]);
+        assert_eq!(
+            audio.read(4, 2).unwrap_err().to_string(),
+            "audio frame at 4 is truncated"
+        );
+    }
+
+    #[test]
+    fn reports_invalid_lines() {
+        let directory = testing::temp_dir("replay-log-invalid");
+        fs::write(
+            directory.join(EVENTS_FILE),
+            "{\"t\":0.0,\"type\":\"shutdown\"}\n\n{\"t\":1.0,\"type\":\"unknown\"}\n",
+        )
+        .unwrap();
+        let error = read_entries(&directory).unwrap_err().to_string();
+        assert!(
+            error.ends_with("events.jsonl:3: invalid entry"),
+            "{}",
+            error
+        );
+    }
+}
@@ -0,0 +1,286 @@
+/*
+    事件记录与回放
+    1. log：事件循环把跨通道的事件（音频帧按偏移引用、唤醒事件、识别结果、控制请求、GUI 状态）写入带时间戳的日志
+    2. 回放：按日志的虚拟时间把事件重新送入事件处理，比较产生的 GUI 状态序列与记录是否一致
+*/
+
+pub mod log;
+
+use crate::api::EventHub;
+use crate::dialog::{Recognition, Services};
+use crate::event::{EventHandler, TICK};
+use crate::tts::SpeechQueue;
+use anyhow::anyhow;
+use crossbeam_channel::{unbounded, Receiver};
+use gui::status::WakeStatus;
+use log::{AudioReader, Record};
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tracing::info;
+use voice::asr::{AsrBackend, Transcript};
+use voice::event::wake_event::WakeEvent;
+
+/// 回放时的识别后端：不产生结果，识别结果来自日志
+struct ReplayRecognizer;
+
+impl AsrBackend for ReplayRecognizer {
+    fn start(&mut self, _sample_rate: u32) -> Result<(), anyhow::Error> {
+        Ok(())
+    }
+
+    fn feed(&mut self, _pcm: &[f32]) -> Result<Vec<Transcript>, anyhow::Error> {
+        Ok(Vec::new())
+    }
+
+    fn finish(&mut self) -> Result<Vec<Transcript>, anyhow::Error> {
+        Ok(Vec::new())
+    }
+}
+
+/// 回放结果
+pub struct ReplayReport {
+    pub events: usize,
+    pub expected: Vec<(f64, WakeStatus)>, // 记录的状态切换
+    pub actual: Vec<(f64, WakeStatus)>,   // 回放产生的状态切换
+}
+
+impl ReplayReport {
+    /// 第一个不一致的状态切换序号
+    pub fn divergence(&self) -> Option<usize> {
+        let common = self.expected.len().min(self.actual.len());
+        (0..common)
+            .find(|&i| self.expected[i].1 != self.actual[i].1)
+            .or((self.expected.len() != self.actual.len()).then_some(common))
+    }
+}
+
+/// 按虚拟时间回放会话目录中的事件日志，音频按记录时的采样率处理
+pub fn replay(directory: &Path) -> Result<ReplayReport, anyhow::Error> {
+    let entries = log::read_entries(directory)?;
+    let mut audio = AudioReader::open(directory)?;
+    let sample_rate = match entries.first().map(|entry| &entry.record) {
+        Some(Record::Session { sample_rate, .. }) => *sample_rate,
+        _ => return Err(anyhow!("event log does not start with a session record")),
+    };
+
+    // 回放使用模拟总线与内存中的档案、定时器，不录音也不播报
+    let (gui_sender, statuses) = unbounded();
+    let start = Instant::now();
+    let mut handler = EventHandler::new(
+        gui_sender,
+        EventHub::default(),
+        Services::in_memory(start),
+        None,
+        sample_rate,
+    )?;
+    handler.dialog.set_speech(SpeechQueue::disabled());
+
+    let mut clock = Duration::ZERO;
+    let mut report = ReplayReport {
+        events: 0,
+        expected: Vec::new(),
+        actual: Vec::new(),
+    };
+    for entry in entries {
+        let time = Duration::from_secs_f64(entry.t);
+        // 两个事件之间按事件循环的周期检查超时
+        while clock + TICK < time {
+            clock += TICK;
+            handler.tick(start + clock);
+            collect(&statuses, clock, &mut report.actual);
+        }
+        clock = clock.max(time);
+        let now = start + clock;
+
+        match entry.record {
+            Record::Session { recognizer, .. } => {
+                let backend: Box<dyn AsrBackend> = Box::new(ReplayRecognizer);
+                handler.dialog.set_recognizer(recognizer.then_some(backend));
+                continue;
+            }
+            Record::Status { status } => {
+                report.expected.push((entry.t, status));
+                continue;
+            }
+            Record::AudioFrame { offset, samples } => {
+                let frame = audio.read(offset, samples)?;
+                handler.handle(WakeEvent::AudioFrame(frame), now);
+            }
+            Record::WakeDetected(info) => handler.handle(WakeEvent::WakeDetected(info), now),
+            Record::Direction(direction) => handler.handle(WakeEvent::Direction(direction), now),
+            Record::StreamError { message } => handler.handle(WakeEvent::StreamError(message), now),
+            Record::Recognition {
+                session,
+                transcripts,
+                error,
+            } => {
+                let recognition = Recognition {
+                    session,
+                    backend: Box::new(ReplayRecognizer),
+                    transcripts: match error {
+                        Some(error) => Err(anyhow!(error)),
+                        None => Ok(transcripts),
+                    },
+                };
+                handler.dialog.on_recognition(recognition, now);
+            }
+            Record::Control { request } => {
+                let _ = handler.control(request, now);
+            }
+            Record::Shutdown => handler.dialog.cancel(now),
+        }
+        report.events += 1;
+        handler.tick(now);
+        collect(&statuses, clock, &mut report.actual);
+    }
+    Ok(report)
+}
+
+fn collect(statuses: &Receiver<WakeStatus>, clock: Duration, actual: &mut Vec<(f64, WakeStatus)>) {
+    actual.extend(statuses.try_iter().map(|s| (clock.as_secs_f64(), s)));
+}
+
+/// 回放并校验状态切换，不一致时返回错误
+pub fn run(directory: &Path) -> Result<(), anyhow::Error> {
+    let report = replay(directory)?;
+    info!(
+        events = report.events,
+        transitions = report.actual.len(),
+        "Replay finished"
+    );
+    match report.divergence() {
+        None => {
+            info!("Replay matches recording");
+            Ok(())
+        }
+        Some(index) => {
+            let expected = report.expected.get(index);
+            let actual = report.actual.get(index);
+            Err(anyhow!(
+                "transition #{} diverged: expected {:?}, got {:?}",
+                index + 1,
+                expected,
+                actual
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::log::{EventLog, EVENTS_FILE};
+    use super::*;
+    use crate::api::ControlRequest;
+    use crate::testing::{self, SAMPLE_RATE};
+    use std::fs;
+    use std::path::PathBuf;
+
+    /// 模拟事件循环录制一段会话：静音中手动唤醒、超时，再执行一条文本指令
+    fn record_session(name: &str) -> PathBuf {
+        testing::settings();
+        let mut log = EventLog::create(testing::temp_dir(name), SAMPLE_RATE, false).unwrap();
+        let (gui_sender, statuses) = unbounded();
+        let start = Instant::now();
+        let mut handler = EventHandler::new(
+            gui_sender,
+            EventHub::default(),
+            Services::in_memory(start),
+            None,
+            SAMPLE_RATE,
+        )
+        .unwrap();
+        handler.dialog.set_speech(SpeechQueue::disabled());
+
+        // 100ms一帧
+        let frame = SAMPLE_RATE as usize / 10;
+        for i in 0..80 {
+            let now = start + Duration::from_millis(100 * i);
+            let request = match i {
+                5 => Some(ControlRequest::StartListening),
+                70 => Some(ControlRequest::Execute {
+                    text: "空调二十二度".to_string(),
+                }),
+                _ => None,
+            };
+            if let Some(request) = request {
+                log.control(now, &request);
+                let _ = handler.control(request, now);
+            }
+            let event = WakeEvent::AudioFrame(vec![0.0; frame]);
+            log.wake_event(now, &event);
+            handler.handle(event, now);
+            handler.tick(now);
+            for status in statuses.try_iter() {
+                log.status(now, &status);
+            }
+        }
+        let now = start + Duration::from_secs(8);
+        log.shutdown(now);
+        handler.dialog.cancel(now);
+        for status in statuses.try_iter() {
+            log.status(now, &status);
+        }
+        log.directory().to_path_buf()
+    }
+
+    #[test]
+    fn replay_matches_recording() {
+        let directory = record_session("replay-match");
+        let report = replay(&directory).unwrap();
+        assert!(report.expected.len() >= 4, "{:?}", report.expected);
+        assert_eq!(report.divergence(), None);
+        assert!(run(&directory).is_ok());
+    }
+
+    #[test]
+    fn reports_changed_transition() {
+        let directory = record_session("replay-diverge");
+        let path = directory.join(EVENTS_FILE);
+
+        // 篡改第二个状态切换
+        let mut statuses = 0;
+        let lines: Vec<String> = fs::read_to_string(&path)
+            .unwrap()
+            .lines()
+            .map(|line| {
+                let mut entry: log::Entry = serde_json::from_str(line).unwrap();
+                if let Record::Status { status } = &mut entry.record {
+                    statuses += 1;
+                    if statuses == 2 {
+                        *status = WakeStatus::Responding {
+                            text: "changed".to_string(),
+                        };
+                    }
+                }
+                serde_json::to_string(&entry).unwrap()
+            })
+            .collect();
+        fs::write(&path, lines.join("\n")).unwrap();
+
+        let report = replay(&directory).unwrap();
+        assert_eq!(report.divergence(), Some(1));
+        assert!(run(&directory).is_err());
+    }
+
+    #[test]
+    fn requires_session_record() {
+        let directory = testing::temp_dir("replay-no-session");
+        fs::write(
+            directory.join(EVENTS_FILE),
+            "{\"t\":0.0,\"type\":\"shutdown\"}\n",
+        )
+        .unwrap();
+        fs::write(directory.join(log::AUDIO_FILE), []).unwrap();
+        assert!(replay(&directory).is_err());
+    }
+
+    #[test]
+    fn divergence_of_different_lengths() {
+        let report = ReplayReport {
+            events: 0,
+            expected: vec![(0.0, WakeStatus::Idle), (1.0, WakeStatus::Recognizing)],
+            actual: vec![(0.0, WakeStatus::Idle)],
+        };
+        assert_eq!(report.divergence(), Some(1));
+    }
+}
@@ -0,0 +1,69 @@
+use super::{Skill, SkillContext};
+use crate::dialog::CommandRequest;
+use crate::intent::command::Zone;
+use crate::intent::VehicleCommand;
+use anyhow::anyhow;
+use tracing::warn;
+use voice::array::Seat;
+
+/// 空调温度（设定的温度记入当前用户档案，可按档案恢复）
+pub struct ClimateSkill;
+
+impl Skill for ClimateSkill {
+    fn name(&self) -> &str {
+        "climate"
+    }
+
+    fn intents(&self) -> &[&str] {
+        &["set_temperature", "preferred_temperature"]
+    }
+
+    fn handle(
+        &mut self,
+        request: &CommandRequest,
+        context: &mut SkillContext,
+    ) -> Result<String, anyhow::Error> {
+        if request.intent == "preferred_temperature" {
+            let preferred = context
+                .profile
+                .and_then(|id| context.profiles.get(id))
+                .and_then(|profile| profile.preferences.temperature);
+            let Some(celsius) = preferred else {
+                return Ok("I don't know your preferred temperature yet".to_string());
+            };
+            context.vehicle.execute(&VehicleCommand::SetTemperature {
+                zone: Zone::All,
+                celsius,
+            })?;
+            return Ok(format!(
+                "Setting temperature to your preferred {} degrees",
+                celsius
+            ));
+        }
+
+        let Some(VehicleCommand::SetTemperature { zone, celsius }) = request.command else {
+            return Err(anyhow!("unexpected request: {:?}", request.command));
+        };
+        // 未说明区域时调节说话人所在的座位
+        let zone = match request.seat {
+            Some(Seat::Driver) if !request.slots.contains_key("zone") => Zone::Driver,
+            Some(Seat::Passenger) if !request.slots.contains_key("zone") => Zone::Passenger,
+            _ => zone,
+        };
+        context
+            .vehicle
+            .execute(&VehicleCommand::SetTemperature { zone, celsius })?;
+        if let Some(id) = context.profile {
+            if let Err(e) = context.profiles.update(id, |profile| {
+                profile.preferences.temperature = Some(celsius)
+            }) {
+                warn!("failed to save preferred temperature: {:?}", e);
+            }
+        }
+        Ok(format!(
+            "Setting {} temperature to {} degrees",
+            zone.label(),
+            celsius
+        ))
+    }
+}
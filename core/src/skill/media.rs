@@ -0,0 +1,29 @@
+use super::{Skill, SkillContext};
+use crate::dialog::CommandRequest;
+use crate::intent::VehicleCommand;
+use anyhow::anyhow;
+
+/// 媒体播放控制（经车身总线发送到主机）
+pub struct MediaSkill;
+
+impl Skill for MediaSkill {
+    fn name(&self) -> &str {
+        "media"
+    }
+
+    fn intents(&self) -> &[&str] {
+        &["media"]
+    }
+
+    fn handle(
+        &mut self,
+        request: &CommandRequest,
+        context: &mut SkillContext,
+    ) -> Result<String, anyhow::Error> {
+        let Some(command @ VehicleCommand::Media { action }) = &request.command else {
+            return Err(anyhow!("unexpected request: {:?}", request.command));
+        };
+        context.vehicle.execute(command)?;
+        Ok(format!("Media: {}", action.label()))
+    }
+}
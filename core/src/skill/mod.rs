@@ -0,0 +1,270 @@
+/*
+    技能注册表
+    技能声明自己处理的意图，执行时获得上下文（车辆总线、音频输出、配置）并返回回复文本。
+    启动时按配置中的技能名注册，新能力（天气、媒体、空调等）可独立开发，
+    技能自带的语法文件放在配置的语法目录中即可被意图解析器加载
+*/
+
+mod climate;
+mod media;
+mod privacy;
+mod timer;
+mod vehicle_status;
+mod window;
+
+use crate::dialog::CommandRequest;
+use crate::profile::ProfileStore;
+use crate::timer::Scheduler;
+use crate::vehicle::VehicleController;
+use anyhow::anyhow;
+use std::collections::HashMap;
+use std::time::Instant;
+use tracing::{error, info};
+use voice::config::Settings;
+use voice::privacy::PrivacySwitch;
+
+/// 音频输出（提示音、语音播报等）
+pub trait AudioSink: Send {
+    fn play(&mut self, samples: &[f32], sample_rate: u32) -> Result<(), anyhow::Error>;
+}
+
+/// 丢弃所有音频的输出
+pub struct NullSink;
+
+impl AudioSink for NullSink {
+    fn play(&mut self, _samples: &[f32], _sample_rate: u32) -> Result<(), anyhow::Error> {
+        Ok(())
+    }
+}
+
+/// 技能执行上下文
+pub struct SkillContext<'a> {
+    pub vehicle: &'a mut VehicleController,
+    // 内置技能暂未用到音频输出与配置，供独立开发的技能使用
+    #[allow(dead_code)]
+    pub audio: &'a mut dyn AudioSink,
+    #[allow(dead_code)]
+    pub settings: &'a Settings,
+    pub profiles: &'a mut ProfileStore,
+    pub profile: Option<&'a str>, // 当前用户档案
+    pub timers: &'a mut Scheduler,
+    pub privacy: &'a PrivacySwitch,
+    pub now: Instant, // 当前时间（回放时为虚拟时间）
+}
+
+/// 技能
+pub trait Skill: Send {
+    fn name(&self) -> &str;
+
+    /// 处理的意图名
+    fn intents(&self) -> &[&str];
+
+    /// 执行请求，返回回复文本
+    fn handle(
+        &mut self,
+        request: &CommandRequest,
+        context: &mut SkillContext,
+    ) -> Result<String, anyhow::Error>;
+}
+
+/// 按名称创建内置技能
+pub(crate) fn builtin(name: &str) -> Option<Box<dyn Skill>> {
+    Some(match name {
+        "climate" => Box::new(climate::ClimateSkill),
+        "window" => Box::new(window::WindowSkill),
+        "media" => Box::new(media::MediaSkill),
+        "vehicle_status" => Box::new(vehicle_status::VehicleStatusSkill),
+        "timer" => Box::new(timer::TimerSkill),
+        "privacy" => Box::new(privacy::PrivacySkill),
+        _ => return None,
+    })
+}
+
+/// 技能注册表：意图 → 技能
+#[derive(Default)]
+pub struct SkillRegistry {
+    skills: Vec<Box<dyn Skill>>,
+    routes: HashMap<String, usize>,
+}
+
+impl SkillRegistry {
+    /// 按配置的技能名注册内置技能，未知技能跳过
+    pub fn from_names(names: &[String]) -> Self {
+        let mut registry = Self::default();
+        for name in names {
+            let result = match builtin(name) {
+                Some(skill) => registry.register(skill),
+                None => Err(anyhow!("unknown skill: {}", name)),
+            };
+            if let Err(e) = result {
+                error!("failed to register skill: {:?}", e);
+            }
+        }
+        registry
+    }
+
+    /// 注册技能，意图不能与已注册技能重复
+    pub fn register(&mut self, skill: Box<dyn Skill>) -> Result<(), anyhow::Error> {
+        if let Some(intent) = skill
+            .intents()
+            .iter()
+            .find(|intent| self.routes.contains_key(**intent))
+        {
+            let owner = &self.skills[self.routes[*intent]];
+            return Err(anyhow!(
+                "intent {} of skill {} is already handled by {}",
+                intent,
+                skill.name(),
+                owner.name()
+            ));
+        }
+
+        let index = self.skills.len();
+        for intent in skill.intents() {
+            self.routes.insert(intent.to_string(), index);
+        }
+        info!("Skill registered: {} {:?}", skill.name(), skill.intents());
+        self.skills.push(skill);
+        Ok(())
+    }
+
+    /// 分发请求，没有技能处理该意图时返回 None
+    pub fn dispatch(
+        &mut self,
+        request: &CommandRequest,
+        context: &mut SkillContext,
+    ) -> Option<Result<String, anyhow::Error>> {
+        let index = *self.routes.get(&request.intent)?;
+        Some(self.skills[index].handle(request, context))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intent::command::{MediaAction, WindowAction, WindowPosition, Zone};
+    use crate::intent::VehicleCommand;
+    use crate::vehicle::codec::Codec;
+    use crate::vehicle::mock::MockBus;
+    use voice::array::Seat;
+
+    fn request(intent: &str, command: VehicleCommand) -> CommandRequest {
+        CommandRequest {
+            intent: intent.to_string(),
+            slots: HashMap::new(),
+            command: Some(command),
+            speaker: None,
+            seat: None,
+            transcript: String::new(),
+        }
+    }
+
+    /// 以默认技能执行请求，返回回复
+    fn respond(request: &CommandRequest) -> String {
+        let mut registry = SkillRegistry::from_names(&Settings::default().skills);
+        let mut vehicle = VehicleController::new(Box::new(MockBus::new()), Codec::default());
+        let mut profiles = ProfileStore::in_memory();
+        let mut timers = Scheduler::in_memory(Instant::now());
+        let mut context = SkillContext {
+            vehicle: &mut vehicle,
+            audio: &mut NullSink,
+            settings: &Settings::default(),
+            profiles: &mut profiles,
+            profile: None,
+            timers: &mut timers,
+            privacy: &PrivacySwitch::default(),
+            now: Instant::now(),
+        };
+        registry
+            .dispatch(request, &mut context)
+            .expect("no skill for intent")
+            .unwrap()
+    }
+
+    #[test]
+    fn responses_use_readable_names() {
+        let cases = [
+            (
+                request(
+                    "set_temperature",
+                    VehicleCommand::SetTemperature {
+                        zone: Zone::Driver,
+                        celsius: 22.0,
+                    },
+                ),
+                "Setting driver temperature to 22 degrees",
+            ),
+            (
+                request(
+                    "set_temperature",
+                    VehicleCommand::SetTemperature {
+                        zone: Zone::All,
+                        celsius: 24.5,
+                    },
+                ),
+                "Setting cabin temperature to 24.5 degrees",
+            ),
+            (
+                request(
+                    "window",
+                    VehicleCommand::Window {
+                        which: WindowPosition::FrontLeft,
+                        action: WindowAction::Open,
+                    },
+                ),
+                "Opening the front left window",
+            ),
+            (
+                request(
+                    "window",
+                    VehicleCommand::Window {
+                        which: WindowPosition::All,
+                        action: WindowAction::Close,
+                    },
+                ),
+                "Closing all windows",
+            ),
+            (
+                request(
+                    "media",
+                    VehicleCommand::Media {
+                        action: MediaAction::VolumeUp,
+                    },
+                ),
+                "Media: volume up",
+            ),
+        ];
+        for (request, expected) in cases {
+            assert_eq!(respond(&request), expected);
+        }
+    }
+
+    #[test]
+    fn climate_defaults_to_speaker_seat() {
+        let command = VehicleCommand::SetTemperature {
+            zone: Zone::All,
+            celsius: 22.0,
+        };
+        let mut from_seat = request("set_temperature", command.clone());
+        from_seat.seat = Some(Seat::Passenger);
+        assert_eq!(
+            respond(&from_seat),
+            "Setting passenger temperature to 22 degrees"
+        );
+
+        // 说明了区域时以说明的为准
+        let mut spoken = from_seat.clone();
+        spoken.slots.insert("zone".into(), "all".into());
+        assert_eq!(respond(&spoken), "Setting cabin temperature to 22 degrees");
+    }
+
+    #[test]
+    fn registry_routes() {
+        let mut registry =
+            SkillRegistry::from_names(&["climate".to_string(), "unknown".to_string()]);
+        assert_eq!(registry.routes.len(), 2);
+        assert!(registry.register(builtin("climate").unwrap()).is_err());
+        assert!(registry.register(builtin("window").unwrap()).is_ok());
+        assert!(builtin("weather").is_none());
+    }
+}
@@ -0,0 +1,24 @@
+use super::{Skill, SkillContext};
+use crate::dialog::CommandRequest;
+
+/// 隐私模式（语音只能开启，关闭需经屏幕或外部接口）
+pub struct PrivacySkill;
+
+impl Skill for PrivacySkill {
+    fn name(&self) -> &str {
+        "privacy"
+    }
+
+    fn intents(&self) -> &[&str] {
+        &["privacy_mode"]
+    }
+
+    fn handle(
+        &mut self,
+        _request: &CommandRequest,
+        context: &mut SkillContext,
+    ) -> Result<String, anyhow::Error> {
+        context.privacy.set(true);
+        Ok("Privacy mode on, the microphone is off".to_string())
+    }
+}
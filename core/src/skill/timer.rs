@@ -0,0 +1,115 @@
+use super::{Skill, SkillContext};
+use crate::dialog::CommandRequest;
+use crate::intent::parse_number;
+use crate::timer::describe;
+use anyhow::anyhow;
+use std::time::Duration;
+
+/// 定时器与提醒
+pub struct TimerSkill;
+
+impl Skill for TimerSkill {
+    fn name(&self) -> &str {
+        "timer"
+    }
+
+    fn intents(&self) -> &[&str] {
+        &["set_reminder", "set_timer", "list_timers", "clear_timers"]
+    }
+
+    fn handle(
+        &mut self,
+        request: &CommandRequest,
+        context: &mut SkillContext,
+    ) -> Result<String, anyhow::Error> {
+        let owner = context.profile.map(str::to_string);
+        match request.intent.as_str() {
+            "set_reminder" => {
+                let length = length(request)?;
+                let message = request
+                    .slots
+                    .get("message")
+                    .cloned()
+                    .ok_or_else(|| anyhow!("missing reminder message"))?;
+                if request.slots.contains_key("repeat") {
+                    context.timers.add(
+                        Some(message.clone()),
+                        length,
+                        Some(length),
+                        owner,
+                        context.now,
+                    )?;
+                    Ok(format!(
+                        "OK, I'll remind you to {} every {}",
+                        message,
+                        describe(length)
+                    ))
+                } else {
+                    context
+                        .timers
+                        .add(Some(message.clone()), length, None, owner, context.now)?;
+                    Ok(format!(
+                        "OK, I'll remind you to {} in {}",
+                        message,
+                        describe(length)
+                    ))
+                }
+            }
+            "set_timer" => {
+                let length = length(request)?;
+                context.timers.add(None, length, None, owner, context.now)?;
+                Ok(format!("Timer set for {}", describe(length)))
+            }
+            "list_timers" => {
+                let timers = context.timers.timers();
+                if timers.is_empty() {
+                    return Ok("You have no timers or reminders".to_string());
+                }
+                let items: Vec<String> = timers
+                    .iter()
+                    .map(|timer| {
+                        // 一分钟以上按分钟取整播报
+                        let remaining = context.timers.remaining(timer, context.now).as_secs();
+                        let remaining = describe(Duration::from_secs(if remaining < 60 {
+                            remaining.max(1)
+                        } else {
+                            remaining.div_ceil(60) * 60
+                        }));
+                        match &timer.message {
+                            Some(message) => format!("a reminder to {} in {}", message, remaining),
+                            None => format!("a timer ending in {}", remaining),
+                        }
+                    })
+                    .collect();
+                Ok(format!("You have {}", items.join(", ")))
+            }
+            "clear_timers" => {
+                let count = context.timers.clear()?;
+                Ok(format!("Cleared {} timers and reminders", count))
+            }
+            intent => Err(anyhow!("unexpected intent: {}", intent)),
+        }
+    }
+}
+
+/// 由数量与单位槽位计算时长（未说数量时为 1）
+fn length(request: &CommandRequest) -> Result<Duration, anyhow::Error> {
+    let amount = match request.slots.get("amount") {
+        Some(amount) => {
+            parse_number(amount).ok_or_else(|| anyhow!("invalid amount: {}", amount))?
+        }
+        None => 1.0,
+    };
+    let unit = match request.slots.get("unit").map(String::as_str) {
+        Some("second") => 1.0,
+        Some("minute") => 60.0,
+        Some("hour") => 3600.0,
+        Some("day") => 86400.0,
+        unit => return Err(anyhow!("invalid time unit: {:?}", unit)),
+    };
+    let seconds = amount * unit;
+    if !(1.0..=366.0 * 86400.0).contains(&seconds) {
+        return Err(anyhow!("timer length out of range: {} seconds", seconds));
+    }
+    Ok(Duration::from_secs_f32(seconds))
+}
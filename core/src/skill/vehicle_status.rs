@@ -0,0 +1,30 @@
+use super::{Skill, SkillContext};
+use crate::dialog::CommandRequest;
+use crate::intent::VehicleCommand;
+use anyhow::anyhow;
+
+/// 车况查询（油量、车速、故障码等）
+pub struct VehicleStatusSkill;
+
+impl Skill for VehicleStatusSkill {
+    fn name(&self) -> &str {
+        "vehicle_status"
+    }
+
+    fn intents(&self) -> &[&str] {
+        &["query"]
+    }
+
+    fn handle(
+        &mut self,
+        request: &CommandRequest,
+        context: &mut SkillContext,
+    ) -> Result<String, anyhow::Error> {
+        let Some(VehicleCommand::Query { item }) = &request.command else {
+            return Err(anyhow!("unexpected request: {:?}", request.command));
+        };
+        // 回答前读取最新的状态帧
+        context.vehicle.poll()?;
+        Ok(context.vehicle.state().describe(*item))
+    }
+}
@@ -0,0 +1,37 @@
+use super::{Skill, SkillContext};
+use crate::dialog::CommandRequest;
+use crate::intent::command::{WindowAction, WindowPosition};
+use crate::intent::VehicleCommand;
+use anyhow::anyhow;
+
+/// 车窗
+pub struct WindowSkill;
+
+impl Skill for WindowSkill {
+    fn name(&self) -> &str {
+        "window"
+    }
+
+    fn intents(&self) -> &[&str] {
+        &["window"]
+    }
+
+    fn handle(
+        &mut self,
+        request: &CommandRequest,
+        context: &mut SkillContext,
+    ) -> Result<String, anyhow::Error> {
+        let Some(command @ VehicleCommand::Window { which, action }) = &request.command else {
+            return Err(anyhow!("unexpected request: {:?}", request.command));
+        };
+        context.vehicle.execute(command)?;
+        let action = match action {
+            WindowAction::Open => "Opening",
+            WindowAction::Close => "Closing",
+        };
+        Ok(match which {
+            WindowPosition::All => format!("{} all windows", action),
+            which => format!("{} the {} window", action, which.label()),
+        })
+    }
+}
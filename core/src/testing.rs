@@ -0,0 +1,43 @@
+/*
+    测试公用的进程配置
+    配置在进程内只能初始化一次，需要配置的测试共用同一份：
+    唤醒模版写入临时目录，阈值设为不会触发，不播报、不监视模型文件、不写状态文件
+*/
+
+use std::fs;
+use std::sync::Once;
+use voice::config::Settings;
+
+/// 测试使用的采样率
+pub const SAMPLE_RATE: u32 = 16000;
+
+pub fn settings() -> Settings {
+    static INIT: Once = Once::new();
+    INIT.call_once(|| {
+        let directory = std::env::temp_dir().join(format!("core-test-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let template = directory.join("wake.bin");
+        let bytes: Vec<u8> = [1.0f32; 13].iter().flat_map(|v| v.to_le_bytes()).collect();
+        fs::write(&template, bytes).unwrap();
+
+        Settings::init(Settings {
+            sample_rate: SAMPLE_RATE,
+            wakeword_path: template.to_string_lossy().into_owned(),
+            wake_threshold: 2.0,
+            reject_threshold: 2.0,
+            wakeword_reload_secs: None,
+            tts_command: None,
+            speech_output: false,
+            ..Settings::default()
+        });
+    });
+    Settings::load()
+}
+
+/// 测试专用的临时目录（已存在时清空）
+pub fn temp_dir(name: &str) -> std::path::PathBuf {
+    let directory = std::env::temp_dir().join(format!("core-test-{}-{}", std::process::id(), name));
+    let _ = fs::remove_dir_all(&directory);
+    fs::create_dir_all(&directory).unwrap();
+    directory
+}
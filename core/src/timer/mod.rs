@@ -0,0 +1,303 @@
+/*
+    定时器与提醒
+    1. 一次性与周期性定时器由意图创建，保存到文件（按墙上时间），重启后恢复
+    2. 对话空闲时检查到期的定时器，经 GUI 与音频输出提醒；离线期间错过的周期只提醒一次
+*/
+
+use anyhow::{anyhow, Context};
+use serde::{Deserialize, Serialize};
+use std::f32::consts::PI;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tracing::{error, info};
+
+/// 定时器
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Timer {
+    pub id: u64,
+    pub message: Option<String>,  // 提醒内容，计时器为空
+    pub due_ms: u64,              // 到期时间（Unix 毫秒）
+    pub repeat_secs: Option<u64>, // 重复间隔，一次性定时器为空
+    pub length_secs: u64,         // 设定的时长（用于播报）
+    pub owner: Option<String>,    // 创建者的用户档案
+}
+
+impl Timer {
+    /// 提醒文本
+    pub fn announcement(&self) -> String {
+        match &self.message {
+            Some(message) => format!("Reminder: {}", message),
+            None => format!(
+                "Your {} timer is done",
+                describe(Duration::from_secs(self.length_secs))
+            ),
+        }
+    }
+}
+
+/// 保存的文件内容
+#[derive(Default, Serialize, Deserialize)]
+struct State {
+    next_id: u64,
+    timers: Vec<Timer>,
+}
+
+/// 定时器调度（时间以对话使用的 Instant 表示，保存时换算为墙上时间）
+pub struct Scheduler {
+    path: Option<PathBuf>, // 为空时仅保存在内存中（回放等）
+    state: State,
+    started: Instant,
+    epoch: SystemTime, // 与 started 对应的墙上时间
+}
+
+impl Scheduler {
+    pub fn in_memory(now: Instant) -> Self {
+        Self {
+            path: None,
+            state: State {
+                next_id: 1,
+                timers: Vec::new(),
+            },
+            started: now,
+            epoch: SystemTime::now(),
+        }
+    }
+
+    /// 从文件恢复（文件不存在时为空）
+    pub fn open(path: impl AsRef<Path>, now: Instant) -> Result<Self, anyhow::Error> {
+        let path = path.as_ref().to_path_buf();
+        let mut scheduler = Self::in_memory(now);
+        if path.exists() {
+            let source = fs::read_to_string(&path)
+                .with_context(|| format!("failed to read {}", path.display()))?;
+            scheduler.state = serde_json::from_str(&source)
+                .with_context(|| format!("invalid timer file: {}", path.display()))?;
+            scheduler.state.next_id = scheduler.state.next_id.max(1);
+        }
+        info!(
+            timers = scheduler.state.timers.len(),
+            "Loaded timers from {}",
+            path.display()
+        );
+        scheduler.path = Some(path);
+        Ok(scheduler)
+    }
+
+    pub fn timers(&self) -> &[Timer] {
+        &self.state.timers
+    }
+
+    /// 新建定时器，repeat 为重复间隔
+    pub fn add(
+        &mut self,
+        message: Option<String>,
+        delay: Duration,
+        repeat: Option<Duration>,
+        owner: Option<String>,
+        now: Instant,
+    ) -> Result<&Timer, anyhow::Error> {
+        if delay.is_zero() || repeat.is_some_and(|r| r.is_zero()) {
+            return Err(anyhow!("timer length must be positive"));
+        }
+        let timer = Timer {
+            id: self.state.next_id,
+            message,
+            due_ms: self.unix_millis(now) + delay.as_millis() as u64,
+            repeat_secs: repeat.map(|r| r.as_secs().max(1)),
+            length_secs: delay.as_secs(),
+            owner,
+        };
+        self.state.next_id += 1;
+        self.state.timers.push(timer);
+        self.save()?;
+        Ok(self.state.timers.last().unwrap())
+    }
+
+    /// 删除全部定时器，返回删除的数量
+    pub fn clear(&mut self) -> Result<usize, anyhow::Error> {
+        let count = self.state.timers.len();
+        self.state.timers.clear();
+        self.save()?;
+        Ok(count)
+    }
+
+    /// 到下次到期的时长
+    pub fn remaining(&self, timer: &Timer, now: Instant) -> Duration {
+        Duration::from_millis(timer.due_ms.saturating_sub(self.unix_millis(now)))
+    }
+
+    /// 取出到期的定时器，周期性定时器顺延到下一个未来的时间点
+    pub fn take_due(&mut self, now: Instant) -> Vec<Timer> {
+        let current = self.unix_millis(now);
+        if !self.state.timers.iter().any(|t| t.due_ms <= current) {
+            return Vec::new();
+        }
+        let mut due = Vec::new();
+        self.state.timers.retain_mut(|timer| {
+            if timer.due_ms > current {
+                return true;
+            }
+            due.push(timer.clone());
+            match timer.repeat_secs {
+                Some(repeat) => {
+                    let repeat = repeat * 1000;
+                    let missed = (current - timer.due_ms) / repeat + 1;
+                    timer.due_ms += missed * repeat;
+                    true
+                }
+                None => false,
+            }
+        });
+        if let Err(e) = self.save() {
+            error!("failed to save timers: {:?}", e);
+        }
+        due
+    }
+
+    fn unix_millis(&self, now: Instant) -> u64 {
+        let wall = self.epoch + now.saturating_duration_since(self.started);
+        wall.duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64
+    }
+
+    /// 写入临时文件后替换，避免写到一半断电丢失全部定时器
+    fn save(&self) -> Result<(), anyhow::Error> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        let temporary = path.with_extension("tmp");
+        fs::write(&temporary, serde_json::to_vec_pretty(&self.state)?)
+            .with_context(|| format!("failed to write {}", temporary.display()))?;
+        fs::rename(&temporary, path)?;
+        Ok(())
+    }
+}
+
+/// 时长的口语描述，如 “20 minutes”
+pub fn describe(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    let (amount, unit) = match seconds {
+        s if s >= 86400 && s % 86400 == 0 => (s / 86400, "day"),
+        s if s >= 3600 && s % 3600 == 0 => (s / 3600, "hour"),
+        s if s >= 60 && s % 60 == 0 => (s / 60, "minute"),
+        s => (s, "second"),
+    };
+    format!("{} {}{}", amount, unit, if amount == 1 { "" } else { "s" })
+}
+
+/// 提示音：两声短促的正弦音
+pub fn chime(sample_rate: u32) -> Vec<f32> {
+    let tone = |frequency: f32, seconds: f32| {
+        let samples = (sample_rate as f32 * seconds) as usize;
+        (0..samples).map(move |i| {
+            let t = i as f32 / sample_rate as f32;
+            // 首尾淡入淡出避免爆音
+            let envelope = (t / 0.01).min(1.0).min((seconds - t) / 0.01);
+            0.3 * envelope * (2.0 * PI * frequency * t).sin()
+        })
+    };
+    let silence = (0..sample_rate as usize / 10).map(|_| 0.0);
+    tone(880.0, 0.15)
+        .chain(silence)
+        .chain(tone(1320.0, 0.2))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    const MINUTE: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn repeats_catch_up_once() {
+        let start = Instant::now();
+        let mut scheduler = Scheduler::in_memory(start);
+        scheduler
+            .add(Some("stretch".into()), MINUTE, Some(MINUTE), None, start)
+            .unwrap();
+        scheduler.add(None, MINUTE, None, None, start).unwrap();
+        let first_due = scheduler.timers()[0].due_ms;
+
+        assert!(scheduler.take_due(start + MINUTE / 2).is_empty());
+
+        // 错过三个周期只提醒一次，下次到期顺延到未来
+        let later = start + MINUTE * 3 + Duration::from_secs(1);
+        let due = scheduler.take_due(later);
+        assert_eq!(due.len(), 2);
+        assert_eq!(due[0].announcement(), "Reminder: stretch");
+        assert_eq!(due[1].announcement(), "Your 1 minute timer is done");
+        assert_eq!(scheduler.timers().len(), 1);
+        assert_eq!(scheduler.timers()[0].due_ms, first_due + 3 * 60_000);
+        assert_eq!(
+            scheduler.remaining(&scheduler.timers()[0], later),
+            MINUTE - Duration::from_secs(1)
+        );
+        assert!(scheduler.take_due(later).is_empty());
+
+        assert!(scheduler
+            .add(None, Duration::ZERO, None, None, start)
+            .is_err());
+        assert!(scheduler
+            .add(None, MINUTE, Some(Duration::ZERO), None, start)
+            .is_err());
+    }
+
+    #[test]
+    fn persists_across_reopen() {
+        let path = testing::temp_dir("timer-reopen").join("timers.json");
+        let now = Instant::now();
+        let mut scheduler = Scheduler::open(&path, now).unwrap();
+        scheduler
+            .add(
+                Some("call home".into()),
+                MINUTE * 60,
+                None,
+                Some("alice".into()),
+                now,
+            )
+            .unwrap();
+        let saved = scheduler.timers()[0].clone();
+        drop(scheduler);
+
+        let mut scheduler = Scheduler::open(&path, Instant::now()).unwrap();
+        let timer = &scheduler.timers()[0];
+        assert_eq!(timer.due_ms, saved.due_ms);
+        assert_eq!(timer.owner.as_deref(), Some("alice"));
+        let remaining = scheduler.remaining(timer, Instant::now());
+        assert!(remaining <= MINUTE * 60 && remaining > MINUTE * 59);
+        // 编号在重启后继续递增
+        assert_eq!(scheduler.add(None, MINUTE, None, None, now).unwrap().id, 2);
+
+        assert_eq!(scheduler.clear().unwrap(), 2);
+        drop(scheduler);
+        assert!(Scheduler::open(&path, now).unwrap().timers().is_empty());
+
+        fs::write(&path, "{").unwrap();
+        assert!(Scheduler::open(&path, now).is_err());
+    }
+
+    #[test]
+    fn describes_durations() {
+        let cases = [
+            (1, "1 second"),
+            (45, "45 seconds"),
+            (60, "1 minute"),
+            (90, "90 seconds"),
+            (1200, "20 minutes"),
+            (7200, "2 hours"),
+            (5400, "90 minutes"),
+            (86400, "1 day"),
+            (90000, "25 hours"),
+        ];
+        for (seconds, text) in cases {
+            assert_eq!(describe(Duration::from_secs(seconds)), text);
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use super::{Speech, Synthesizer};
+use anyhow::{anyhow, Context};
+use std::io::{Cursor, Write};
+use std::process::{Command, Stdio};
+use std::thread;
+use tracing::debug;
+use voice::audio::wav::decode_wav;
+
+/// 本地合成程序：文本从标准输入写入，WAV 从标准输出读取
+/// 如 “espeak-ng --stdout”、“piper --model zh_CN.onnx --output_file -”
+pub struct CommandSynthesizer {
+    program: String,
+    args: Vec<String>,
+}
+
+impl CommandSynthesizer {
+    pub fn new(program: impl Into<String>, args: Vec<String>) -> Self {
+        Self {
+            program: program.into(),
+            args,
+        }
+    }
+
+    /// 由命令行解析（按空白拆分参数）
+    pub fn parse(command: &str) -> Result<Self, anyhow::Error> {
+        let mut parts = command.split_whitespace().map(str::to_string);
+        let program = parts
+            .next()
+            .ok_or_else(|| anyhow!("empty synthesizer command"))?;
+        Ok(Self::new(program, parts.collect()))
+    }
+}
+
+impl Synthesizer for CommandSynthesizer {
+    fn name(&self) -> &str {
+        &self.program
+    }
+
+    fn synthesize(&mut self, text: &str) -> Result<Speech, anyhow::Error> {
+        let mut child = Command::new(&self.program)
+            .args(&self.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("failed to run {}", self.program))?;
+
+        // 另起线程写入，避免输出写满管道时双方互相等待
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        let input = format!("{}\n", text);
+        let writer = thread::spawn(move || stdin.write_all(input.as_bytes()));
+
+        let output = child.wait_with_output()?;
+        let _ = writer.join();
+        if !output.status.success() {
+            return Err(anyhow!(
+                "{} exited with {}: {}",
+                self.program,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+
+        let wav = decode_wav(Cursor::new(output.stdout))
+            .with_context(|| format!("invalid audio from {}", self.program))?;
+        debug!(
+            program = %self.program,
+            sample_rate = wav.sample_rate,
+            channels = wav.channels,
+            "Synthesized"
+        );
+        Ok(Speech {
+            samples: wav.channel(0),
+            sample_rate: wav.sample_rate,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+    use voice::audio::wav::{write_wav, WavData};
+
+    #[test]
+    fn parses_command_lines() {
+        let synthesizer = CommandSynthesizer::parse("  espeak-ng  --stdout -v en ").unwrap();
+        assert_eq!(synthesizer.name(), "espeak-ng");
+        assert_eq!(synthesizer.args, ["--stdout", "-v", "en"]);
+        assert!(CommandSynthesizer::parse("   ").is_err());
+    }
+
+    #[test]
+    fn reads_wav_from_stdout() {
+        let path = testing::temp_dir("tts-command").join("speech.wav");
+        write_wav(
+            &path,
+            &WavData {
+                sample_rate: 22050,
+                channels: 2,
+                samples: [0.25, -0.25].repeat(100),
+            },
+        )
+        .unwrap();
+
+        // 读完标准输入后输出 WAV
+        let script = format!("cat > /dev/null; cat {}", path.display());
+        let mut synthesizer = CommandSynthesizer::new("sh", vec!["-c".into(), script]);
+        let speech = synthesizer.synthesize("hello").unwrap();
+        assert_eq!(speech.sample_rate, 22050);
+        assert_eq!(speech.samples.len(), 100);
+        assert!(speech.samples.iter().all(|&s| (s - 0.25).abs() < 1e-3));
+    }
+
+    #[test]
+    fn reports_failures() {
+        let mut missing = CommandSynthesizer::parse("no-such-synthesizer").unwrap();
+        let error = missing.synthesize("hello").unwrap_err();
+        assert_eq!(error.to_string(), "failed to run no-such-synthesizer");
+
+        let script = "echo broken >&2; exit 3".to_string();
+        let mut failing = CommandSynthesizer::new("sh", vec!["-c".into(), script]);
+        let error = failing.synthesize("hello").unwrap_err().to_string();
+        assert!(
+            error.contains("exit status: 3") && error.ends_with("broken"),
+            "{}",
+            error
+        );
+
+        let mut silent = CommandSynthesizer::parse("true").unwrap();
+        let error = silent.synthesize("hello").unwrap_err();
+        assert_eq!(error.to_string(), "invalid audio from true");
+    }
+}
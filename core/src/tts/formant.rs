@@ -0,0 +1,218 @@
+use super::{Speech, Synthesizer};
+use std::f32::consts::PI;
+
+/// 音素类别
+#[derive(Debug, Clone, Copy)]
+enum Phone {
+    Vowel(f32, f32),  // 元音：F1、F2
+    Voiced(f32, f32), // 浊辅音（鼻音、流音等）：F1、F2，幅度较低
+    Fricative(f32),   // 清擦音：噪声中心频率
+    Stop(f32),        // 塞音：短暂静音后的爆破噪声
+    Silence(f32),     // 停顿（秒）
+}
+
+/// 按字母粗略映射到音素（英文拼写，数字按单词读）
+fn phones(text: &str) -> Vec<Phone> {
+    const DIGITS: [&str; 10] = [
+        "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+    ];
+    let mut phones = Vec::new();
+    for c in text.to_lowercase().chars() {
+        if let Some(digit) = c.to_digit(10) {
+            phones.extend(phones_of_word(DIGITS[digit as usize]));
+            phones.push(Phone::Silence(0.05));
+            continue;
+        }
+        phones.extend(phone(c));
+    }
+    phones
+}
+
+fn phones_of_word(word: &str) -> Vec<Phone> {
+    word.chars().filter_map(phone).collect()
+}
+
+fn phone(c: char) -> Option<Phone> {
+    Some(match c {
+        'a' => Phone::Vowel(730.0, 1090.0),
+        'e' => Phone::Vowel(530.0, 1840.0),
+        'i' | 'y' => Phone::Vowel(270.0, 2290.0),
+        'o' => Phone::Vowel(570.0, 840.0),
+        'u' => Phone::Vowel(300.0, 870.0),
+        'm' | 'n' => Phone::Voiced(250.0, 1200.0),
+        'l' => Phone::Voiced(360.0, 1300.0),
+        'r' => Phone::Voiced(490.0, 1350.0),
+        'w' => Phone::Voiced(300.0, 610.0),
+        'v' | 'z' | 'j' => Phone::Voiced(300.0, 1500.0),
+        's' | 'c' | 'x' => Phone::Fricative(5000.0),
+        'f' => Phone::Fricative(3500.0),
+        'h' => Phone::Fricative(1500.0),
+        'p' | 'b' => Phone::Stop(800.0),
+        't' | 'd' => Phone::Stop(4000.0),
+        'k' | 'g' | 'q' => Phone::Stop(2000.0),
+        ',' | ';' | ':' => Phone::Silence(0.2),
+        '.' | '!' | '?' => Phone::Silence(0.35),
+        c if c.is_whitespace() => Phone::Silence(0.06),
+        _ => return None,
+    })
+}
+
+/// 二阶谐振器
+#[derive(Default)]
+struct Resonator {
+    a: f32,
+    b: f32,
+    c: f32,
+    y1: f32,
+    y2: f32,
+}
+
+impl Resonator {
+    fn tune(&mut self, frequency: f32, bandwidth: f32, sample_rate: f32) {
+        let t = 1.0 / sample_rate;
+        self.c = -(-2.0 * PI * bandwidth * t).exp();
+        self.b = 2.0 * (-PI * bandwidth * t).exp() * (2.0 * PI * frequency * t).cos();
+        self.a = 1.0 - self.b - self.c;
+    }
+
+    fn process(&mut self, x: f32) -> f32 {
+        let y = self.a * x + self.b * self.y1 + self.c * self.y2;
+        self.y2 = self.y1;
+        self.y1 = y;
+        y
+    }
+}
+
+/// 内置的极简共振峰合成：脉冲串经两个谐振器形成元音，噪声形成擦音与爆破音
+/// 可懂度有限，只用于合成引擎不可用时的关键提示
+pub struct FormantSynthesizer {
+    sample_rate: u32,
+    pitch: f32, // 基频（Hz）
+}
+
+impl Default for FormantSynthesizer {
+    fn default() -> Self {
+        Self {
+            sample_rate: 16000,
+            pitch: 120.0,
+        }
+    }
+}
+
+impl Synthesizer for FormantSynthesizer {
+    fn name(&self) -> &str {
+        "formant"
+    }
+
+    fn synthesize(&mut self, text: &str) -> Result<Speech, anyhow::Error> {
+        let sample_rate = self.sample_rate as f32;
+        let mut samples = Vec::new();
+        let mut f1 = Resonator::default();
+        let mut f2 = Resonator::default();
+        let mut noise_filter = Resonator::default();
+        let mut noise_state = 0x1234_5678u32;
+        let mut noise = move || {
+            // xorshift 伪随机噪声
+            noise_state ^= noise_state << 13;
+            noise_state ^= noise_state >> 17;
+            noise_state ^= noise_state << 5;
+            noise_state as f32 / u32::MAX as f32 * 2.0 - 1.0
+        };
+        let mut phase = 0.0f32;
+        let phones = phones(text);
+        let count = phones.len().max(1) as f32;
+
+        for (index, phone) in phones.into_iter().enumerate() {
+            // 句末基频逐渐降低
+            let pitch = self.pitch * (1.0 - 0.2 * index as f32 / count);
+            let (seconds, voiced, noisy) = match phone {
+                Phone::Vowel(a, b) => {
+                    f1.tune(a, 80.0, sample_rate);
+                    f2.tune(b, 120.0, sample_rate);
+                    (0.11, 1.0, 0.0)
+                }
+                Phone::Voiced(a, b) => {
+                    f1.tune(a, 100.0, sample_rate);
+                    f2.tune(b, 200.0, sample_rate);
+                    (0.07, 0.5, 0.0)
+                }
+                Phone::Fricative(center) => {
+                    noise_filter.tune(center.min(sample_rate * 0.45), 1500.0, sample_rate);
+                    (0.09, 0.0, 0.3)
+                }
+                Phone::Stop(center) => {
+                    // 闭塞段
+                    samples.extend(std::iter::repeat_n(0.0, (0.04 * sample_rate) as usize));
+                    noise_filter.tune(center.min(sample_rate * 0.45), 2000.0, sample_rate);
+                    (0.02, 0.0, 0.5)
+                }
+                Phone::Silence(seconds) => {
+                    samples.extend(std::iter::repeat_n(0.0, (seconds * sample_rate) as usize));
+                    continue;
+                }
+            };
+
+            let length = (seconds * sample_rate) as usize;
+            let ramp = (0.01 * sample_rate) as usize;
+            for i in 0..length {
+                // 首尾淡入淡出避免爆音
+                let envelope = (i.min(length - i) as f32 / ramp as f32).min(1.0);
+                let mut sample = 0.0;
+                if voiced > 0.0 {
+                    phase += pitch / sample_rate;
+                    // 每个基音周期一个脉冲
+                    let pulse = if phase >= 1.0 {
+                        phase -= 1.0;
+                        1.0
+                    } else {
+                        0.0
+                    };
+                    sample += voiced * f2.process(f1.process(pulse));
+                }
+                if noisy > 0.0 {
+                    sample += noisy * noise_filter.process(noise());
+                }
+                samples.push(sample * envelope);
+            }
+        }
+
+        // 归一化
+        let peak = samples.iter().fold(0.0f32, |peak, x| peak.max(x.abs()));
+        if peak > 0.0 {
+            samples.iter_mut().for_each(|x| *x *= 0.5 / peak);
+        }
+        Ok(Speech {
+            samples,
+            sample_rate: self.sample_rate,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spells_digits_as_words() {
+        assert_eq!(phones("2").len(), phones("two").len() + 1);
+        assert!(phones("#@").is_empty());
+        assert!(matches!(phones("a.")[1], Phone::Silence(s) if s == 0.35));
+    }
+
+    #[test]
+    fn synthesizes_normalized_audio() {
+        let mut synthesizer = FormantSynthesizer::default();
+        let speech = synthesizer.synthesize("Timer done.").unwrap();
+        assert_eq!(speech.sample_rate, 16000);
+        // 每个音素至少 20 毫秒
+        assert!(speech.samples.len() > phones("Timer done.").len() * 320);
+        let peak = speech
+            .samples
+            .iter()
+            .fold(0.0f32, |peak, x| peak.max(x.abs()));
+        assert!((peak - 0.5).abs() < 1e-6);
+        assert!(speech.samples.iter().all(|x| x.is_finite()));
+
+        assert!(synthesizer.synthesize("").unwrap().samples.is_empty());
+    }
+}
@@ -0,0 +1,416 @@
+/*
+    语音合成（TTS）
+    回复文本经合成引擎转为音频，按顺序送到音频输出：
+    1. command：调用本地离线合成程序（如 espeak-ng、piper），从标准输出读取 WAV
+    2. formant：内置的极简共振峰合成，引擎不可用时播报关键提示（提醒等）
+    3. output：系统默认音频输出设备
+    4. SpeechQueue：后台线程依次合成与播放，支持 <break time="500ms"/> 停顿，取消对话时丢弃未播放的内容
+*/
+
+pub mod command;
+pub mod formant;
+pub mod output;
+
+use crate::skill::{AudioSink, NullSink};
+use command::CommandSynthesizer;
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use formant::FormantSynthesizer;
+use output::DeviceSink;
+use regex::Regex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock};
+use std::thread;
+use std::time::Duration;
+use tracing::{debug, error, warn};
+use voice::config::Settings;
+
+/// 合成的音频
+#[derive(Debug)]
+pub struct Speech {
+    pub samples: Vec<f32>,
+    pub sample_rate: u32,
+}
+
+/// 合成引擎
+pub trait Synthesizer: Send {
+    fn name(&self) -> &str;
+
+    fn synthesize(&mut self, text: &str) -> Result<Speech, anyhow::Error>;
+}
+
+/// 待播报文本的片段
+#[derive(Debug, Clone, PartialEq)]
+pub enum Segment {
+    Text(String),
+    Pause(Duration),
+}
+
+/// 未指定时长的停顿
+const DEFAULT_BREAK: Duration = Duration::from_millis(500);
+
+static BREAK: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"<break(?:\s+time\s*=\s*["']?(\d+(?:\.\d+)?)\s*(ms|s)["']?)?\s*/?>"#).unwrap()
+});
+static TAG: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<[^>]*>").unwrap());
+
+/// 按停顿标记拆分文本，其余标记忽略
+pub fn segments(text: &str) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    let mut last = 0;
+    for captures in BREAK.captures_iter(text) {
+        let tag = captures.get(0).unwrap();
+        push_text(&mut segments, &text[last..tag.start()]);
+        last = tag.end();
+        let pause = match (captures.get(1), captures.get(2)) {
+            (Some(amount), Some(unit)) => {
+                let amount: f64 = amount.as_str().parse().unwrap_or(0.0);
+                let seconds = if unit.as_str() == "ms" {
+                    amount / 1000.0
+                } else {
+                    amount
+                };
+                // 过长的停顿截断到 10 秒
+                Duration::from_secs_f64(seconds.min(10.0))
+            }
+            _ => DEFAULT_BREAK,
+        };
+        segments.push(Segment::Pause(pause));
+    }
+    push_text(&mut segments, &text[last..]);
+    segments
+}
+
+/// 去掉标记后的文本（用于展示）
+pub fn plain_text(text: &str) -> String {
+    segments(text)
+        .into_iter()
+        .filter_map(|segment| match segment {
+            Segment::Text(text) => Some(text),
+            Segment::Pause(_) => None,
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// 去掉标记与多余空白后加入文本片段
+fn push_text(segments: &mut Vec<Segment>, text: &str) {
+    let text = TAG.replace_all(text, " ");
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if !text.is_empty() {
+        segments.push(Segment::Text(text));
+    }
+}
+
+/// 线性插值重采样
+pub fn resample(samples: &[f32], from: u32, to: u32) -> Vec<f32> {
+    if from == to || samples.is_empty() {
+        return samples.to_vec();
+    }
+    let length = (samples.len() as u64 * to as u64 / from as u64) as usize;
+    let step = from as f64 / to as f64;
+    (0..length)
+        .map(|i| {
+            let position = i as f64 * step;
+            let index = position as usize;
+            let fraction = (position - index as f64) as f32;
+            let current = samples[index.min(samples.len() - 1)];
+            let next = samples[(index + 1).min(samples.len() - 1)];
+            current + (next - current) * fraction
+        })
+        .collect()
+}
+
+enum Item {
+    Speak { text: String, critical: bool },
+    Play { samples: Vec<f32>, sample_rate: u32 },
+}
+
+/// 播报队列：后台线程依次合成并播放
+/// 作为 AudioSink 使用时音频也进入同一队列，与播报保持先后顺序
+pub struct SpeechQueue {
+    sender: Option<Sender<(u64, Item)>>, // 为空时丢弃所有内容（回放等）
+    generation: Arc<AtomicU64>,          // 清空队列时递增，旧的条目不再播放
+}
+
+impl SpeechQueue {
+    /// 丢弃所有内容的队列
+    pub fn disabled() -> Self {
+        Self {
+            sender: None,
+            generation: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// 启动播报线程，synthesizer 为空时只能播报关键提示
+    pub fn start(
+        synthesizer: Option<Box<dyn Synthesizer>>,
+        fallback: FormantSynthesizer,
+        sink: Box<dyn AudioSink>,
+    ) -> Result<Self, anyhow::Error> {
+        let (sender, items) = unbounded();
+        let generation = Arc::new(AtomicU64::new(0));
+        let worker = Worker {
+            synthesizer,
+            fallback,
+            sink,
+            generation: generation.clone(),
+        };
+        thread::Builder::new()
+            .name("speech".to_string())
+            .spawn(move || worker.run(items))?;
+        Ok(Self {
+            sender: Some(sender),
+            generation,
+        })
+    }
+
+    /// 按配置创建：合成程序与音频输出设备
+    pub fn from_settings(settings: &Settings) -> Self {
+        let synthesizer = settings.tts_command.as_deref().and_then(|command| {
+            match CommandSynthesizer::parse(command) {
+                Ok(synthesizer) => Some(Box::new(synthesizer) as Box<dyn Synthesizer>),
+                Err(e) => {
+                    error!("invalid speech synthesizer command: {:?}", e);
+                    None
+                }
+            }
+        });
+        let sink: Box<dyn AudioSink> = if settings.speech_output {
+            Box::new(DeviceSink)
+        } else {
+            Box::new(NullSink)
+        };
+        Self::start(synthesizer, FormantSynthesizer::default(), sink).unwrap_or_else(|e| {
+            error!("failed to start speech output: {:?}", e);
+            Self::disabled()
+        })
+    }
+
+    /// 排队播报文本；critical 为关键提示，合成引擎不可用时使用内置合成
+    pub fn speak(&self, text: &str, critical: bool) {
+        self.send(Item::Speak {
+            text: text.to_string(),
+            critical,
+        });
+    }
+
+    /// 丢弃尚未播放的内容（正在播放的片段会播完）
+    pub fn clear(&self) {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn send(&self, item: Item) {
+        if let Some(sender) = &self.sender {
+            let _ = sender.send((self.generation.load(Ordering::SeqCst), item));
+        }
+    }
+}
+
+impl AudioSink for SpeechQueue {
+    fn play(&mut self, samples: &[f32], sample_rate: u32) -> Result<(), anyhow::Error> {
+        self.send(Item::Play {
+            samples: samples.to_vec(),
+            sample_rate,
+        });
+        Ok(())
+    }
+}
+
+struct Worker {
+    synthesizer: Option<Box<dyn Synthesizer>>,
+    fallback: FormantSynthesizer,
+    sink: Box<dyn AudioSink>,
+    generation: Arc<AtomicU64>,
+}
+
+impl Worker {
+    fn run(mut self, items: Receiver<(u64, Item)>) {
+        for (generation, item) in items {
+            match item {
+                Item::Play {
+                    samples,
+                    sample_rate,
+                } if self.current(generation) => {
+                    if let Err(e) = self.sink.play(&samples, sample_rate) {
+                        warn!("failed to play audio: {:?}", e);
+                    }
+                }
+                Item::Speak { text, critical } => {
+                    for segment in segments(&text) {
+                        if !self.current(generation) {
+                            break;
+                        }
+                        match segment {
+                            Segment::Pause(pause) => thread::sleep(pause),
+                            Segment::Text(text) => self.say(&text, critical),
+                        }
+                    }
+                }
+                Item::Play { .. } => {}
+            }
+        }
+    }
+
+    fn current(&self, generation: u64) -> bool {
+        self.generation.load(Ordering::SeqCst) == generation
+    }
+
+    fn say(&mut self, text: &str, critical: bool) {
+        let mut speech = None;
+        if let Some(synthesizer) = self.synthesizer.as_mut() {
+            match synthesizer.synthesize(text) {
+                Ok(result) => speech = Some((synthesizer.name().to_string(), result)),
+                Err(e) => warn!(
+                    synthesizer = synthesizer.name(),
+                    "speech synthesis failed: {:?}", e
+                ),
+            }
+        }
+        // 合成引擎不可用时只播报关键提示
+        if speech.is_none() && critical {
+            match self.fallback.synthesize(text) {
+                Ok(result) => speech = Some((self.fallback.name().to_string(), result)),
+                Err(e) => warn!("fallback speech synthesis failed: {:?}", e),
+            }
+        }
+        let Some((synthesizer, speech)) = speech else {
+            return;
+        };
+        debug!(
+            synthesizer,
+            text,
+            seconds = speech.samples.len() as f32 / speech.sample_rate as f32,
+            "Speaking"
+        );
+        if let Err(e) = self.sink.play(&speech.samples, speech.sample_rate) {
+            warn!("failed to play speech: {:?}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+
+    /// 把播放的音频长度转发到通道
+    struct ChannelSink(Sender<(usize, u32)>);
+
+    impl AudioSink for ChannelSink {
+        fn play(&mut self, samples: &[f32], sample_rate: u32) -> Result<(), anyhow::Error> {
+            let _ = self.0.send((samples.len(), sample_rate));
+            Ok(())
+        }
+    }
+
+    /// 每个字符合成一个采样，含 “fail” 的文本合成失败
+    struct Echo;
+
+    impl Synthesizer for Echo {
+        fn name(&self) -> &str {
+            "echo"
+        }
+
+        fn synthesize(&mut self, text: &str) -> Result<Speech, anyhow::Error> {
+            if text.contains("fail") {
+                return Err(anyhow!("cannot say {}", text));
+            }
+            Ok(Speech {
+                samples: vec![0.0; text.len()],
+                sample_rate: 8000,
+            })
+        }
+    }
+
+    #[test]
+    fn splits_breaks_and_strips_markup() {
+        let text = r#"<speak>Turn <emphasis>left</emphasis><break time="250ms"/>then
+            right <break time='1.5s'> and <break/>stop<break time="60s"/></speak>"#;
+        assert_eq!(
+            segments(text),
+            [
+                Segment::Text("Turn left".into()),
+                Segment::Pause(Duration::from_millis(250)),
+                Segment::Text("then right".into()),
+                Segment::Pause(Duration::from_millis(1500)),
+                Segment::Text("and".into()),
+                Segment::Pause(DEFAULT_BREAK),
+                Segment::Text("stop".into()),
+                Segment::Pause(Duration::from_secs(10)),
+            ]
+        );
+        assert_eq!(plain_text(text), "Turn left then right and stop");
+        assert_eq!(plain_text("no markup"), "no markup");
+        assert!(segments("<break/>")
+            .iter()
+            .all(|s| matches!(s, Segment::Pause(_))));
+    }
+
+    #[test]
+    fn resamples_length_and_values() {
+        let samples = [0.0, 1.0, 0.0, -1.0];
+        assert_eq!(resample(&samples, 16000, 16000), samples);
+        assert_eq!(
+            resample(&samples, 16000, 32000),
+            [0.0, 0.5, 1.0, 0.5, 0.0, -0.5, -1.0, -1.0]
+        );
+        assert_eq!(resample(&samples, 16000, 8000), [0.0, 0.0]);
+        assert!(resample(&[], 16000, 48000).is_empty());
+    }
+
+    #[test]
+    fn queue_speaks_in_order_and_falls_back_for_critical() {
+        let (sender, played) = unbounded();
+        let mut queue = SpeechQueue::start(
+            Some(Box::new(Echo)),
+            FormantSynthesizer::default(),
+            Box::new(ChannelSink(sender)),
+        )
+        .unwrap();
+
+        queue.speak("hello<break time=\"10ms\"/>there", false);
+        queue.play(&[0.0; 3], 44100).unwrap();
+        queue.speak("fail quietly", false);
+        queue.speak("fail loudly", true);
+
+        let timeout = Duration::from_secs(5);
+        assert_eq!(played.recv_timeout(timeout).unwrap(), (5, 8000));
+        assert_eq!(played.recv_timeout(timeout).unwrap(), (5, 8000));
+        assert_eq!(played.recv_timeout(timeout).unwrap(), (3, 44100));
+        // 合成失败的普通回复不播报，关键提示改用内置合成
+        let (length, sample_rate) = played.recv_timeout(timeout).unwrap();
+        assert_eq!(sample_rate, 16000);
+        assert!(length > 0);
+        assert!(played.recv_timeout(Duration::from_millis(100)).is_err());
+    }
+
+    #[test]
+    fn clear_drops_pending_speech() {
+        let (sender, played) = unbounded();
+        let queue = SpeechQueue::start(
+            Some(Box::new(Echo)),
+            FormantSynthesizer::default(),
+            Box::new(ChannelSink(sender)),
+        )
+        .unwrap();
+
+        // 第一个片段播放后进入长停顿，清空后其余内容不再播放
+        queue.speak("first<break time=\"300ms\"/>second", false);
+        assert_eq!(
+            played.recv_timeout(Duration::from_secs(5)).unwrap(),
+            (5, 8000)
+        );
+        queue.clear();
+        assert!(played.recv_timeout(Duration::from_millis(600)).is_err());
+
+        queue.speak("third", false);
+        assert_eq!(
+            played.recv_timeout(Duration::from_secs(5)).unwrap(),
+            (5, 8000)
+        );
+
+        let mut disabled = SpeechQueue::disabled();
+        disabled.speak("ignored", true);
+        assert!(disabled.play(&[0.0], 16000).is_ok());
+    }
+}
@@ -0,0 +1,59 @@
+use super::resample;
+use crate::skill::AudioSink;
+use anyhow::anyhow;
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use crossbeam_channel::bounded;
+use std::thread;
+use std::time::Duration;
+use tracing::error;
+
+/// 系统默认音频输出设备（每次播放时打开，播放完成后返回）
+pub struct DeviceSink;
+
+impl AudioSink for DeviceSink {
+    fn play(&mut self, samples: &[f32], sample_rate: u32) -> Result<(), anyhow::Error> {
+        if samples.is_empty() {
+            return Ok(());
+        }
+        let device = cpal::default_host()
+            .default_output_device()
+            .ok_or_else(|| anyhow!("no audio output device"))?;
+        let supported = device.default_output_config()?;
+        if supported.sample_format() != cpal::SampleFormat::F32 {
+            return Err(anyhow!(
+                "unsupported output sample format: {:?}",
+                supported.sample_format()
+            ));
+        }
+        let config: cpal::StreamConfig = supported.into();
+        let channels = config.channels as usize;
+        let output_rate = config.sample_rate.0;
+        let samples = resample(samples, sample_rate, output_rate);
+        let duration = Duration::from_secs_f64(samples.len() as f64 / output_rate as f64);
+
+        // 单声道复制到所有声道，播放完毕后通知
+        let (done, finished) = bounded(1);
+        let mut position = 0;
+        let stream = device.build_output_stream(
+            &config,
+            move |data: &mut [f32], _: &cpal::OutputCallbackInfo| {
+                for frame in data.chunks_mut(channels) {
+                    frame.fill(samples.get(position).copied().unwrap_or(0.0));
+                    position += 1;
+                }
+                if position >= samples.len() {
+                    let _ = done.try_send(());
+                }
+            },
+            |err| error!("Audio output error: {:?}", err),
+            None,
+        )?;
+        stream.play()?;
+        finished
+            .recv_timeout(duration + Duration::from_secs(1))
+            .map_err(|_| anyhow!("audio output stalled"))?;
+        // 等设备缓冲中的最后一段播完
+        thread::sleep(Duration::from_millis(50));
+        Ok(())
+    }
+}
@@ -0,0 +1,331 @@
+/*
+    车控指令 / 车辆状态与 CAN 帧之间的编解码
+    报文布局由 DBC 定义，此处只约定报文名与信号名：
+
+    指令报文：
+      ClimateCommand  Zone(值表：温区) Temperature(°C)
+      WindowCommand   Window(值表：车窗) Action(值表：open/close)
+      MediaCommand    Action(值表：媒体动作)
+    状态报文：
+      ClimateStatus   DriverTemperature PassengerTemperature RearTemperature CabinTemperature
+      WindowStatus    FrontLeft FrontRight RearLeft RearRight(开度 %)
+      BodyStatus      Ignition Speed(km/h) Fuel(%)，可按复用分页
+*/
+
+use super::dbc::{Dbc, SignalReading, SignalValue};
+use super::{CanFrame, VehicleState};
+use crate::intent::VehicleCommand;
+use anyhow::anyhow;
+use serde::de::value::{Error as ValueError, StrDeserializer};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tracing::{debug, info};
+
+const CLIMATE_COMMAND: &str = "ClimateCommand";
+const WINDOW_COMMAND: &str = "WindowCommand";
+const MEDIA_COMMAND: &str = "MediaCommand";
+const CLIMATE_STATUS: &str = "ClimateStatus";
+const WINDOW_STATUS: &str = "WindowStatus";
+const BODY_STATUS: &str = "BodyStatus";
+
+/// 基于 DBC 的编解码器
+#[derive(Debug, Clone, Default)]
+pub struct Codec {
+    dbc: Dbc,
+}
+
+impl Codec {
+    pub fn new(dbc: Dbc) -> Self {
+        Self { dbc }
+    }
+
+    /// 加载 DBC 文件，未指定时使用内置定义
+    pub fn load(path: Option<&str>) -> Result<Self, anyhow::Error> {
+        let codec = match path {
+            Some(path) => Self::new(Dbc::load(path)?),
+            None => Self::default(),
+        };
+        let messages = codec.dbc.messages();
+        info!(
+            dbc = path.unwrap_or("built-in"),
+            messages = messages.len(),
+            "DBC loaded"
+        );
+        for message in messages {
+            let signals: Vec<String> = message
+                .signals
+                .iter()
+                .map(|s| format!("{}[{}] -> {}", s.name, s.unit, s.receivers.join(",")))
+                .collect();
+            debug!(
+                id = message.id,
+                name = %message.name,
+                transmitter = %message.transmitter,
+                "DBC message: {}",
+                signals.join(" ")
+            );
+        }
+        Ok(codec)
+    }
+
+    /// 指令编码为 CAN 帧
+    pub fn encode_command(&self, command: &VehicleCommand) -> Result<Vec<CanFrame>, anyhow::Error> {
+        let frame = match command {
+            VehicleCommand::SetTemperature { zone, celsius } => self.dbc.encode(
+                CLIMATE_COMMAND,
+                &[
+                    ("Zone", SignalValue::Label(&label(zone)?)),
+                    ("Temperature", SignalValue::Physical(*celsius as f64)),
+                ],
+            )?,
+            VehicleCommand::Window { which, action } => self.dbc.encode(
+                WINDOW_COMMAND,
+                &[
+                    ("Window", SignalValue::Label(&label(which)?)),
+                    ("Action", SignalValue::Label(&label(action)?)),
+                ],
+            )?,
+            VehicleCommand::Media { action } => self.dbc.encode(
+                MEDIA_COMMAND,
+                &[("Action", SignalValue::Label(&label(action)?))],
+            )?,
+            // 车况查询由状态回答，不下发报文
+            VehicleCommand::Query { .. } => return Ok(Vec::new()),
+        };
+        Ok(vec![frame])
+    }
+
+    /// 解码指令帧（用于模拟 ECU 与总线监听）
+    pub fn decode_command(
+        &self,
+        frame: &CanFrame,
+    ) -> Result<Option<VehicleCommand>, anyhow::Error> {
+        let Some((message, signals)) = self.dbc.decode(frame) else {
+            return Ok(None);
+        };
+        let signals = signals?;
+
+        let command = match message.name.as_str() {
+            CLIMATE_COMMAND => VehicleCommand::SetTemperature {
+                zone: from_label(&signals, "Zone")?,
+                celsius: physical(&signals, "Temperature")? as f32,
+            },
+            WINDOW_COMMAND => VehicleCommand::Window {
+                which: from_label(&signals, "Window")?,
+                action: from_label(&signals, "Action")?,
+            },
+            MEDIA_COMMAND => VehicleCommand::Media {
+                action: from_label(&signals, "Action")?,
+            },
+            _ => return Ok(None),
+        };
+        Ok(Some(command))
+    }
+
+    /// 车辆状态编码为状态帧（复用报文每个复用值各一帧）
+    pub fn encode_status(&self, state: &VehicleState) -> Result<Vec<CanFrame>, anyhow::Error> {
+        let statuses: [(&str, Vec<(&str, f64)>); 3] = [
+            (
+                CLIMATE_STATUS,
+                vec![
+                    ("DriverTemperature", state.driver_celsius as f64),
+                    ("PassengerTemperature", state.passenger_celsius as f64),
+                    ("RearTemperature", state.rear_celsius as f64),
+                    ("CabinTemperature", state.cabin_celsius as f64),
+                ],
+            ),
+            (
+                WINDOW_STATUS,
+                vec![
+                    ("FrontLeft", state.windows[0] as f64),
+                    ("FrontRight", state.windows[1] as f64),
+                    ("RearLeft", state.windows[2] as f64),
+                    ("RearRight", state.windows[3] as f64),
+                ],
+            ),
+            (
+                BODY_STATUS,
+                vec![
+                    ("Ignition", state.ignition_on as u8 as f64),
+                    ("Speed", state.speed_kph as f64),
+                    ("Fuel", state.fuel_percent as f64),
+                ],
+            ),
+        ];
+
+        let mut frames = Vec::new();
+        for (name, values) in statuses {
+            let Some(message) = self.dbc.message(name) else {
+                continue;
+            };
+            let pages = match message.multiplexor() {
+                Some(multiplexor) => message
+                    .multiplex_values()
+                    .into_iter()
+                    .map(|page| Some((multiplexor.name.as_str(), page)))
+                    .collect(),
+                None => vec![None],
+            };
+
+            for page in pages {
+                let mut signals: Vec<(&str, SignalValue)> = values
+                    .iter()
+                    .filter(|(signal, _)| {
+                        message.signal(signal).is_some_and(|s| match page {
+                            Some((_, page)) => s.is_active(page),
+                            None => true,
+                        })
+                    })
+                    .map(|(signal, value)| (*signal, SignalValue::Physical(*value)))
+                    .collect();
+                if let Some((multiplexor, page)) = page {
+                    signals.push((multiplexor, SignalValue::Physical(page as f64)));
+                }
+                frames.push(message.encode(&signals)?);
+            }
+        }
+        Ok(frames)
+    }
+
+    /// 解码状态帧并更新车辆状态，非状态帧忽略
+    pub fn decode_status(&self, frame: &CanFrame, state: &mut VehicleState) -> bool {
+        let Some((message, Ok(signals))) = self.dbc.decode(frame) else {
+            return false;
+        };
+        let value = |name: &str| signals.get(name).map(|s| s.physical as f32);
+
+        match message.name.as_str() {
+            CLIMATE_STATUS => {
+                update(&mut state.driver_celsius, value("DriverTemperature"));
+                update(&mut state.passenger_celsius, value("PassengerTemperature"));
+                update(&mut state.rear_celsius, value("RearTemperature"));
+                update(&mut state.cabin_celsius, value("CabinTemperature"));
+            }
+            WINDOW_STATUS => {
+                for (i, name) in ["FrontLeft", "FrontRight", "RearLeft", "RearRight"]
+                    .iter()
+                    .enumerate()
+                {
+                    if let Some(percent) = value(name) {
+                        state.windows[i] = percent.clamp(0.0, 100.0) as u8;
+                    }
+                }
+            }
+            BODY_STATUS => {
+                if let Some(ignition) = value("Ignition") {
+                    state.ignition_on = ignition != 0.0;
+                }
+                update(&mut state.speed_kph, value("Speed"));
+                update(&mut state.fuel_percent, value("Fuel"));
+            }
+            _ => return false,
+        }
+        true
+    }
+}
+
+fn update(field: &mut f32, value: Option<f32>) {
+    if let Some(value) = value {
+        *field = value;
+    }
+}
+
+/// 枚举值对应的值表描述（与槽位规范值一致）
+fn label<T: Serialize>(value: &T) -> Result<String, anyhow::Error> {
+    match toml::Value::try_from(value)? {
+        toml::Value::String(label) => Ok(label),
+        other => Err(anyhow!("not a label: {}", other)),
+    }
+}
+
+fn from_label<'de, T: Deserialize<'de>>(
+    signals: &'de HashMap<String, SignalReading>,
+    name: &str,
+) -> Result<T, anyhow::Error> {
+    let reading = signals
+        .get(name)
+        .ok_or_else(|| anyhow!("missing signal: {}", name))?;
+    let label = reading
+        .label
+        .as_deref()
+        .ok_or_else(|| anyhow!("no description for {} = {}", name, reading.raw))?;
+    T::deserialize(StrDeserializer::<ValueError>::new(label))
+        .map_err(|e| anyhow!("invalid value {:?} for {}: {}", label, name, e))
+}
+
+fn physical(signals: &HashMap<String, SignalReading>, name: &str) -> Result<f64, anyhow::Error> {
+    signals
+        .get(name)
+        .map(|s| s.physical)
+        .ok_or_else(|| anyhow!("missing signal: {}", name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intent::command::{MediaAction, WindowAction, WindowPosition, Zone};
+
+    #[test]
+    fn commands_round_trip() {
+        let codec = Codec::default();
+        let commands = [
+            VehicleCommand::SetTemperature {
+                zone: Zone::Passenger,
+                celsius: 22.5,
+            },
+            VehicleCommand::Window {
+                which: WindowPosition::RearLeft,
+                action: WindowAction::Close,
+            },
+            VehicleCommand::Media {
+                action: MediaAction::VolumeDown,
+            },
+        ];
+        for command in commands {
+            let frames = codec.encode_command(&command).unwrap();
+            assert_eq!(frames.len(), 1);
+            assert_eq!(codec.decode_command(&frames[0]).unwrap(), Some(command));
+        }
+
+        let frame = &codec
+            .encode_command(&VehicleCommand::SetTemperature {
+                zone: Zone::Driver,
+                celsius: 22.0,
+            })
+            .unwrap()[0];
+        assert_eq!((frame.id, frame.data.as_slice()), (928, &[1, 44][..]));
+        // 超出 DBC 中的温度范围
+        assert!(codec
+            .encode_command(&VehicleCommand::SetTemperature {
+                zone: Zone::Driver,
+                celsius: 40.0,
+            })
+            .is_err());
+    }
+
+    #[test]
+    fn status_round_trip() {
+        let codec = Codec::default();
+        let state = VehicleState {
+            ignition_on: true,
+            speed_kph: 88.5,
+            fuel_percent: 64.0,
+            cabin_celsius: -5.0,
+            driver_celsius: 21.5,
+            passenger_celsius: 23.0,
+            rear_celsius: 20.0,
+            windows: [0, 100, 35, 0],
+            ..VehicleState::default()
+        };
+        let frames = codec.encode_status(&state).unwrap();
+        // BodyStatus 按复用分两页
+        assert_eq!(frames.len(), 4);
+
+        let mut decoded = VehicleState::default();
+        for frame in &frames {
+            assert!(codec.decode_status(frame, &mut decoded));
+            assert_eq!(codec.decode_command(frame).unwrap(), None);
+        }
+        assert_eq!(decoded, state);
+    }
+}
@@ -0,0 +1,535 @@
+/*
+    DBC 信号定义
+    解析 DBC 文件中的报文、信号（位布局、缩放、单位）、值表与多路复用，
+    按信号名对 CAN 帧进行编解码，更换车型时只需替换 DBC 文件
+*/
+
+mod parser;
+
+use super::CanFrame;
+use anyhow::{anyhow, Context};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+
+/// 内置的车辆报文定义
+const DEFAULT_DBC: &str = include_str!("../../../dbc/vehicle.dbc");
+
+/// 信号字节序
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteOrder {
+    LittleEndian, // Intel，@1
+    BigEndian,    // Motorola，@0
+}
+
+/// 信号的多路复用角色
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Multiplexing {
+    None,
+    Multiplexor,
+    Multiplexed(u64), // 仅当复用信号取该值时有效
+}
+
+/// 信号定义
+#[derive(Debug, Clone)]
+pub struct Signal {
+    pub name: String,
+    pub start_bit: u32,
+    pub size: u32,
+    pub byte_order: ByteOrder,
+    pub signed: bool,
+    pub factor: f64,
+    pub offset: f64,
+    pub min: f64,
+    pub max: f64,
+    pub unit: String,
+    pub receivers: Vec<String>,
+    pub multiplexing: Multiplexing,
+    pub values: BTreeMap<i64, String>, // 值表：原始值 -> 描述
+}
+
+/// 报文定义
+#[derive(Debug, Clone)]
+pub struct Message {
+    pub id: u32,
+    pub extended: bool,
+    pub name: String,
+    pub size: usize,
+    pub transmitter: String,
+    pub signals: Vec<Signal>,
+}
+
+/// 编码时的信号取值
+#[derive(Debug, Clone, Copy)]
+pub enum SignalValue<'a> {
+    Physical(f64),
+    Label(&'a str), // 按值表描述取值
+}
+
+/// 解码后的信号
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignalReading {
+    pub raw: i64,
+    pub physical: f64,
+    pub label: Option<String>,
+}
+
+/// 解码结果：信号名 -> 读数
+pub type SignalReadings = HashMap<String, SignalReading>;
+
+/// DBC 数据库
+#[derive(Debug, Clone)]
+pub struct Dbc {
+    messages: Vec<Message>,
+}
+
+impl Dbc {
+    pub fn parse(source: &str) -> Result<Self, anyhow::Error> {
+        parser::parse(source)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, anyhow::Error> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path)
+            .with_context(|| format!("failed to read DBC file: {}", path.display()))?;
+        Self::parse(&source).with_context(|| format!("invalid DBC file: {}", path.display()))
+    }
+
+    pub fn messages(&self) -> &[Message] {
+        &self.messages
+    }
+
+    pub fn message(&self, name: &str) -> Option<&Message> {
+        self.messages.iter().find(|m| m.name == name)
+    }
+
+    pub fn message_by_id(&self, id: u32, extended: bool) -> Option<&Message> {
+        self.messages
+            .iter()
+            .find(|m| m.id == id && m.extended == extended)
+    }
+
+    /// 按报文名编码
+    pub fn encode(
+        &self,
+        message: &str,
+        values: &[(&str, SignalValue)],
+    ) -> Result<CanFrame, anyhow::Error> {
+        self.message(message)
+            .ok_or_else(|| anyhow!("message not defined in DBC: {}", message))?
+            .encode(values)
+    }
+
+    /// 解码已定义的报文，未知报文返回 None
+    pub fn decode(
+        &self,
+        frame: &CanFrame,
+    ) -> Option<(&Message, Result<SignalReadings, anyhow::Error>)> {
+        let message = self.message_by_id(frame.id, frame.extended)?;
+        Some((message, message.decode(&frame.data)))
+    }
+}
+
+impl Default for Dbc {
+    fn default() -> Self {
+        Self::parse(DEFAULT_DBC).expect("invalid built-in DBC")
+    }
+}
+
+impl Message {
+    pub fn signal(&self, name: &str) -> Option<&Signal> {
+        self.signals.iter().find(|s| s.name == name)
+    }
+
+    /// 复用信号
+    pub fn multiplexor(&self) -> Option<&Signal> {
+        self.signals
+            .iter()
+            .find(|s| s.multiplexing == Multiplexing::Multiplexor)
+    }
+
+    /// 报文中出现的所有复用值
+    pub fn multiplex_values(&self) -> Vec<u64> {
+        let mut values: Vec<u64> = self
+            .signals
+            .iter()
+            .filter_map(|s| match s.multiplexing {
+                Multiplexing::Multiplexed(value) => Some(value),
+                _ => None,
+            })
+            .collect();
+        values.sort_unstable();
+        values.dedup();
+        values
+    }
+
+    /// 编码报文，未给出的信号取原始值 0
+    pub fn encode(&self, values: &[(&str, SignalValue)]) -> Result<CanFrame, anyhow::Error> {
+        let mut data = vec![0u8; self.size];
+        let mut selected = None;
+
+        // 先写入复用信号，确定当前生效的信号组
+        if let Some(multiplexor) = self.multiplexor() {
+            let value = values
+                .iter()
+                .find(|(name, _)| *name == multiplexor.name)
+                .map(|(_, value)| multiplexor.to_raw(*value))
+                .transpose()?
+                .unwrap_or(0);
+            multiplexor.insert(&mut data, value);
+            selected = Some(value);
+        }
+
+        for (name, value) in values {
+            let signal = self
+                .signal(name)
+                .ok_or_else(|| anyhow!("signal {} not defined in {}", name, self.name))?;
+            match signal.multiplexing {
+                Multiplexing::Multiplexor => continue,
+                Multiplexing::Multiplexed(id) if Some(id) != selected => {
+                    return Err(anyhow!(
+                        "signal {} is not active for multiplexor value {:?}",
+                        name,
+                        selected
+                    ));
+                }
+                _ => {}
+            }
+            let raw = signal.to_raw(*value)?;
+            signal.insert(&mut data, raw);
+        }
+
+        CanFrame::new(self.id, self.extended, &data)
+    }
+
+    /// 解码报文，仅返回当前复用值下生效的信号
+    pub fn decode(&self, data: &[u8]) -> Result<SignalReadings, anyhow::Error> {
+        if data.len() < self.size {
+            return Err(anyhow!(
+                "{} expects {} bytes, got {}",
+                self.name,
+                self.size,
+                data.len()
+            ));
+        }
+
+        let selected = self.multiplexor().map(|s| s.extract(data));
+        Ok(self
+            .signals
+            .iter()
+            .filter(|s| selected.is_none_or(|value| s.is_active(value)))
+            .map(|s| (s.name.clone(), s.read(data)))
+            .collect())
+    }
+}
+
+impl Signal {
+    /// 原始值转物理值
+    pub fn to_physical(&self, raw: i64) -> f64 {
+        raw as f64 * self.factor + self.offset
+    }
+
+    /// 物理值（或值表描述）转原始位
+    pub fn to_raw(&self, value: SignalValue) -> Result<u64, anyhow::Error> {
+        let raw = match value {
+            SignalValue::Physical(physical) => {
+                // 最小值与最大值同为 0 时表示不限制
+                if self.min < self.max && !(self.min..=self.max).contains(&physical) {
+                    return Err(anyhow!(
+                        "{} {} out of range [{}, {}]",
+                        self.name,
+                        physical,
+                        self.min,
+                        self.max
+                    ));
+                }
+                ((physical - self.offset) / self.factor).round() as i64
+            }
+            SignalValue::Label(label) => self
+                .values
+                .iter()
+                .find(|(_, name)| name.as_str() == label)
+                .map(|(raw, _)| *raw)
+                .ok_or_else(|| anyhow!("no value {:?} for signal {}", label, self.name))?,
+        };
+
+        let (lower, upper) = if self.signed {
+            (-(1i128 << (self.size - 1)), (1i128 << (self.size - 1)) - 1)
+        } else {
+            (0, (1i128 << self.size) - 1)
+        };
+        if !(lower..=upper).contains(&(raw as i128)) {
+            return Err(anyhow!(
+                "value {} out of range for signal {} ({} bits)",
+                raw,
+                self.name,
+                self.size
+            ));
+        }
+        Ok(raw as u64 & self.mask())
+    }
+
+    /// 复用值为给定值时信号是否生效
+    pub fn is_active(&self, multiplexor_value: u64) -> bool {
+        match self.multiplexing {
+            Multiplexing::Multiplexed(id) => id == multiplexor_value,
+            _ => true,
+        }
+    }
+
+    /// 值表描述
+    pub fn label(&self, raw: i64) -> Option<&str> {
+        self.values.get(&raw).map(String::as_str)
+    }
+
+    fn read(&self, data: &[u8]) -> SignalReading {
+        let bits = self.extract(data);
+        let raw = if self.signed && self.size < 64 && bits >> (self.size - 1) & 1 == 1 {
+            (bits | !self.mask()) as i64 // 符号扩展
+        } else {
+            bits as i64
+        };
+        SignalReading {
+            raw,
+            physical: self.to_physical(raw),
+            label: self.label(raw).map(str::to_string),
+        }
+    }
+
+    fn extract(&self, data: &[u8]) -> u64 {
+        self.bit_positions().enumerate().fold(0, |value, (i, pos)| {
+            let bit = data[pos as usize / 8] >> (pos % 8) & 1;
+            value | (bit as u64) << i
+        })
+    }
+
+    fn insert(&self, data: &mut [u8], value: u64) {
+        for (i, pos) in self.bit_positions().enumerate() {
+            let byte = &mut data[pos as usize / 8];
+            let mask = 1u8 << (pos % 8);
+            if value >> i & 1 == 1 {
+                *byte |= mask;
+            } else {
+                *byte &= !mask;
+            }
+        }
+    }
+
+    /// 信号各位在报文中的位置（从最低有效位开始）
+    fn bit_positions(&self) -> impl Iterator<Item = u32> {
+        let positions: Vec<u32> = match self.byte_order {
+            ByteOrder::LittleEndian => (self.start_bit..self.start_bit + self.size).collect(),
+            ByteOrder::BigEndian => {
+                // 起始位为最高有效位，按锯齿顺序向低位移动
+                let mut positions = Vec::with_capacity(self.size as usize);
+                let mut pos = self.start_bit;
+                for _ in 0..self.size {
+                    positions.push(pos);
+                    pos = if pos.is_multiple_of(8) {
+                        pos + 15
+                    } else {
+                        pos - 1
+                    };
+                }
+                positions.reverse();
+                positions
+            }
+        };
+        positions.into_iter()
+    }
+
+    fn mask(&self) -> u64 {
+        if self.size >= 64 {
+            u64::MAX
+        } else {
+            (1 << self.size) - 1
+        }
+    }
+
+    /// 信号占用的最高字节（用于校验报文长度）
+    fn last_byte(&self) -> usize {
+        self.bit_positions()
+            .map(|p| p as usize / 8)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_DBC: &str = r#"
+VERSION ""
+
+BU_: ECU DASH GW
+
+BO_ 256 Engine: 8 ECU
+ SG_ Rpm : 0|16@1+ (0.25,0) [0|16383.75] "rpm" DASH
+ SG_ Coolant : 16|8@1+ (1,-40) [-40|215] "degC" DASH
+ SG_ Pressure : 31|16@0+ (0.1,0) [0|6553.5] "kPa" DASH
+ SG_ Torque : 40|12@1- (0.5,0) [-1024|1023.5] "Nm" DASH,GW
+
+BO_ 2147484672 Drive: 2 GW
+ SG_ Page M : 0|8@1+ (1,0) [0|1] "" ECU
+ SG_ Gear m0 : 8|4@1+ (1,0) [0|15] "" ECU
+ SG_ Mode m1 : 8|8@1+ (1,0) [0|2] "" ECU
+
+CM_ SG_ 256 Pressure "Motorola signal crossing bytes 3 and 4";
+VAL_ 2147484672 Mode 0 "eco" 1 "normal"
+  2 "sport" ;
+"#;
+
+    fn dbc() -> Dbc {
+        Dbc::parse(TEST_DBC).unwrap()
+    }
+
+    #[test]
+    fn parses_definitions() {
+        let dbc = dbc();
+        assert_eq!(dbc.messages().len(), 2);
+
+        let engine = dbc.message("Engine").unwrap();
+        assert_eq!((engine.id, engine.extended, engine.size), (256, false, 8));
+        assert_eq!(engine.transmitter, "ECU");
+        let torque = engine.signal("Torque").unwrap();
+        assert!(torque.signed);
+        assert_eq!(torque.unit, "Nm");
+        assert_eq!(torque.receivers, ["DASH", "GW"]);
+        assert_eq!(
+            engine.signal("Pressure").unwrap().byte_order,
+            ByteOrder::BigEndian
+        );
+
+        let drive = dbc.message_by_id(0x400, true).unwrap();
+        assert_eq!(drive.name, "Drive");
+        assert!(dbc.message_by_id(0x400, false).is_none());
+        assert_eq!(drive.multiplexor().unwrap().name, "Page");
+        assert_eq!(drive.multiplex_values(), [0, 1]);
+        assert_eq!(drive.signal("Mode").unwrap().label(2), Some("sport"));
+    }
+
+    #[test]
+    fn encodes_signal_layouts() {
+        let frame = dbc()
+            .encode(
+                "Engine",
+                &[
+                    ("Rpm", SignalValue::Physical(3000.0)),
+                    ("Coolant", SignalValue::Physical(90.0)),
+                    ("Pressure", SignalValue::Physical(100.0)),
+                    ("Torque", SignalValue::Physical(-10.0)),
+                ],
+            )
+            .unwrap();
+        assert_eq!(frame.id, 256);
+        assert!(!frame.extended);
+        // Rpm 12000 小端；Coolant 130；Pressure 1000 大端跨字节；Torque -20 的 12 位补码
+        assert_eq!(frame.data, [0xE0, 0x2E, 0x82, 0x03, 0xE8, 0xEC, 0x0F, 0x00]);
+    }
+
+    #[test]
+    fn round_trips_physical_values() {
+        let dbc = dbc();
+        let values = [
+            ("Rpm", 812.25),
+            ("Coolant", -40.0),
+            ("Pressure", 6553.5),
+            ("Torque", -1024.0),
+        ];
+        let signals: Vec<_> = values
+            .iter()
+            .map(|(name, value)| (*name, SignalValue::Physical(*value)))
+            .collect();
+        let frame = dbc.encode("Engine", &signals).unwrap();
+
+        let (message, readings) = dbc.decode(&frame).unwrap();
+        assert_eq!(message.name, "Engine");
+        let readings = readings.unwrap();
+        for (name, value) in values {
+            assert!(
+                (readings[name].physical - value).abs() < 1e-9,
+                "{}: {} != {}",
+                name,
+                readings[name].physical,
+                value
+            );
+        }
+        assert_eq!(readings["Torque"].raw, -2048);
+    }
+
+    #[test]
+    fn multiplexed_pages() {
+        let dbc = dbc();
+        let frame = dbc
+            .encode(
+                "Drive",
+                &[
+                    ("Page", SignalValue::Physical(1.0)),
+                    ("Mode", SignalValue::Label("sport")),
+                ],
+            )
+            .unwrap();
+        assert!(frame.extended);
+        assert_eq!(frame.data, [0x01, 0x02]);
+
+        let readings = dbc.decode(&frame).unwrap().1.unwrap();
+        assert_eq!(readings["Mode"].label.as_deref(), Some("sport"));
+        assert!(!readings.contains_key("Gear"));
+
+        // 未给出复用信号时取第 0 页
+        let frame = dbc
+            .encode("Drive", &[("Gear", SignalValue::Physical(5.0))])
+            .unwrap();
+        let readings = dbc.decode(&frame).unwrap().1.unwrap();
+        assert_eq!(readings["Gear"].raw, 5);
+        assert!(!readings.contains_key("Mode"));
+    }
+
+    #[test]
+    fn rejects_invalid_values() {
+        let dbc = dbc();
+        let encode = |signals: &[(&str, SignalValue)]| dbc.encode("Engine", signals);
+        assert!(encode(&[("Coolant", SignalValue::Physical(300.0))]).is_err());
+        assert!(encode(&[("Torque", SignalValue::Physical(-1024.5))]).is_err());
+        assert!(encode(&[("Boost", SignalValue::Physical(1.0))]).is_err());
+        assert!(encode(&[("Rpm", SignalValue::Label("idle"))]).is_err());
+        assert!(dbc
+            .encode("Drive", &[("Mode", SignalValue::Label("turbo"))])
+            .is_err());
+        // Gear 仅在第 0 页有效
+        assert!(dbc
+            .encode(
+                "Drive",
+                &[
+                    ("Page", SignalValue::Physical(1.0)),
+                    ("Gear", SignalValue::Physical(2.0)),
+                ],
+            )
+            .is_err());
+        assert!(dbc.encode("Brake", &[]).is_err());
+
+        let short = CanFrame::new(256, false, &[0; 4]).unwrap();
+        assert!(dbc.decode(&short).unwrap().1.is_err());
+        let unknown = CanFrame::new(0x123, false, &[0; 8]).unwrap();
+        assert!(dbc.decode(&unknown).is_none());
+    }
+
+    #[test]
+    fn rejects_invalid_definitions() {
+        let invalid = [
+            "BO_ 1 Short: 1 ECU\n SG_ Wide : 0|16@1+ (1,0) [0|0] \"\" DASH",
+            "BO_ 1 Zero: 1 ECU\n SG_ Flat : 0|8@1+ (0,0) [0|0] \"\" DASH",
+            "BO_ 1 Pages: 2 ECU\n SG_ Value m1 : 8|8@1+ (1,0) [0|0] \"\" DASH",
+            "BO_ 1 Order: 1 ECU\n SG_ Value : 0|8@2+ (1,0) [0|0] \"\" DASH",
+            "BO_ 1 Fd: 64 ECU",
+            "SG_ Orphan : 0|8@1+ (1,0) [0|0] \"\" DASH",
+            "VAL_ 1 Missing 0 \"off\" ;",
+            "VAL_ 1 Open 0 \"off\"",
+        ];
+        for source in invalid {
+            assert!(Dbc::parse(source).is_err(), "accepted {:?}", source);
+        }
+        Dbc::default();
+    }
+}
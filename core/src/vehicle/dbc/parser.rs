@@ -0,0 +1,371 @@
+use super::{ByteOrder, Dbc, Message, Multiplexing, Signal};
+use anyhow::{anyhow, Context};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+/// 扩展帧标志位（DBC 中报文 ID 的最高位）
+const EXTENDED_FLAG: u32 = 0x8000_0000;
+
+/// 以分号结尾、可能跨行的语句
+const TERMINATED: [&str; 14] = [
+    "VAL_",
+    "VAL_TABLE_",
+    "CM_",
+    "BA_DEF_",
+    "BA_DEF_DEF_",
+    "BA_",
+    "BA_DEF_REL_",
+    "BA_DEF_DEF_REL_",
+    "BA_REL_",
+    "SIG_VALTYPE_",
+    "SIG_GROUP_",
+    "SG_MUL_VAL_",
+    "BO_TX_BU_",
+    "EV_",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Str(String),
+    Punct(char),
+}
+
+/// 词法游标
+struct Cursor {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Cursor {
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn word(&mut self) -> Result<String, anyhow::Error> {
+        match self.next() {
+            Some(Token::Word(word)) => Ok(word),
+            other => Err(anyhow!("expected identifier, found {:?}", other)),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, anyhow::Error> {
+        match self.next() {
+            Some(Token::Str(s)) => Ok(s),
+            other => Err(anyhow!("expected string, found {:?}", other)),
+        }
+    }
+
+    fn number<T: FromStr>(&mut self) -> Result<T, anyhow::Error> {
+        let word = self.word()?;
+        word.parse()
+            .map_err(|_| anyhow!("expected number, found {:?}", word))
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), anyhow::Error> {
+        match self.next() {
+            Some(Token::Punct(p)) if p == c => Ok(()),
+            other => Err(anyhow!("expected {:?}, found {:?}", c, other)),
+        }
+    }
+}
+
+/// 解析 DBC 文本
+pub(super) fn parse(source: &str) -> Result<Dbc, anyhow::Error> {
+    let mut messages: Vec<Message> = Vec::new();
+    let mut values = Vec::new(); // VAL_ 可能出现在报文定义之前
+    let mut in_symbols = false;
+
+    let mut lines = source.lines().enumerate();
+    while let Some((number, line)) = lines.next() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with("//") {
+            continue;
+        }
+        // NS_ 段落中缩进的符号名不是语句
+        if in_symbols && line.starts_with(char::is_whitespace) {
+            continue;
+        }
+        in_symbols = false;
+
+        let keyword = trimmed.split_whitespace().next().unwrap_or_default();
+        let keyword = keyword.trim_end_matches(':');
+        let mut statement = trimmed.to_string();
+        if TERMINATED.contains(&keyword) {
+            while !is_terminated(&statement) {
+                let Some((_, next)) = lines.next() else {
+                    return Err(anyhow!("line {}: unterminated {}", number + 1, keyword));
+                };
+                statement.push('\n');
+                statement.push_str(next);
+            }
+        }
+
+        let result = (|| -> Result<(), anyhow::Error> {
+            match keyword {
+                "NS_" => in_symbols = true,
+                "BO_" => messages.push(parse_message(&mut cursor(&statement)?)?),
+                "SG_" => {
+                    let signal = parse_signal(&mut cursor(&statement)?)?;
+                    messages
+                        .last_mut()
+                        .ok_or_else(|| anyhow!("signal {} outside of a message", signal.name))?
+                        .signals
+                        .push(signal);
+                }
+                "VAL_" => values.push(parse_values(&mut cursor(&statement)?)?),
+                // 其余语句（节点、注释、属性、具名值表等）不影响编解码
+                _ => {}
+            }
+            Ok(())
+        })();
+        result.with_context(|| format!("line {}: {}", number + 1, trimmed))?;
+    }
+
+    for (id, name, descriptions) in values {
+        let signal = messages
+            .iter_mut()
+            .find(|m| m.raw_id() == id)
+            .and_then(|m| m.signals.iter_mut().find(|s| s.name == name))
+            .ok_or_else(|| anyhow!("value table for unknown signal {} in {}", name, id))?;
+        signal.values = descriptions;
+    }
+
+    for message in &messages {
+        validate(message)?;
+    }
+
+    Ok(Dbc { messages })
+}
+
+impl Message {
+    /// DBC 中的报文 ID（扩展帧带最高位标志）
+    fn raw_id(&self) -> u32 {
+        if self.extended {
+            self.id | EXTENDED_FLAG
+        } else {
+            self.id
+        }
+    }
+}
+
+/// BO_ <id> <name>: <size> <transmitter>
+fn parse_message(cursor: &mut Cursor) -> Result<Message, anyhow::Error> {
+    cursor.word()?;
+    let raw_id: u32 = cursor.number()?;
+    let name = cursor.word()?;
+    cursor.expect(':')?;
+    let size = cursor.number()?;
+    let transmitter = cursor.word().unwrap_or_default();
+
+    Ok(Message {
+        id: raw_id & !EXTENDED_FLAG,
+        extended: raw_id & EXTENDED_FLAG != 0,
+        name,
+        size,
+        transmitter,
+        signals: Vec::new(),
+    })
+}
+
+/// SG_ <name> [M|m<n>] : <start>|<size>@<order><sign> (<factor>,<offset>) [<min>|<max>] "<unit>" <receivers>
+fn parse_signal(cursor: &mut Cursor) -> Result<Signal, anyhow::Error> {
+    cursor.word()?;
+    let name = cursor.word()?;
+
+    let multiplexing = match cursor.peek() {
+        Some(Token::Word(word)) if word == "M" => Multiplexing::Multiplexor,
+        Some(Token::Word(word)) if word.starts_with('m') => {
+            let id = word[1..]
+                .parse()
+                .map_err(|_| anyhow!("unsupported multiplexing {:?}", word))?;
+            Multiplexing::Multiplexed(id)
+        }
+        _ => Multiplexing::None,
+    };
+    if multiplexing != Multiplexing::None {
+        cursor.next();
+    }
+
+    cursor.expect(':')?;
+    let start_bit = cursor.number()?;
+    cursor.expect('|')?;
+    let size: u32 = cursor.number()?;
+    cursor.expect('@')?;
+    let layout = cursor.word()?;
+    let (byte_order, signed) = match layout.as_str() {
+        "1+" => (ByteOrder::LittleEndian, false),
+        "1-" => (ByteOrder::LittleEndian, true),
+        "0+" => (ByteOrder::BigEndian, false),
+        "0-" => (ByteOrder::BigEndian, true),
+        _ => return Err(anyhow!("invalid signal layout {:?}", layout)),
+    };
+    if !(1..=64).contains(&size) {
+        return Err(anyhow!("invalid signal size {}", size));
+    }
+
+    cursor.expect('(')?;
+    let factor = cursor.number()?;
+    cursor.expect(',')?;
+    let offset = cursor.number()?;
+    cursor.expect(')')?;
+    cursor.expect('[')?;
+    let min = cursor.number()?;
+    cursor.expect('|')?;
+    let max = cursor.number()?;
+    cursor.expect(']')?;
+    let unit = cursor.string()?;
+
+    let mut receivers = Vec::new();
+    while let Some(token) = cursor.next() {
+        if let Token::Word(receiver) = token {
+            receivers.push(receiver);
+        }
+    }
+
+    Ok(Signal {
+        name,
+        start_bit,
+        size,
+        byte_order,
+        signed,
+        factor,
+        offset,
+        min,
+        max,
+        unit,
+        receivers,
+        multiplexing,
+        values: BTreeMap::new(),
+    })
+}
+
+/// VAL_ <id> <signal> <value> "<description>" ... ;
+fn parse_values(
+    cursor: &mut Cursor,
+) -> Result<(u32, String, BTreeMap<i64, String>), anyhow::Error> {
+    cursor.word()?;
+    let id = cursor.number()?;
+    let signal = cursor.word()?;
+    Ok((id, signal, parse_value_descriptions(cursor)?))
+}
+
+fn parse_value_descriptions(cursor: &mut Cursor) -> Result<BTreeMap<i64, String>, anyhow::Error> {
+    let mut descriptions = BTreeMap::new();
+    while !matches!(cursor.peek(), Some(Token::Punct(';')) | None) {
+        let value = cursor.number()?;
+        descriptions.insert(value, cursor.string()?);
+    }
+    Ok(descriptions)
+}
+
+/// 校验信号位布局不超出报文长度
+fn validate(message: &Message) -> Result<(), anyhow::Error> {
+    if message.size > 8 {
+        return Err(anyhow!(
+            "{}: CAN FD messages ({} bytes) are not supported",
+            message.name,
+            message.size
+        ));
+    }
+    for signal in &message.signals {
+        if signal.last_byte() >= message.size {
+            return Err(anyhow!(
+                "{}: signal {} exceeds message length",
+                message.name,
+                signal.name
+            ));
+        }
+        if signal.factor == 0.0 {
+            return Err(anyhow!(
+                "{}: signal {} has zero factor",
+                message.name,
+                signal.name
+            ));
+        }
+    }
+    let multiplexors = message
+        .signals
+        .iter()
+        .filter(|s| s.multiplexing == Multiplexing::Multiplexor)
+        .count();
+    if multiplexors > 1 {
+        return Err(anyhow!("{}: multiple multiplexor signals", message.name));
+    }
+    if multiplexors == 0 && !message.multiplex_values().is_empty() {
+        return Err(anyhow!(
+            "{}: multiplexed signals without multiplexor",
+            message.name
+        ));
+    }
+    Ok(())
+}
+
+/// 语句是否以引号外的分号结束
+fn is_terminated(statement: &str) -> bool {
+    let mut quoted = false;
+    let mut escaped = false;
+    let mut terminated = false;
+    for c in statement.chars() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            ';' if !quoted => terminated = true,
+            c if !c.is_whitespace() => terminated = false,
+            _ => {}
+        }
+    }
+    terminated
+}
+
+fn cursor(statement: &str) -> Result<Cursor, anyhow::Error> {
+    Ok(Cursor {
+        tokens: tokenize(statement)?,
+        pos: 0,
+    })
+}
+
+fn tokenize(statement: &str) -> Result<Vec<Token>, anyhow::Error> {
+    const PUNCTUATION: &str = ":|@()[],;";
+
+    let mut tokens = Vec::new();
+    let mut chars = statement.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '"' {
+            chars.next();
+            let mut text = String::new();
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some('\\') => text.extend(chars.next()),
+                    Some(c) => text.push(c),
+                    None => return Err(anyhow!("unterminated string")),
+                }
+            }
+            tokens.push(Token::Str(text));
+        } else if PUNCTUATION.contains(c) {
+            chars.next();
+            tokens.push(Token::Punct(c));
+        } else {
+            let mut word = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() || c == '"' || PUNCTUATION.contains(c) {
+                    break;
+                }
+                word.push(c);
+                chars.next();
+            }
+            tokens.push(Token::Word(word));
+        }
+    }
+    Ok(tokens)
+}
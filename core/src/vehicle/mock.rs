@@ -0,0 +1,200 @@
+use super::codec::Codec;
+use super::{CanFrame, VehicleBus, VehicleState};
+use crate::intent::command::{WindowAction, WindowPosition, Zone};
+use crate::intent::VehicleCommand;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// 模拟总线的共享状态
+struct Inner {
+    sent: Vec<CanFrame>,
+    inbox: VecDeque<CanFrame>,
+    ecu: VehicleState, // 模拟 ECU 维护的车辆状态
+    codec: Codec,
+}
+
+/// 内存模拟总线：记录发出的帧，并由内置 ECU 执行指令、回送状态帧
+#[derive(Clone)]
+pub struct MockBus {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl MockBus {
+    pub fn new() -> Self {
+        Self::with_codec(Codec::default())
+    }
+
+    /// 使用指定的 DBC 编解码器
+    pub fn with_codec(codec: Codec) -> Self {
+        let ecu = VehicleState {
+            ignition_on: true,
+            fuel_percent: 60.0,
+            cabin_celsius: 26.0,
+            driver_celsius: 22.0,
+            passenger_celsius: 22.0,
+            rear_celsius: 22.0,
+            ..VehicleState::default()
+        };
+        let mut inner = Inner {
+            sent: Vec::new(),
+            inbox: VecDeque::new(),
+            ecu,
+            codec,
+        };
+        // 上电时广播一次状态
+        broadcast(&mut inner);
+        Self {
+            inner: Arc::new(Mutex::new(inner)),
+        }
+    }
+
+    /// 已发送的全部帧
+    #[cfg(test)]
+    pub fn sent(&self) -> Vec<CanFrame> {
+        self.inner.lock().unwrap().sent.clone()
+    }
+
+    /// 向接收队列注入一帧（模拟其他节点发出的报文）
+    #[cfg(test)]
+    pub fn inject(&self, frame: CanFrame) {
+        self.inner.lock().unwrap().inbox.push_back(frame);
+    }
+
+    /// 修改模拟 ECU 的车辆状态并广播状态帧
+    #[cfg(test)]
+    pub fn update_state(&self, update: impl FnOnce(&mut VehicleState)) {
+        let mut inner = self.inner.lock().unwrap();
+        update(&mut inner.ecu);
+        broadcast(&mut inner);
+    }
+}
+
+impl Default for MockBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VehicleBus for MockBus {
+    fn send(&mut self, frame: &CanFrame) -> Result<(), anyhow::Error> {
+        let mut inner = self.inner.lock().unwrap();
+        inner.sent.push(frame.clone());
+
+        let command = inner.codec.decode_command(frame)?;
+        if let Some(command) = command {
+            apply(&mut inner.ecu, &command);
+            broadcast(&mut inner);
+        }
+        Ok(())
+    }
+
+    fn recv(&mut self, _timeout: Duration) -> Result<Option<CanFrame>, anyhow::Error> {
+        Ok(self.inner.lock().unwrap().inbox.pop_front())
+    }
+}
+
+/// 模拟 ECU 执行指令
+fn apply(state: &mut VehicleState, command: &VehicleCommand) {
+    match command {
+        VehicleCommand::SetTemperature { zone, celsius } => match zone {
+            Zone::Driver => state.driver_celsius = *celsius,
+            Zone::Passenger => state.passenger_celsius = *celsius,
+            Zone::Rear => state.rear_celsius = *celsius,
+            Zone::All => {
+                state.driver_celsius = *celsius;
+                state.passenger_celsius = *celsius;
+                state.rear_celsius = *celsius;
+            }
+        },
+        VehicleCommand::Window { which, action } => {
+            let percent = match action {
+                WindowAction::Open => 100,
+                WindowAction::Close => 0,
+            };
+            let index = match which {
+                WindowPosition::FrontLeft => Some(0),
+                WindowPosition::FrontRight => Some(1),
+                WindowPosition::RearLeft => Some(2),
+                WindowPosition::RearRight => Some(3),
+                WindowPosition::All => None,
+            };
+            match index {
+                Some(i) => state.windows[i] = percent,
+                None => state.windows = [percent; 4],
+            }
+        }
+        VehicleCommand::Media { .. } | VehicleCommand::Query { .. } => {}
+    }
+}
+
+fn broadcast(inner: &mut Inner) {
+    if let Ok(frames) = inner.codec.encode_status(&inner.ecu) {
+        inner.inbox.extend(frames);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vehicle::VehicleController;
+
+    fn controller() -> (MockBus, VehicleController) {
+        let bus = MockBus::new();
+        let controller = VehicleController::new(Box::new(bus.clone()), Codec::default());
+        (bus, controller)
+    }
+
+    #[test]
+    fn ecu_executes_commands() {
+        let (bus, mut controller) = controller();
+        controller.poll().unwrap();
+        assert_eq!(controller.state().driver_celsius, 22.0);
+
+        controller
+            .execute(&VehicleCommand::SetTemperature {
+                zone: Zone::All,
+                celsius: 19.5,
+            })
+            .unwrap();
+        controller
+            .execute(&VehicleCommand::Window {
+                which: WindowPosition::FrontRight,
+                action: WindowAction::Open,
+            })
+            .unwrap();
+        assert_eq!(bus.sent().len(), 2);
+
+        controller.poll().unwrap();
+        let state = controller.state();
+        assert_eq!(
+            (
+                state.driver_celsius,
+                state.passenger_celsius,
+                state.rear_celsius
+            ),
+            (19.5, 19.5, 19.5)
+        );
+        assert_eq!(state.windows, [0, 100, 0, 0]);
+    }
+
+    #[test]
+    fn receives_injected_and_broadcast_frames() {
+        let (bus, mut controller) = controller();
+        bus.update_state(|state| {
+            state.speed_kph = 50.0;
+            state.fuel_percent = 12.0;
+        });
+        controller.poll().unwrap();
+        assert_eq!(controller.state().speed_kph, 50.0);
+        assert_eq!(controller.state().fuel_percent, 12.0);
+
+        // 其他节点发出的窗户状态
+        bus.inject(CanFrame::new(1185, false, &[10, 20, 30, 40]).unwrap());
+        // 未定义的报文忽略
+        bus.inject(CanFrame::new(0x7FF, false, &[0xFF]).unwrap());
+        controller.poll().unwrap();
+        assert_eq!(controller.state().windows, [10, 20, 30, 40]);
+        assert!(bus.sent().is_empty());
+    }
+}
@@ -0,0 +1,198 @@
+/*
+    车辆控制抽象
+    意图层产生的车控指令经编码后以 CAN 帧发送到总线，
+    总线上的状态帧解码后更新车辆状态：
+    1. socketcan：Linux SocketCAN 实现（可在 vcan 虚拟接口上调试）
+    2. mock：内存模拟总线，内置简易 ECU 响应指令
+    3. dbc：DBC 信号定义的解析与按信号名编解码
+    4. codec：基于 DBC 的指令 / 状态编解码
+    5. obd：ELM327 适配器上的 OBD-II 诊断（实时数据与故障码）
+*/
+
+pub mod codec;
+pub mod dbc;
+pub mod mock;
+pub mod obd;
+#[cfg(target_os = "linux")]
+pub mod socketcan;
+
+use crate::intent::command::VehicleQuery;
+use crate::intent::VehicleCommand;
+use anyhow::anyhow;
+use codec::Codec;
+use crossbeam_channel::Receiver;
+use obd::ObdReading;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// 标准帧 / 扩展帧标识符上限
+const STANDARD_ID_MAX: u32 = 0x7FF;
+const EXTENDED_ID_MAX: u32 = 0x1FFF_FFFF;
+
+/// CAN 数据帧（经典 CAN，最多8字节）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CanFrame {
+    pub id: u32,
+    pub extended: bool,
+    pub data: Vec<u8>,
+}
+
+impl CanFrame {
+    /// 帧格式由调用方指定：标准帧 ID 不超过 11 位，扩展帧不超过 29 位
+    pub fn new(id: u32, extended: bool, data: &[u8]) -> Result<Self, anyhow::Error> {
+        if data.len() > 8 {
+            return Err(anyhow!("CAN frame payload too long: {} bytes", data.len()));
+        }
+        let max = if extended {
+            EXTENDED_ID_MAX
+        } else {
+            STANDARD_ID_MAX
+        };
+        if id > max {
+            return Err(anyhow!(
+                "invalid {} CAN id: {:#x}",
+                if extended { "extended" } else { "standard" },
+                id
+            ));
+        }
+        Ok(Self {
+            id,
+            extended,
+            data: data.to_vec(),
+        })
+    }
+}
+
+/// 车辆总线接口
+pub trait VehicleBus: Send {
+    fn send(&mut self, frame: &CanFrame) -> Result<(), anyhow::Error>;
+
+    /// 接收一帧，超时返回 None
+    fn recv(&mut self, timeout: Duration) -> Result<Option<CanFrame>, anyhow::Error>;
+}
+
+/// 车辆状态（由状态帧解码更新）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct VehicleState {
+    pub ignition_on: bool,
+    pub speed_kph: f32,
+    pub fuel_percent: f32,
+    pub cabin_celsius: f32,
+    pub driver_celsius: f32,
+    pub passenger_celsius: f32,
+    pub rear_celsius: f32,
+    pub windows: [u8; 4], // 开度百分比：左前、右前、左后、右后
+    // 以下来自 OBD 诊断，未连接适配器时为空
+    pub engine_rpm: Option<f32>,
+    pub coolant_celsius: Option<f32>,
+    pub check_engine_on: Option<bool>,
+    pub trouble_codes: Option<Vec<String>>,
+}
+
+impl VehicleState {
+    /// 车况查询的语音回复
+    pub fn describe(&self, query: VehicleQuery) -> String {
+        match query {
+            VehicleQuery::FuelLevel => format!("Fuel level is {:.0} percent", self.fuel_percent),
+            VehicleQuery::Speed => {
+                format!("You are going {:.0} kilometers per hour", self.speed_kph)
+            }
+            VehicleQuery::CoolantTemperature => match self.coolant_celsius {
+                Some(celsius) => format!("Coolant temperature is {:.0} degrees", celsius),
+                None => "Coolant temperature is not available".to_string(),
+            },
+            VehicleQuery::EngineWarnings => match (&self.trouble_codes, self.check_engine_on) {
+                (None, _) => "Engine diagnostics are not available".to_string(),
+                (Some(codes), Some(true)) if codes.is_empty() => {
+                    "The check engine light is on, but no trouble codes are stored".to_string()
+                }
+                (Some(codes), _) if codes.is_empty() => "No engine warnings".to_string(),
+                (Some(codes), _) => format!(
+                    "{} engine warning{}: {}",
+                    codes.len(),
+                    if codes.len() == 1 { "" } else { "s" },
+                    codes.join(", ")
+                ),
+            },
+        }
+    }
+}
+
+/// 车辆控制器：指令下发 + 状态维护
+pub struct VehicleController {
+    bus: Box<dyn VehicleBus>,
+    codec: Codec,
+    state: VehicleState,
+    obd: Option<Receiver<ObdReading>>,
+}
+
+impl VehicleController {
+    pub fn new(bus: Box<dyn VehicleBus>, codec: Codec) -> Self {
+        Self {
+            bus,
+            codec,
+            state: VehicleState::default(),
+            obd: None,
+        }
+    }
+
+    /// 按配置打开总线（未指定接口时使用内存模拟总线）与 DBC（未指定时使用内置定义）
+    pub fn open(interface: Option<&str>, dbc: Option<&str>) -> Result<Self, anyhow::Error> {
+        let codec = Codec::load(dbc)?;
+        let bus: Box<dyn VehicleBus> = match interface {
+            #[cfg(target_os = "linux")]
+            Some(interface) => Box::new(socketcan::SocketCanBus::open(interface)?),
+            #[cfg(not(target_os = "linux"))]
+            Some(interface) => return Err(anyhow!("SocketCAN unavailable for {}", interface)),
+            None => Box::new(mock::MockBus::with_codec(codec.clone())),
+        };
+        Ok(Self::new(bus, codec))
+    }
+
+    /// 接入 OBD 轮询结果
+    pub fn attach_obd(&mut self, readings: Receiver<ObdReading>) {
+        self.obd = Some(readings);
+    }
+
+    pub fn state(&self) -> &VehicleState {
+        &self.state
+    }
+
+    /// 编码并发送车控指令
+    pub fn execute(&mut self, command: &VehicleCommand) -> Result<(), anyhow::Error> {
+        for frame in self.codec.encode_command(command)? {
+            self.bus.send(&frame)?;
+        }
+        Ok(())
+    }
+
+    /// 读取总线上所有待处理的状态帧并更新车辆状态
+    pub fn poll(&mut self) -> Result<(), anyhow::Error> {
+        while let Some(frame) = self.bus.recv(Duration::ZERO)? {
+            self.codec.decode_status(&frame, &mut self.state);
+        }
+        if let Some(obd) = &self.obd {
+            for reading in obd.try_iter() {
+                reading.apply(&mut self.state);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frame_format_is_explicit() {
+        let frame = CanFrame::new(0x100, true, &[1, 2]).unwrap();
+        assert!(frame.extended);
+        assert!(!CanFrame::new(0x100, false, &[]).unwrap().extended);
+
+        assert!(CanFrame::new(STANDARD_ID_MAX + 1, false, &[]).is_err());
+        assert!(CanFrame::new(STANDARD_ID_MAX + 1, true, &[]).is_ok());
+        assert!(CanFrame::new(EXTENDED_ID_MAX + 1, true, &[]).is_err());
+        assert!(CanFrame::new(1, false, &[0; 9]).is_err());
+    }
+}
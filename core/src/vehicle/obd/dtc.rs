@@ -0,0 +1,75 @@
+use std::fmt;
+
+/// 诊断故障码（如 P0133）
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Dtc(pub String);
+
+impl Dtc {
+    /// 由两个字节解码：高两位为系统（P/C/B/U），其余为四位编号
+    pub fn from_bytes(a: u8, b: u8) -> Self {
+        let system = ['P', 'C', 'B', 'U'][(a >> 6) as usize];
+        Self(format!(
+            "{}{:X}{:X}{:02X}",
+            system,
+            (a >> 4) & 0x3,
+            a & 0xF,
+            b
+        ))
+    }
+}
+
+impl fmt::Display for Dtc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// 解码 Mode 03 响应数据（不含模式字节）
+/// CAN 协议下首字节为故障码数量（数据长度为奇数），旧协议固定补齐为3个故障码
+pub fn decode_dtcs(data: &[u8]) -> Vec<Dtc> {
+    let pairs = if data.len() % 2 == 1 {
+        &data[1..]
+    } else {
+        data
+    };
+    pairs
+        .chunks_exact(2)
+        .filter(|pair| pair != &[0, 0])
+        .map(|pair| Dtc::from_bytes(pair[0], pair[1]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn codes(data: &[u8]) -> Vec<String> {
+        decode_dtcs(data).iter().map(Dtc::to_string).collect()
+    }
+
+    #[test]
+    fn from_bytes() {
+        assert_eq!(Dtc::from_bytes(0x01, 0x33).to_string(), "P0133");
+        assert_eq!(Dtc::from_bytes(0x41, 0x23).to_string(), "C0123");
+        assert_eq!(Dtc::from_bytes(0x9A, 0xBC).to_string(), "B1ABC");
+        assert_eq!(Dtc::from_bytes(0xC1, 0x00).to_string(), "U0100");
+    }
+
+    #[test]
+    fn can_layout_with_count() {
+        // CAN：首字节为数量，数据长度为奇数
+        assert_eq!(codes(&[0x02, 0x01, 0x33, 0x03, 0x00]), ["P0133", "P0300"]);
+        assert!(codes(&[0x00]).is_empty());
+    }
+
+    #[test]
+    fn legacy_padded_layout() {
+        // 旧协议：固定3个故障码，不足时以 00 00 填充
+        assert_eq!(codes(&[0x01, 0x33, 0x00, 0x00, 0x00, 0x00]), ["P0133"]);
+        assert_eq!(
+            codes(&[0x01, 0x33, 0x41, 0x23, 0xC1, 0x00]),
+            ["P0133", "C0123", "U0100"]
+        );
+        assert!(codes(&[0; 6]).is_empty());
+    }
+}
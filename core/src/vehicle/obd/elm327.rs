@@ -0,0 +1,233 @@
+use anyhow::{anyhow, Context};
+use serialport::SerialPort;
+use std::io::{self, Read, Write};
+use std::time::{Duration, Instant};
+
+/// 单条指令的响应超时（首次查询时适配器需搜索协议，耗时较长）
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(5);
+/// 串口单次读取超时
+const READ_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// 适配器返回的错误响应
+const ERRORS: [&str; 8] = [
+    "?",
+    "UNABLE TO CONNECT",
+    "CAN ERROR",
+    "BUS INIT",
+    "BUS ERROR",
+    "STOPPED",
+    "ERR",
+    "BUFFER FULL",
+];
+
+/// ELM327 兼容适配器（AT 指令集）
+pub struct Elm327 {
+    port: Box<dyn SerialPort>,
+    version: String,
+}
+
+impl Elm327 {
+    pub fn open(path: &str, baud_rate: u32) -> Result<Self, anyhow::Error> {
+        let port = serialport::new(path, baud_rate)
+            .timeout(READ_TIMEOUT)
+            .open()
+            .with_context(|| format!("failed to open OBD adapter: {}", path))?;
+        let mut elm = Self {
+            port,
+            version: String::new(),
+        };
+        elm.initialize()?;
+        Ok(elm)
+    }
+
+    /// 适配器版本信息（ATZ 响应）
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// 复位并设置：关闭回显、换行与报文头，自动选择协议
+    fn initialize(&mut self) -> Result<(), anyhow::Error> {
+        self.version = self.command("ATZ")?;
+        for setting in ["ATE0", "ATL0", "ATS1", "ATH0", "ATSP0"] {
+            let response = self.command(setting)?;
+            if !response.contains("OK") {
+                return Err(anyhow!("{} failed: {:?}", setting, response));
+            }
+        }
+        Ok(())
+    }
+
+    /// 发送指令并读取到提示符 `>` 为止的响应
+    pub fn command(&mut self, command: &str) -> Result<String, anyhow::Error> {
+        self.port.clear(serialport::ClearBuffer::Input)?;
+        self.port.write_all(format!("{}\r", command).as_bytes())?;
+        self.port.flush()?;
+
+        let deadline = Instant::now() + RESPONSE_TIMEOUT;
+        let mut response = Vec::new();
+        let mut buffer = [0u8; 64];
+        loop {
+            match self.port.read(&mut buffer) {
+                Ok(0) => {}
+                Ok(n) => {
+                    response.extend_from_slice(&buffer[..n]);
+                    if response.contains(&b'>') {
+                        break;
+                    }
+                }
+                Err(e) if e.kind() == io::ErrorKind::TimedOut => {}
+                Err(e) => return Err(e.into()),
+            }
+            if Instant::now() > deadline {
+                return Err(anyhow!("no response to {}", command));
+            }
+        }
+
+        let text = String::from_utf8_lossy(&response);
+        let text = text.split('>').next().unwrap_or_default();
+        // 即使关闭回显，ATZ 复位后首条响应仍可能带回显
+        let text = text.trim().trim_start_matches(command).trim();
+        Ok(text.to_string())
+    }
+
+    /// 发送 OBD 请求，返回各 ECU 响应中模式（与 PID）字节之后的数据
+    pub fn request(&mut self, mode: u8, pid: Option<u8>) -> Result<Vec<Vec<u8>>, anyhow::Error> {
+        let command = match pid {
+            Some(pid) => format!("{:02X}{:02X}", mode, pid),
+            None => format!("{:02X}", mode),
+        };
+        let response = self.command(&command)?;
+        parse_response(&command, mode, pid, &response)
+    }
+}
+
+/// 解析 OBD 请求的响应文本，返回各 ECU 响应中模式（与 PID）字节之后的数据
+fn parse_response(
+    command: &str,
+    mode: u8,
+    pid: Option<u8>,
+    response: &str,
+) -> Result<Vec<Vec<u8>>, anyhow::Error> {
+    let lines: Vec<&str> = response
+        .lines()
+        .flat_map(|line| line.split('\r'))
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with("SEARCHING"))
+        .collect();
+    if lines.iter().any(|line| line.contains("NO DATA")) {
+        return Ok(Vec::new());
+    }
+    if let Some(error) = lines
+        .iter()
+        .find(|line| ERRORS.iter().any(|e| line.starts_with(e)))
+    {
+        return Err(anyhow!("{} failed: {}", command, error));
+    }
+
+    let header = match pid {
+        Some(pid) => vec![mode + 0x40, pid],
+        None => vec![mode + 0x40],
+    };
+    let mut results = Vec::new();
+    for bytes in assemble(&lines)? {
+        if let Some(data) = bytes.strip_prefix(header.as_slice()) {
+            results.push(data.to_vec());
+        }
+    }
+    Ok(results)
+}
+
+/// 合并多帧响应（CAN 协议下形如 `0: 43 ...`、`1: ...` 的分段），每个 ECU 一条
+fn assemble(lines: &[&str]) -> Result<Vec<Vec<u8>>, anyhow::Error> {
+    let segmented = lines.iter().any(|line| is_segment(line));
+    if !segmented {
+        return lines.iter().map(|line| parse_hex(line)).collect();
+    }
+
+    // 分段前一行为报文总字节数，末段的填充字节按总长截断
+    let mut length = None;
+    let mut message = Vec::new();
+    for line in lines {
+        match line.split_once(':') {
+            Some((_, data)) => message.extend(parse_hex(data)?),
+            None => length = usize::from_str_radix(line, 16).ok(),
+        }
+    }
+    if let Some(length) = length {
+        message.truncate(length);
+    }
+    Ok(vec![message])
+}
+
+fn is_segment(line: &str) -> bool {
+    line.split_once(':').is_some_and(|(index, _)| {
+        !index.is_empty() && index.trim().chars().all(|c| c.is_ascii_hexdigit())
+    })
+}
+
+/// 解析十六进制字节（空格可有可无）
+fn parse_hex(text: &str) -> Result<Vec<u8>, anyhow::Error> {
+    let digits: Vec<char> = text.chars().filter(|c| !c.is_whitespace()).collect();
+    if !digits.len().is_multiple_of(2) {
+        return Err(anyhow!("malformed response: {:?}", text));
+    }
+    digits
+        .chunks(2)
+        .map(|pair| {
+            let byte: String = pair.iter().collect();
+            u8::from_str_radix(&byte, 16).map_err(|_| anyhow!("malformed response: {:?}", text))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_ecu() {
+        let data = parse_response("010C", 0x01, Some(0x0C), "41 0C 1A F8").unwrap();
+        assert_eq!(data, [vec![0x1A, 0xF8]]);
+        // 无空格、带搜索提示
+        let data = parse_response("010D", 0x01, Some(0x0D), "SEARCHING...\r410D3C\r").unwrap();
+        assert_eq!(data, [vec![0x3C]]);
+    }
+
+    #[test]
+    fn multiple_ecus() {
+        let response = "41 00 BE 1F A8 13\r41 00 80 00 00 01\r";
+        let data = parse_response("0100", 0x01, Some(0x00), response).unwrap();
+        assert_eq!(data, [vec![0xBE, 0x1F, 0xA8, 0x13], vec![0x80, 0, 0, 0x01]]);
+    }
+
+    #[test]
+    fn other_pid_is_ignored() {
+        let data = parse_response("010C", 0x01, Some(0x0C), "41 0D 3C").unwrap();
+        assert!(data.is_empty());
+    }
+
+    #[test]
+    fn segmented_can_response() {
+        // 总长 0x0B 字节，末段填充 00
+        let response = "00B\r0: 43 04 01 33 03 00\r1: 01 71 C1 00 00 00 00";
+        let data = parse_response("03", 0x03, None, response).unwrap();
+        assert_eq!(
+            data,
+            [vec![
+                0x04, 0x01, 0x33, 0x03, 0x00, 0x01, 0x71, 0xC1, 0x00, 0x00
+            ]]
+        );
+    }
+
+    #[test]
+    fn no_data_and_errors() {
+        assert!(parse_response("0146", 0x01, Some(0x46), "NO DATA")
+            .unwrap()
+            .is_empty());
+        let error = parse_response("0100", 0x01, Some(0x00), "SEARCHING...\rUNABLE TO CONNECT")
+            .unwrap_err();
+        assert_eq!(error.to_string(), "0100 failed: UNABLE TO CONNECT");
+        assert!(parse_response("0100", 0x01, Some(0x00), "41 00 B").is_err());
+        assert!(parse_response("0100", 0x01, Some(0x00), "41 00 ZZ").is_err());
+    }
+}
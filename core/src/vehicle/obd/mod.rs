@@ -0,0 +1,223 @@
+/*
+    OBD-II 诊断
+    通过 ELM327 兼容的串口适配器读取 Mode 01 实时数据与 Mode 03 故障码，
+    后台线程周期轮询并将结果送入车辆状态（可用伪终端模拟器调试）
+*/
+
+pub mod dtc;
+pub mod elm327;
+pub mod pid;
+
+use super::VehicleState;
+use crossbeam_channel::{unbounded, Receiver};
+use dtc::Dtc;
+use elm327::Elm327;
+use pid::{MonitorStatus, Pid};
+use std::thread;
+use std::time::Duration;
+use tracing::{debug, error, info, warn};
+
+/// 轮询的实时数据
+const POLLED: [Pid; 4] = [
+    Pid::VehicleSpeed,
+    Pid::EngineRpm,
+    Pid::FuelLevel,
+    Pid::CoolantTemperature,
+];
+/// 轮询周期
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// 每隔多少个轮询周期读取一次故障码
+const DTC_EVERY: usize = 30;
+/// 连续失败多少次后放弃
+const MAX_FAILURES: usize = 5;
+
+/// OBD 客户端
+pub struct ObdClient {
+    elm: Elm327,
+    supported: Vec<u8>,
+}
+
+impl ObdClient {
+    pub fn open(path: &str, baud_rate: u32) -> Result<Self, anyhow::Error> {
+        let elm = Elm327::open(path, baud_rate)?;
+        info!("OBD adapter connected: {}", elm.version());
+        let mut client = Self {
+            elm,
+            supported: Vec::new(),
+        };
+        client.supported = client.supported_pids()?;
+        let known: Vec<Pid> = client
+            .supported
+            .iter()
+            .filter_map(|code| Pid::from_code(*code))
+            .collect();
+        info!(?known, "Supported PIDs: {}", client.supported.len());
+        Ok(client)
+    }
+
+    /// 查询车辆支持的 PID（PID 00/20/40... 位图，末位表示下一组是否支持）
+    fn supported_pids(&mut self) -> Result<Vec<u8>, anyhow::Error> {
+        let mut supported = Vec::new();
+        let mut base = 0u8;
+        loop {
+            let Some(data) = self.elm.request(0x01, Some(base))?.into_iter().next() else {
+                break;
+            };
+            supported.extend(pid::decode_supported(base, &data));
+            if base == 0xE0 || !supported.contains(&(base + 0x20)) {
+                break;
+            }
+            base += 0x20;
+        }
+        Ok(supported)
+    }
+
+    pub fn supports(&self, pid: Pid) -> bool {
+        self.supported.contains(&pid.code())
+    }
+
+    /// 读取实时数据，车辆无响应时返回 None
+    pub fn read(&mut self, pid: Pid) -> Result<Option<f32>, anyhow::Error> {
+        let responses = self.elm.request(0x01, Some(pid.code()))?;
+        Ok(responses.iter().find_map(|data| pid.decode(data)))
+    }
+
+    pub fn monitor_status(&mut self) -> Result<Option<MonitorStatus>, anyhow::Error> {
+        let responses = self.elm.request(0x01, Some(0x01))?;
+        Ok(responses
+            .iter()
+            .find_map(|data| MonitorStatus::decode(data)))
+    }
+
+    /// 读取所有 ECU 的已确认故障码
+    pub fn trouble_codes(&mut self) -> Result<Vec<Dtc>, anyhow::Error> {
+        let mut codes: Vec<Dtc> = Vec::new();
+        for data in self.elm.request(0x03, None)? {
+            for code in dtc::decode_dtcs(&data) {
+                if !codes.contains(&code) {
+                    codes.push(code);
+                }
+            }
+        }
+        Ok(codes)
+    }
+}
+
+/// 轮询结果
+#[derive(Debug, Clone)]
+pub enum ObdReading {
+    Value(Pid, f32),
+    Monitor(MonitorStatus),
+    TroubleCodes(Vec<Dtc>),
+}
+
+impl ObdReading {
+    /// 更新车辆状态
+    pub fn apply(self, state: &mut VehicleState) {
+        match self {
+            ObdReading::Value(Pid::VehicleSpeed, speed) => state.speed_kph = speed,
+            ObdReading::Value(Pid::FuelLevel, fuel) => state.fuel_percent = fuel,
+            ObdReading::Value(Pid::EngineRpm, rpm) => state.engine_rpm = Some(rpm),
+            ObdReading::Value(Pid::CoolantTemperature, celsius) => {
+                state.coolant_celsius = Some(celsius)
+            }
+            ObdReading::Value(..) => {}
+            ObdReading::Monitor(status) => state.check_engine_on = Some(status.mil_on),
+            ObdReading::TroubleCodes(codes) => {
+                state.trouble_codes = Some(codes.iter().map(Dtc::to_string).collect())
+            }
+        }
+    }
+}
+
+/// 启动后台轮询线程（在线程内连接适配器，不阻塞启动）
+pub fn spawn_monitor(path: String, baud_rate: u32) -> Receiver<ObdReading> {
+    let (sender, receiver) = unbounded();
+    thread::spawn(move || {
+        let mut client = match ObdClient::open(&path, baud_rate) {
+            Ok(client) => client,
+            Err(e) => {
+                error!("failed to connect OBD adapter: {:?}", e);
+                return;
+            }
+        };
+
+        let polled: Vec<Pid> = POLLED
+            .into_iter()
+            .filter(|pid| client.supports(*pid))
+            .collect();
+        let mut failures = 0;
+        for cycle in 0.. {
+            let mut readings = Vec::new();
+            let result = (|| -> Result<(), anyhow::Error> {
+                for &pid in &polled {
+                    if let Some(value) = client.read(pid)? {
+                        debug!("{:?}: {} {}", pid, value, pid.unit());
+                        readings.push(ObdReading::Value(pid, value));
+                    }
+                }
+                if cycle % DTC_EVERY == 0 {
+                    if let Some(status) = client.monitor_status()? {
+                        readings.push(ObdReading::Monitor(status));
+                    }
+                    readings.push(ObdReading::TroubleCodes(client.trouble_codes()?));
+                }
+                Ok(())
+            })();
+
+            for reading in readings {
+                if sender.send(reading).is_err() {
+                    return; // 接收端已关闭
+                }
+            }
+            match result {
+                Ok(()) => failures = 0,
+                Err(e) => {
+                    warn!("OBD polling failed: {:?}", e);
+                    failures += 1;
+                    if failures >= MAX_FAILURES {
+                        error!("OBD adapter not responding, polling stopped");
+                        return;
+                    }
+                }
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+    });
+    receiver
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn readings_update_vehicle_state() {
+        let mut state = VehicleState::default();
+        let readings = [
+            ObdReading::Value(Pid::VehicleSpeed, 88.0),
+            ObdReading::Value(Pid::EngineRpm, 2400.0),
+            ObdReading::Value(Pid::CoolantTemperature, 90.0),
+            ObdReading::Monitor(MonitorStatus {
+                mil_on: true,
+                dtc_count: 1,
+            }),
+            ObdReading::TroubleCodes(vec![Dtc::from_bytes(0x01, 0x33)]),
+        ];
+        for reading in readings {
+            reading.apply(&mut state);
+        }
+        assert_eq!(state.speed_kph, 88.0);
+        assert_eq!(state.engine_rpm, Some(2400.0));
+        assert_eq!(state.coolant_celsius, Some(90.0));
+        assert_eq!(state.check_engine_on, Some(true));
+        assert_eq!(state.trouble_codes, Some(vec!["P0133".to_string()]));
+        assert_eq!(state.fuel_percent, 0.0);
+    }
+
+    #[test]
+    fn missing_adapter_ends_monitor() {
+        let readings = spawn_monitor("/dev/no-such-obd-adapter".into(), 38400);
+        assert!(readings.recv_timeout(Duration::from_secs(5)).is_err());
+    }
+}
@@ -0,0 +1,192 @@
+/// Mode 01 标准 PID
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Pid {
+    EngineLoad,
+    CoolantTemperature,
+    IntakePressure,
+    EngineRpm,
+    VehicleSpeed,
+    IntakeAirTemperature,
+    MassAirFlow,
+    ThrottlePosition,
+    RunTime,
+    FuelLevel,
+    BarometricPressure,
+    ModuleVoltage,
+    AmbientAirTemperature,
+    OilTemperature,
+}
+
+impl Pid {
+    pub const ALL: [Pid; 14] = [
+        Pid::EngineLoad,
+        Pid::CoolantTemperature,
+        Pid::IntakePressure,
+        Pid::EngineRpm,
+        Pid::VehicleSpeed,
+        Pid::IntakeAirTemperature,
+        Pid::MassAirFlow,
+        Pid::ThrottlePosition,
+        Pid::RunTime,
+        Pid::FuelLevel,
+        Pid::BarometricPressure,
+        Pid::ModuleVoltage,
+        Pid::AmbientAirTemperature,
+        Pid::OilTemperature,
+    ];
+
+    pub fn code(self) -> u8 {
+        match self {
+            Pid::EngineLoad => 0x04,
+            Pid::CoolantTemperature => 0x05,
+            Pid::IntakePressure => 0x0B,
+            Pid::EngineRpm => 0x0C,
+            Pid::VehicleSpeed => 0x0D,
+            Pid::IntakeAirTemperature => 0x0F,
+            Pid::MassAirFlow => 0x10,
+            Pid::ThrottlePosition => 0x11,
+            Pid::RunTime => 0x1F,
+            Pid::FuelLevel => 0x2F,
+            Pid::BarometricPressure => 0x33,
+            Pid::ModuleVoltage => 0x42,
+            Pid::AmbientAirTemperature => 0x46,
+            Pid::OilTemperature => 0x5C,
+        }
+    }
+
+    pub fn from_code(code: u8) -> Option<Self> {
+        Self::ALL.into_iter().find(|pid| pid.code() == code)
+    }
+
+    pub fn unit(self) -> &'static str {
+        match self {
+            Pid::EngineLoad | Pid::ThrottlePosition | Pid::FuelLevel => "%",
+            Pid::CoolantTemperature
+            | Pid::IntakeAirTemperature
+            | Pid::AmbientAirTemperature
+            | Pid::OilTemperature => "°C",
+            Pid::IntakePressure | Pid::BarometricPressure => "kPa",
+            Pid::EngineRpm => "rpm",
+            Pid::VehicleSpeed => "km/h",
+            Pid::MassAirFlow => "g/s",
+            Pid::RunTime => "s",
+            Pid::ModuleVoltage => "V",
+        }
+    }
+
+    /// 按 SAE J1979 公式解码数据字节（不含模式与 PID 字节）
+    pub fn decode(self, data: &[u8]) -> Option<f32> {
+        let a = *data.first()? as f32;
+        let word = || data.get(1).map(|b| a * 256.0 + *b as f32);
+        Some(match self {
+            Pid::EngineLoad | Pid::ThrottlePosition | Pid::FuelLevel => a * 100.0 / 255.0,
+            Pid::CoolantTemperature
+            | Pid::IntakeAirTemperature
+            | Pid::AmbientAirTemperature
+            | Pid::OilTemperature => a - 40.0,
+            Pid::IntakePressure | Pid::BarometricPressure | Pid::VehicleSpeed => a,
+            Pid::EngineRpm => word()? / 4.0,
+            Pid::MassAirFlow => word()? / 100.0,
+            Pid::RunTime => word()?,
+            Pid::ModuleVoltage => word()? / 1000.0,
+        })
+    }
+}
+
+/// 故障灯与故障码数量（PID 01）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MonitorStatus {
+    pub mil_on: bool,
+    pub dtc_count: u8,
+}
+
+impl MonitorStatus {
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        let a = *data.first()?;
+        Some(Self {
+            mil_on: a & 0x80 != 0,
+            dtc_count: a & 0x7F,
+        })
+    }
+}
+
+/// 解码支持的 PID 位图（PID 00 / 20 / 40 ...），返回支持的 PID 编号
+/// 最后一组（E0）的末位对应的编号超出一个字节，忽略
+pub fn decode_supported(base: u8, data: &[u8]) -> Vec<u8> {
+    data.iter()
+        .take(4)
+        .enumerate()
+        .flat_map(|(byte, bits)| {
+            (0..8)
+                .filter(move |bit| bits & (0x80 >> bit) != 0)
+                .filter_map(move |bit| u8::try_from(base as usize + byte * 8 + bit + 1).ok())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_values() {
+        let cases: [(Pid, &[u8], f32); 8] = [
+            (Pid::EngineRpm, &[0x1A, 0xF8], 1726.0),
+            (Pid::VehicleSpeed, &[0x3C], 60.0),
+            (Pid::CoolantTemperature, &[0x7B], 83.0),
+            (Pid::AmbientAirTemperature, &[0x00], -40.0),
+            (Pid::FuelLevel, &[0xFF], 100.0),
+            (Pid::MassAirFlow, &[0x01, 0x2C], 3.0),
+            (Pid::RunTime, &[0x01, 0x00], 256.0),
+            (Pid::ModuleVoltage, &[0x30, 0xD4], 12.5),
+        ];
+        for (pid, data, expected) in cases {
+            let value = pid.decode(data).unwrap();
+            assert!((value - expected).abs() < 1e-3, "{:?}: {}", pid, value);
+        }
+    }
+
+    #[test]
+    fn decode_short_data() {
+        assert_eq!(Pid::VehicleSpeed.decode(&[]), None);
+        // 双字节 PID 只收到一个字节
+        assert_eq!(Pid::EngineRpm.decode(&[0x1A]), None);
+    }
+
+    #[test]
+    fn codes_round_trip() {
+        for pid in Pid::ALL {
+            assert_eq!(Pid::from_code(pid.code()), Some(pid));
+            assert!(!pid.unit().is_empty());
+        }
+        assert_eq!(Pid::from_code(0x00), None);
+    }
+
+    #[test]
+    fn monitor_status() {
+        let status = MonitorStatus::decode(&[0x83, 0x07, 0xE5, 0x00]).unwrap();
+        assert!(status.mil_on);
+        assert_eq!(status.dtc_count, 3);
+        assert_eq!(MonitorStatus::decode(&[]), None);
+    }
+
+    #[test]
+    fn supported_bitmap() {
+        // 01 00 的典型响应：BE 1F A8 13
+        assert_eq!(
+            decode_supported(0x00, &[0xBE, 0x1F, 0xA8, 0x13]),
+            [
+                0x01, 0x03, 0x04, 0x05, 0x06, 0x07, 0x0C, 0x0D, 0x0E, 0x0F, 0x10, 0x11, 0x13, 0x15,
+                0x1C, 0x1F, 0x20
+            ]
+        );
+        assert_eq!(decode_supported(0x20, &[0x80, 0, 0, 0x01]), [0x21, 0x40]);
+    }
+
+    #[test]
+    fn supported_bitmap_last_group() {
+        // E0 组末位对应 0x100，不能溢出
+        assert_eq!(decode_supported(0xE0, &[0x80, 0, 0, 0x03]), [0xE1, 0xFF]);
+        assert_eq!(decode_supported(0xE0, &[0xFF; 4]).len(), 31);
+    }
+}
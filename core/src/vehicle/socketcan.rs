@@ -0,0 +1,188 @@
+use super::{CanFrame, VehicleBus};
+use anyhow::{anyhow, Context};
+use std::ffi::CString;
+use std::io;
+use std::mem;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use std::time::Duration;
+
+/// Linux SocketCAN 原始套接字（可用 `ip link add dev vcan0 type vcan` 创建虚拟接口调试）
+pub struct SocketCanBus {
+    fd: OwnedFd,
+    interface: String,
+}
+
+impl SocketCanBus {
+    pub fn open(interface: &str) -> Result<Self, anyhow::Error> {
+        let name = CString::new(interface)?;
+        // SAFETY: name 是以 NUL 结尾的有效 C 字符串，调用期间保持存活
+        let index = unsafe { libc::if_nametoindex(name.as_ptr()) };
+        if index == 0 {
+            return Err(io::Error::last_os_error())
+                .with_context(|| format!("CAN interface not found: {}", interface));
+        }
+
+        // SAFETY: 只传入常量参数，返回值在下面检查
+        let raw = unsafe { libc::socket(libc::PF_CAN, libc::SOCK_RAW, libc::CAN_RAW) };
+        if raw < 0 {
+            return Err(io::Error::last_os_error()).context("failed to create CAN socket");
+        }
+        // SAFETY: raw 是刚创建成功的描述符，所有权只交给这一个 OwnedFd
+        let fd = unsafe { OwnedFd::from_raw_fd(raw) };
+
+        // SAFETY: sockaddr_can 是纯数据结构，全零是合法值
+        let mut address: libc::sockaddr_can = unsafe { mem::zeroed() };
+        address.can_family = libc::AF_CAN as libc::sa_family_t;
+        address.can_ifindex = index as libc::c_int;
+        // SAFETY: address 在调用期间有效，传入的长度与其类型一致
+        let result = unsafe {
+            libc::bind(
+                fd.as_raw_fd(),
+                &address as *const libc::sockaddr_can as *const libc::sockaddr,
+                mem::size_of::<libc::sockaddr_can>() as libc::socklen_t,
+            )
+        };
+        if result < 0 {
+            return Err(io::Error::last_os_error())
+                .with_context(|| format!("failed to bind CAN socket to {}", interface));
+        }
+
+        Ok(Self {
+            fd,
+            interface: interface.to_string(),
+        })
+    }
+}
+
+impl VehicleBus for SocketCanBus {
+    fn send(&mut self, frame: &CanFrame) -> Result<(), anyhow::Error> {
+        // SAFETY: can_frame 是纯数据结构，全零是合法值
+        let mut raw: libc::can_frame = unsafe { mem::zeroed() };
+        raw.can_id = if frame.extended {
+            frame.id | libc::CAN_EFF_FLAG
+        } else {
+            frame.id
+        };
+        raw.can_dlc = frame.data.len() as u8;
+        raw.data[..frame.data.len()].copy_from_slice(&frame.data);
+
+        // SAFETY: raw 在调用期间有效，写入长度不超过其大小
+        let written = unsafe {
+            libc::write(
+                self.fd.as_raw_fd(),
+                &raw as *const libc::can_frame as *const libc::c_void,
+                mem::size_of::<libc::can_frame>(),
+            )
+        };
+        if written < 0 {
+            return Err(io::Error::last_os_error())
+                .with_context(|| format!("failed to send CAN frame on {}", self.interface));
+        }
+        if written as usize != mem::size_of::<libc::can_frame>() {
+            return Err(anyhow!(
+                "short CAN write on {}: {} bytes",
+                self.interface,
+                written
+            ));
+        }
+        Ok(())
+    }
+
+    fn recv(&mut self, timeout: Duration) -> Result<Option<CanFrame>, anyhow::Error> {
+        let mut poll = libc::pollfd {
+            fd: self.fd.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        // SAFETY: poll 指向一个有效的 pollfd，数量为 1
+        let ready = unsafe { libc::poll(&mut poll, 1, timeout.as_millis() as libc::c_int) };
+        if ready < 0 {
+            return Err(io::Error::last_os_error()).context("failed to poll CAN socket");
+        }
+        if ready == 0 {
+            return Ok(None);
+        }
+
+        // SAFETY: can_frame 是纯数据结构，全零是合法值
+        let mut raw: libc::can_frame = unsafe { mem::zeroed() };
+        // SAFETY: raw 在调用期间有效，读取长度不超过其大小
+        let read = unsafe {
+            libc::read(
+                self.fd.as_raw_fd(),
+                &mut raw as *mut libc::can_frame as *mut libc::c_void,
+                mem::size_of::<libc::can_frame>(),
+            )
+        };
+        if read < 0 {
+            return Err(io::Error::last_os_error())
+                .with_context(|| format!("failed to read CAN frame on {}", self.interface));
+        }
+        // 原始套接字每次读出一整帧，不足一帧说明数据不完整
+        if read as usize != mem::size_of::<libc::can_frame>() {
+            return Err(anyhow!(
+                "short CAN read on {}: {} bytes",
+                self.interface,
+                read
+            ));
+        }
+        if raw.can_id & libc::CAN_ERR_FLAG != 0 {
+            return Err(anyhow!(
+                "CAN error frame on {}: {:#x}",
+                self.interface,
+                raw.can_id
+            ));
+        }
+
+        let extended = raw.can_id & libc::CAN_EFF_FLAG != 0;
+        let id = if extended {
+            raw.can_id & libc::CAN_EFF_MASK
+        } else {
+            raw.can_id & libc::CAN_SFF_MASK
+        };
+        let len = (raw.can_dlc as usize).min(8);
+        CanFrame::new(id, extended, &raw.data[..len]).map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 需要 vcan0 虚拟接口：
+    /// `ip link add dev vcan0 type vcan && ip link set up vcan0`
+    const VCAN: &str = "vcan0";
+    const TIMEOUT: Duration = Duration::from_secs(1);
+
+    #[test]
+    fn missing_interface() {
+        let error = SocketCanBus::open("nocan9").err().unwrap();
+        assert!(error.to_string().contains("nocan9"));
+        assert!(SocketCanBus::open("bad\0name").is_err());
+    }
+
+    #[test]
+    #[ignore = "requires the vcan0 interface"]
+    fn sends_and_receives_frames() {
+        let mut sender = SocketCanBus::open(VCAN).unwrap();
+        let mut receiver = SocketCanBus::open(VCAN).unwrap();
+
+        let frames = [
+            CanFrame::new(0x3A0, false, &[1, 44]).unwrap(),
+            // 扩展帧的 ID 可以落在标准帧范围内
+            CanFrame::new(0x3A0, true, &[0xDE, 0xAD, 0xBE, 0xEF]).unwrap(),
+            CanFrame::new(0x1FFF_FFFF, true, &[0; 8]).unwrap(),
+            CanFrame::new(0x7FF, false, &[]).unwrap(),
+        ];
+        for frame in &frames {
+            sender.send(frame).unwrap();
+            assert_eq!(receiver.recv(TIMEOUT).unwrap().as_ref(), Some(frame));
+        }
+    }
+
+    #[test]
+    #[ignore = "requires the vcan0 interface"]
+    fn receive_times_out() {
+        let mut bus = SocketCanBus::open(VCAN).unwrap();
+        assert_eq!(bus.recv(Duration::from_millis(50)).unwrap(), None);
+    }
+}
@@ -1,37 +1,92 @@
 use crossbeam_channel::Receiver;
 use eframe::egui;
 use status::WakeStatus;
+use std::time::{Duration, Instant};
 
 pub mod status;
 
+/// 声音事件提醒的显示时长
+const ALERT_DURATION: Duration = Duration::from_secs(5);
+
 pub struct WakeUI {
     status: WakeStatus,
+    privacy: bool,
+    alert: Option<(String, Instant)>, // 最近的声音事件及其时间
     rx: Receiver<WakeStatus>,
+    set_privacy: Box<dyn Fn(bool)>, // 请求切换隐私模式
 }
 
 impl WakeUI {
-    pub fn new(rx: Receiver<WakeStatus>) -> Self {
+    pub fn new(rx: Receiver<WakeStatus>, set_privacy: impl Fn(bool) + 'static) -> Self {
         Self {
             status: WakeStatus::Idle,
+            privacy: false,
+            alert: None,
             rx,
+            set_privacy: Box::new(set_privacy),
         }
     }
 }
 
 impl eframe::App for WakeUI {
-    fn update(&mut self, ctx: &eframe::egui::Context, frame: &mut eframe::Frame) {
+    fn update(&mut self, ctx: &eframe::egui::Context, _frame: &mut eframe::Frame) {
         // 异步更新状态
         while let Ok(status) = self.rx.try_recv() {
-            self.status = status;
+            match status {
+                WakeStatus::Privacy { enabled } => self.privacy = enabled,
+                WakeStatus::Sound { kind } => self.alert = Some((kind, Instant::now())),
+                status => self.status = status,
+            }
         }
 
-        egui::CentralPanel::default().show(ctx, |ui| match self.status {
+        // 隐私模式指示与开关、声音事件提醒
+        egui::TopBottomPanel::top("privacy").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                if self.privacy {
+                    ui.colored_label(egui::Color32::RED, "● Privacy mode: microphone off");
+                    if ui.button("Turn microphone on").clicked() {
+                        (self.set_privacy)(false);
+                    }
+                } else {
+                    ui.label("Microphone on");
+                    if ui.button("Privacy mode").clicked() {
+                        (self.set_privacy)(true);
+                    }
+                }
+            });
+            if let Some((kind, _)) = self
+                .alert
+                .as_ref()
+                .filter(|(_, at)| at.elapsed() < ALERT_DURATION)
+            {
+                ui.colored_label(egui::Color32::YELLOW, format!("⚠ Sound detected: {}", kind));
+            }
+        });
+
+        egui::CentralPanel::default().show(ctx, |ui| match &self.status {
             WakeStatus::Idle => {
                 ui.label("Idle");
             }
-            WakeStatus::Active => {
-                ui.label("Waking");
+            WakeStatus::Listening { follow_up } => {
+                ui.label(if *follow_up {
+                    "Listening (follow-up)"
+                } else {
+                    "Listening"
+                });
             }
+            WakeStatus::Recognizing => {
+                ui.label("Recognizing");
+            }
+            WakeStatus::Executing { intent } => {
+                ui.label(format!("Executing: {}", intent));
+            }
+            WakeStatus::Responding { text } => {
+                ui.label(text);
+            }
+            WakeStatus::Privacy { .. } | WakeStatus::Sound { .. } => {}
         });
+
+        // 状态由后台推送，需定时刷新
+        ctx.request_repaint_after(Duration::from_millis(100));
     }
 }
@@ -1,4 +1,14 @@
+use serde::{Deserialize, Serialize};
+
+/// 对话状态与隐私模式（由核心事件循环推送）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
 pub enum WakeStatus {
     Idle,
-    Active,
+    Listening { follow_up: bool }, // 已唤醒，正在采集语音（follow_up 为多轮对话的后续轮次）
+    Recognizing,                   // 语音识别中
+    Executing { intent: String },  // 正在执行指令
+    Responding { text: String },   // 播报/展示回复
+    Privacy { enabled: bool },     // 隐私模式切换（常驻指示，不替换对话状态）
+    Sound { kind: String },        // 车内声音事件提醒（短暂显示，不替换对话状态）
 }
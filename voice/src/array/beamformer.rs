@@ -0,0 +1,70 @@
+/// 延迟求和波束形成器（流式，整数采样点对齐）
+/// 为处理正负延迟，输出相对输入固定滞后 `max_delay` 个采样点
+pub struct DelayAndSum {
+    channels: usize,
+    max_delay: usize,
+    delays: Vec<isize>,     // 各声道相对参考麦克风的到达延迟（采样点）
+    history: Vec<Vec<f32>>, // 各声道历史采样（长度 2 * max_delay）
+}
+
+impl DelayAndSum {
+    pub fn new(channels: usize, max_delay: usize) -> Self {
+        Self {
+            channels,
+            max_delay,
+            delays: vec![0; channels],
+            history: vec![vec![0.0; 2 * max_delay]; channels],
+        }
+    }
+
+    /// 设置指向方位对应的各声道延迟
+    pub fn steer(&mut self, delays: &[f32]) {
+        let limit = self.max_delay as isize;
+        self.delays = delays
+            .iter()
+            .map(|d| (d.round() as isize).clamp(-limit, limit))
+            .collect();
+    }
+
+    /// 清空历史采样
+    pub fn reset(&mut self) {
+        self.history
+            .iter_mut()
+            .for_each(|history| history.iter_mut().for_each(|x| *x = 0.0));
+    }
+
+    /// 处理交织的多声道采样，返回等长的单声道输出
+    pub fn process(&mut self, interleaved: &[f32]) -> Vec<f32> {
+        let frames = interleaved.len() / self.channels;
+        let lookback = 2 * self.max_delay;
+
+        // 拼接历史与新采样，按声道拆分
+        let signals: Vec<Vec<f32>> = (0..self.channels)
+            .map(|ch| {
+                let mut signal = std::mem::take(&mut self.history[ch]);
+                signal.extend(interleaved.iter().skip(ch).step_by(self.channels));
+                signal
+            })
+            .collect();
+
+        // 输出第 n 点对应输入时刻 n - max_delay，各声道按到达延迟向后取样对齐
+        let output = (0..frames)
+            .map(|n| {
+                signals
+                    .iter()
+                    .zip(&self.delays)
+                    .map(|(signal, delay)| {
+                        signal[(n as isize + self.max_delay as isize + delay) as usize]
+                    })
+                    .sum::<f32>()
+                    / self.channels as f32
+            })
+            .collect();
+
+        for (history, signal) in self.history.iter_mut().zip(signals) {
+            *history = signal[signal.len() - lookback..].to_vec();
+        }
+
+        output
+    }
+}
@@ -0,0 +1,94 @@
+use rustfft::{num_complex::Complex, FftPlanner};
+
+/// GCC-PHAT 时延估计
+/// 返回 `signal` 相对 `reference` 的延迟（采样点，正值表示 `signal` 更晚到达）以及归一化峰值
+pub fn estimate_delay(reference: &[f32], signal: &[f32], max_delay: usize) -> (f32, f32) {
+    // 补零到两倍长度，避免循环相关的混叠
+    let fft_size = (reference.len().max(signal.len()) * 2).next_power_of_two();
+    let mut planner = FftPlanner::new();
+    let fft = planner.plan_fft_forward(fft_size);
+    let ifft = planner.plan_fft_inverse(fft_size);
+
+    let mut ref_spec = to_complex(reference, fft_size);
+    let mut sig_spec = to_complex(signal, fft_size);
+    fft.process(&mut ref_spec);
+    fft.process(&mut sig_spec);
+
+    // 互功率谱 + PHAT加权（仅保留相位信息）
+    let mut cross: Vec<Complex<f32>> = sig_spec
+        .iter()
+        .zip(&ref_spec)
+        .map(|(s, r)| {
+            let c = s * r.conj();
+            let magnitude = c.norm();
+            if magnitude > f32::EPSILON {
+                c / magnitude
+            } else {
+                Complex::new(0.0, 0.0)
+            }
+        })
+        .collect();
+    ifft.process(&mut cross);
+
+    // 在 [-max_delay, max_delay] 范围内寻找相关峰
+    let max_delay = max_delay.min(fft_size / 2 - 1) as isize;
+    let correlation = |lag: isize| cross[lag.rem_euclid(fft_size as isize) as usize].re;
+    let (best_lag, best_value) = (-max_delay..=max_delay)
+        .map(|lag| (lag, correlation(lag)))
+        .fold((0, f32::NEG_INFINITY), |best, cur| {
+            if cur.1 > best.1 {
+                cur
+            } else {
+                best
+            }
+        });
+
+    // 抛物线插值得到亚采样精度
+    let left = correlation(best_lag - 1);
+    let right = correlation(best_lag + 1);
+    let curvature = left - 2.0 * best_value + right;
+    let offset = if curvature.abs() > f32::EPSILON {
+        (0.5 * (left - right) / curvature).clamp(-0.5, 0.5)
+    } else {
+        0.0
+    };
+
+    (best_lag as f32 + offset, best_value / fft_size as f32)
+}
+
+fn to_complex(samples: &[f32], size: usize) -> Vec<Complex<f32>> {
+    let mut buffer: Vec<Complex<f32>> = samples.iter().map(|x| Complex::new(*x, 0.0)).collect();
+    buffer.resize(size, Complex::new(0.0, 0.0));
+    buffer
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::tests::{delayed, noise};
+
+    #[test]
+    fn integer_delays() {
+        let reference = noise(7, 2048);
+        for delay in [0usize, 1, 5, 12] {
+            let (estimate, peak) = estimate_delay(&reference, &delayed(&reference, delay), 16);
+            assert!(
+                (estimate - delay as f32).abs() < 0.5,
+                "delay {}: estimated {}",
+                delay,
+                estimate
+            );
+            assert!(peak > 0.5);
+            // 反向即为负延迟
+            let (estimate, _) = estimate_delay(&delayed(&reference, delay), &reference, 16);
+            assert!((estimate + delay as f32).abs() < 0.5);
+        }
+    }
+
+    #[test]
+    fn delay_limited_to_search_range() {
+        let reference = noise(7, 2048);
+        let (estimate, _) = estimate_delay(&reference, &delayed(&reference, 12), 4);
+        assert!(estimate.abs() <= 4.5);
+    }
+}
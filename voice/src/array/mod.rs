@@ -0,0 +1,300 @@
+/*
+    多麦克风阵列前端
+    车内通常布置2~4个麦克风（线阵，沿车宽方向排列）：
+    1. gcc_phat：基于 GCC-PHAT 的到达时延估计，换算为声源方位角（DOA）
+    2. beamformer：朝估计方位做延迟求和波束形成，输出单声道增强信号
+    3. 由方位角判断说话人所在座位（主驾 / 副驾）
+*/
+
+pub mod beamformer;
+pub mod gcc_phat;
+
+use anyhow::anyhow;
+use beamformer::DelayAndSum;
+use gcc_phat::estimate_delay;
+use serde::{Deserialize, Serialize};
+
+/// 声速（m/s）
+const SPEED_OF_SOUND: f32 = 343.0;
+
+/// 说话人座位
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Seat {
+    Driver,
+    Passenger,
+}
+
+/// 阵列配置
+#[derive(Debug, Clone)]
+pub struct ArrayConfig {
+    pub sample_rate: u32,
+    pub mic_positions: Vec<f32>, // 各麦克风沿车宽方向的坐标（米），正方向指向主驾
+    pub window: usize,           // 每次方位估计使用的采样点数（每声道）
+    pub energy_gate: f32,        // 低于该均方能量的窗口不做方位估计
+    pub seat_margin_deg: f32,    // 方位角绝对值小于该值时无法判定座位
+}
+
+/// 声源方位估计结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirectionOfArrival {
+    pub azimuth_deg: f32, // 相对阵列法线的方位角，正值偏向主驾
+    pub confidence: f32,  // GCC-PHAT 峰值均值（0~1）
+    pub seat: Option<Seat>,
+}
+
+/// 多声道前端：交织采样输入，单声道增强信号 + 方位估计输出
+pub struct ArrayFrontEnd {
+    config: ArrayConfig,
+    beamformer: DelayAndSum,
+    pending: Vec<Vec<f32>>, // 各声道累积的待估计采样
+}
+
+impl ArrayFrontEnd {
+    /// 麦克风坐标数须与输入声道数一致
+    pub fn new(config: ArrayConfig, channels: usize) -> Result<Self, anyhow::Error> {
+        if config.mic_positions.is_empty() {
+            return Err(anyhow!("mic_positions is empty"));
+        }
+        if config.mic_positions.len() != channels {
+            return Err(anyhow!(
+                "mic_positions has {} entries, audio input has {} channels",
+                config.mic_positions.len(),
+                channels
+            ));
+        }
+        let max_delay = max_delay_samples(&config);
+        Ok(Self {
+            beamformer: DelayAndSum::new(channels, max_delay),
+            pending: vec![Vec::with_capacity(config.window); channels],
+            config,
+        })
+    }
+
+    pub fn channels(&self) -> usize {
+        self.config.mic_positions.len()
+    }
+
+    /// 丢弃累积的采样与波束形成历史
+    pub fn reset(&mut self) {
+        self.pending.iter_mut().for_each(Vec::clear);
+        self.beamformer.reset();
+    }
+
+    /// 处理一段交织的多声道采样
+    /// 返回波束形成后的单声道信号，以及（累计满一个窗口时）新的方位估计
+    pub fn process(&mut self, interleaved: &[f32]) -> (Vec<f32>, Option<DirectionOfArrival>) {
+        let channels = self.channels();
+        let mut direction = None;
+
+        for frame in interleaved.chunks_exact(channels) {
+            for (pending, sample) in self.pending.iter_mut().zip(frame) {
+                pending.push(*sample);
+            }
+
+            if self.pending[0].len() >= self.config.window {
+                direction = self.estimate().or(direction);
+                self.pending.iter_mut().for_each(Vec::clear);
+            }
+        }
+
+        if let Some(doa) = &direction {
+            self.beamformer
+                .steer(&self.steering_delays(doa.azimuth_deg));
+        }
+
+        (self.beamformer.process(interleaved), direction)
+    }
+
+    /// 对累积窗口做 GCC-PHAT 方位估计（以0号麦克风为参考，最小二乘合并各麦克风对）
+    fn estimate(&self) -> Option<DirectionOfArrival> {
+        let reference = &self.pending[0];
+        let energy = reference.iter().map(|x| x * x).sum::<f32>() / reference.len() as f32;
+        if energy < self.config.energy_gate {
+            return None;
+        }
+
+        let max_delay = max_delay_samples(&self.config);
+        let origin = self.config.mic_positions[0];
+        let mut numerator = 0.0;
+        let mut denominator = 0.0;
+        let mut confidence = 0.0;
+
+        for (signal, position) in self.pending.iter().zip(&self.config.mic_positions).skip(1) {
+            let (delay, peak) = estimate_delay(reference, signal, max_delay);
+            let spacing = position - origin;
+            // 延迟（秒）= -spacing * sin(θ) / c
+            numerator += -(delay / self.config.sample_rate as f32) * SPEED_OF_SOUND * spacing;
+            denominator += spacing * spacing;
+            confidence += peak;
+        }
+
+        if denominator == 0.0 {
+            return None;
+        }
+
+        let sin_theta = (numerator / denominator).clamp(-1.0, 1.0);
+        let azimuth_deg = sin_theta.asin().to_degrees();
+        let seat = if azimuth_deg > self.config.seat_margin_deg {
+            Some(Seat::Driver)
+        } else if azimuth_deg < -self.config.seat_margin_deg {
+            Some(Seat::Passenger)
+        } else {
+            None
+        };
+
+        Some(DirectionOfArrival {
+            azimuth_deg,
+            confidence: confidence / (self.channels() - 1) as f32,
+            seat,
+        })
+    }
+
+    /// 朝指定方位时，各麦克风相对参考麦克风的到达延迟（采样点）
+    fn steering_delays(&self, azimuth_deg: f32) -> Vec<f32> {
+        let sin_theta = azimuth_deg.to_radians().sin();
+        let origin = self.config.mic_positions[0];
+        self.config
+            .mic_positions
+            .iter()
+            .map(|p| -(p - origin) * sin_theta / SPEED_OF_SOUND * self.config.sample_rate as f32)
+            .collect()
+    }
+}
+
+/// 阵列孔径对应的最大物理延迟（采样点）
+fn max_delay_samples(config: &ArrayConfig) -> usize {
+    let min = config
+        .mic_positions
+        .iter()
+        .copied()
+        .fold(f32::INFINITY, f32::min);
+    let max = config
+        .mic_positions
+        .iter()
+        .copied()
+        .fold(f32::NEG_INFINITY, f32::max);
+    ((max - min) / SPEED_OF_SOUND * config.sample_rate as f32).ceil() as usize + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: u32 = 16000;
+
+    /// 可复现的白噪声（线性同余）
+    pub(super) fn noise(seed: u32, length: usize) -> Vec<f32> {
+        let mut state = seed;
+        (0..length)
+            .map(|_| {
+                state = state.wrapping_mul(1664525).wrapping_add(1013904223);
+                (state >> 8) as f32 / (1 << 24) as f32 * 2.0 - 1.0
+            })
+            .collect()
+    }
+
+    /// 信号整体延后若干采样点（前端补零）
+    pub(super) fn delayed(signal: &[f32], delay: usize) -> Vec<f32> {
+        let mut output = vec![0.0; delay];
+        output.extend_from_slice(&signal[..signal.len() - delay]);
+        output
+    }
+
+    fn config(mic_positions: Vec<f32>) -> ArrayConfig {
+        ArrayConfig {
+            mic_positions,
+            ..crate::config::Settings::default().array_config(SAMPLE_RATE)
+        }
+    }
+
+    fn interleave(channels: &[Vec<f32>]) -> Vec<f32> {
+        (0..channels[0].len())
+            .flat_map(|i| channels.iter().map(move |c| c[i]))
+            .collect()
+    }
+
+    fn power(signal: impl Iterator<Item = f32>) -> f32 {
+        let (sum, count) = signal.fold((0.0, 0), |(sum, count), x| (sum + x * x, count + 1));
+        sum / count as f32
+    }
+
+    #[test]
+    fn rejects_mismatched_channels() {
+        assert!(ArrayFrontEnd::new(config(Vec::new()), 0).is_err());
+        assert!(ArrayFrontEnd::new(config(Vec::new()), 2).is_err());
+        assert!(ArrayFrontEnd::new(config(vec![0.1, -0.1]), 4).is_err());
+        assert!(ArrayFrontEnd::new(config(vec![0.1, -0.1]), 2).is_ok());
+    }
+
+    #[test]
+    fn direction_of_delayed_source() {
+        // 两个麦克风相距0.2米，主驾侧在前；副驾侧麦克风晚6个采样点 ≈ 偏向主驾40°
+        let source = noise(1, SAMPLE_RATE as usize);
+        for (delay, seat) in [(6usize, Seat::Driver), (0, Seat::Passenger)] {
+            let channels = if delay > 0 {
+                vec![source.clone(), delayed(&source, delay)]
+            } else {
+                vec![delayed(&source, 6), source.clone()]
+            };
+            let mut front_end = ArrayFrontEnd::new(config(vec![0.1, -0.1]), 2).unwrap();
+            let (mono, direction) = front_end.process(&interleave(&channels));
+            let direction = direction.expect("no direction estimated");
+
+            let expected = (6.0 * SPEED_OF_SOUND / (SAMPLE_RATE as f32 * 0.2))
+                .asin()
+                .to_degrees();
+            let sign = if seat == Seat::Driver { 1.0 } else { -1.0 };
+            assert!(
+                (direction.azimuth_deg - sign * expected).abs() < 3.0,
+                "azimuth {} expected {}",
+                direction.azimuth_deg,
+                sign * expected
+            );
+            assert_eq!(direction.seat, Some(seat));
+            assert!(direction.confidence > 0.5);
+            assert_eq!(mono.len(), source.len());
+        }
+    }
+
+    #[test]
+    fn silence_has_no_direction() {
+        let mut front_end = ArrayFrontEnd::new(config(vec![0.1, -0.1]), 2).unwrap();
+        let (mono, direction) = front_end.process(&vec![0.0; 4000]);
+        assert!(direction.is_none());
+        assert_eq!(mono.len(), 2000);
+    }
+
+    #[test]
+    fn beamforming_improves_snr() {
+        // 两路麦克风含相同的延迟声源与各自独立的噪声
+        let length = SAMPLE_RATE as usize;
+        let source = noise(1, length);
+        let channels: Vec<Vec<f32>> = [source.clone(), delayed(&source, 6)]
+            .iter()
+            .zip([2, 3])
+            .map(|(signal, seed)| {
+                signal
+                    .iter()
+                    .zip(noise(seed, length))
+                    .map(|(s, n)| s + 0.5 * n)
+                    .collect()
+            })
+            .collect();
+
+        let config = config(vec![0.1, -0.1]);
+        let lag = max_delay_samples(&config);
+        let mut front_end = ArrayFrontEnd::new(config, 2).unwrap();
+        let (mono, _) = front_end.process(&interleave(&channels));
+
+        // 输出相对输入滞后 lag 个采样点
+        let single = power(channels[0].iter().zip(&source).map(|(x, s)| x - s));
+        let beam = power(mono[lag..].iter().zip(&source).map(|(x, s)| x - s));
+        assert!(
+            beam < single * 0.6,
+            "beamformed noise {} vs single channel {}",
+            beam,
+            single
+        );
+    }
+}
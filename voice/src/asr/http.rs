@@ -0,0 +1,288 @@
+use super::{to_pcm16, AsrBackend, Transcript};
+use anyhow::{anyhow, Context};
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+const IO_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 本机 HTTP 识别服务适配器
+/// 请求：`POST {path}?sample_rate=16000&final=true|false`，正文为16位小端单声道PCM
+/// 响应：JSON 格式的 `Transcript` 数组
+pub struct HttpBackend {
+    host: String, // host:port
+    path: String,
+    partial_interval: Option<usize>, // 每累积多少采样点请求一次中间结果
+    partial_interval_ms: Option<u64>,
+    sample_rate: u32,
+    audio: Vec<f32>,
+    since_partial: usize,
+    timeout: Duration, // 读写超时
+}
+
+impl HttpBackend {
+    pub fn new(url: &str, partial_interval_ms: Option<u64>) -> Result<Self, anyhow::Error> {
+        let rest = url
+            .strip_prefix("http://")
+            .ok_or_else(|| anyhow!("only plain http urls are supported: {}", url))?;
+        let (host, path) = match rest.find('/') {
+            Some(index) => (&rest[..index], &rest[index..]),
+            None => (rest, "/"),
+        };
+
+        Ok(Self {
+            host: host.to_string(),
+            path: path.to_string(),
+            partial_interval: None,
+            partial_interval_ms,
+            sample_rate: 0,
+            audio: Vec::new(),
+            since_partial: 0,
+            timeout: IO_TIMEOUT,
+        })
+    }
+
+    /// 设置读写超时
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// 发送当前累积的全部音频
+    fn request(&self, is_final: bool) -> Result<Vec<Transcript>, anyhow::Error> {
+        let body = to_pcm16(&self.audio);
+        let mut stream = TcpStream::connect(&self.host)
+            .with_context(|| format!("failed to connect to recognizer at {}", self.host))?;
+        stream.set_read_timeout(Some(self.timeout))?;
+        stream.set_write_timeout(Some(self.timeout))?;
+
+        let header = format!(
+            "POST {}?sample_rate={}&final={} HTTP/1.1\r\n\
+             Host: {}\r\n\
+             Content-Type: audio/l16\r\n\
+             Content-Length: {}\r\n\
+             Connection: close\r\n\r\n",
+            self.path,
+            self.sample_rate,
+            is_final,
+            self.host,
+            body.len()
+        );
+        stream.write_all(header.as_bytes())?;
+        stream.write_all(&body)?;
+
+        let mut response = Vec::new();
+        stream
+            .read_to_end(&mut response)
+            .context("failed to read recognizer response")?;
+        parse_response(&response)
+    }
+}
+
+impl AsrBackend for HttpBackend {
+    fn start(&mut self, sample_rate: u32) -> Result<(), anyhow::Error> {
+        self.sample_rate = sample_rate;
+        self.partial_interval = self
+            .partial_interval_ms
+            .map(|ms| (sample_rate as u64 * ms / 1000) as usize);
+        self.audio.clear();
+        self.since_partial = 0;
+        Ok(())
+    }
+
+    fn feed(&mut self, pcm: &[f32]) -> Result<Vec<Transcript>, anyhow::Error> {
+        self.audio.extend_from_slice(pcm);
+        self.since_partial += pcm.len();
+
+        match self.partial_interval {
+            Some(interval) if self.since_partial >= interval => {
+                self.since_partial = 0;
+                self.request(false)
+            }
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    fn finish(&mut self) -> Result<Vec<Transcript>, anyhow::Error> {
+        let transcripts = self.request(true);
+        self.audio.clear();
+        transcripts
+    }
+}
+
+/// 解析 HTTP 响应（仅支持 Content-Length 或连接关闭定界的正文）
+fn parse_response(response: &[u8]) -> Result<Vec<Transcript>, anyhow::Error> {
+    let split = response
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .ok_or_else(|| anyhow!("malformed http response"))?;
+    let head = std::str::from_utf8(&response[..split])?;
+    let body = &response[split + 4..];
+
+    let mut lines = head.lines();
+    let status = lines
+        .next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|code| code.parse::<u16>().ok())
+        .ok_or_else(|| anyhow!("malformed http status line"))?;
+
+    let mut content_length = None;
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let name = name.trim().to_ascii_lowercase();
+        let value = value.trim();
+        if name == "content-length" {
+            content_length = value.parse::<usize>().ok();
+        } else if name == "transfer-encoding" && value.eq_ignore_ascii_case("chunked") {
+            return Err(anyhow!("chunked responses are not supported"));
+        }
+    }
+    let body = match content_length {
+        Some(length) => &body[..length.min(body.len())],
+        None => body,
+    };
+
+    if !(200..300).contains(&status) {
+        return Err(anyhow!(
+            "recognizer returned {}: {}",
+            status,
+            String::from_utf8_lossy(body)
+        ));
+    }
+    serde_json::from_slice(body).context("invalid recognizer response")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::BufRead;
+    use std::io::BufReader;
+    use std::net::TcpListener;
+    use std::thread;
+
+    /// 接受一个连接，读取请求后回复给定的响应，返回请求行与正文长度
+    fn serve_once(response: &'static str) -> (String, thread::JoinHandle<(String, usize)>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let mut length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line == "\r\n" {
+                    break;
+                }
+                if let Some(value) = line.strip_prefix("Content-Length:") {
+                    length = value.trim().parse().unwrap();
+                }
+            }
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            reader.get_mut().write_all(response.as_bytes()).unwrap();
+            (request_line.trim_end().to_string(), body.len())
+        });
+        (address, server)
+    }
+
+    #[test]
+    fn final_request() {
+        let (address, server) = serve_once(
+            "HTTP/1.1 200 OK\r\nContent-Length: 31\r\n\r\n[{\"text\":\"hello\",\"final\":true}]",
+        );
+        let mut backend = HttpBackend::new(&format!("http://{}/asr", address), None).unwrap();
+        let transcripts = backend.transcribe(&[0.5; 441], 44100).unwrap();
+
+        assert_eq!(transcripts.len(), 1);
+        assert_eq!(transcripts[0].text, "hello");
+        assert!(transcripts[0].is_final);
+        let (request_line, body) = server.join().unwrap();
+        assert_eq!(
+            request_line,
+            "POST /asr?sample_rate=44100&final=true HTTP/1.1"
+        );
+        assert_eq!(body, 441 * 2);
+    }
+
+    #[test]
+    fn partial_request_after_interval() {
+        let (address, server) = serve_once("HTTP/1.1 200 OK\r\n\r\n[{\"text\":\"he\"}]");
+        let mut backend = HttpBackend::new(&format!("http://{}", address), Some(10)).unwrap();
+        backend.start(16000).unwrap();
+
+        // 10ms = 160个采样点
+        assert!(backend.feed(&[0.0; 100]).unwrap().is_empty());
+        let partial = backend.feed(&[0.0; 60]).unwrap();
+        assert_eq!(partial[0].text, "he");
+        assert!(!partial[0].is_final);
+        let (request_line, body) = server.join().unwrap();
+        assert_eq!(
+            request_line,
+            "POST /?sample_rate=16000&final=false HTTP/1.1"
+        );
+        assert_eq!(body, 160 * 2);
+    }
+
+    #[test]
+    fn error_status() {
+        let (address, server) = serve_once("HTTP/1.1 503 Busy\r\nContent-Length: 4\r\n\r\nbusy");
+        let mut backend = HttpBackend::new(&format!("http://{}/", address), None).unwrap();
+        let error = backend.transcribe(&[0.0; 10], 16000).unwrap_err();
+        assert_eq!(error.to_string(), "recognizer returned 503: busy");
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn connection_refused() {
+        let address = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let mut backend = HttpBackend::new(&format!("http://{}/", address), None).unwrap();
+        assert!(backend.transcribe(&[0.0; 10], 16000).is_err());
+    }
+
+    #[test]
+    fn response_timeout() {
+        // 接受连接但不回复
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            thread::sleep(Duration::from_millis(500));
+            drop(stream);
+        });
+        let mut backend = HttpBackend::new(&format!("http://{}/", address), None)
+            .unwrap()
+            .with_timeout(Duration::from_millis(100));
+        let error = backend.transcribe(&[0.0; 10], 16000).unwrap_err();
+        assert_eq!(error.to_string(), "failed to read recognizer response");
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn rejects_unsupported_urls() {
+        assert!(HttpBackend::new("https://127.0.0.1/asr", None).is_err());
+    }
+
+    #[test]
+    fn parse_responses() {
+        // 按 Content-Length 截断多余的数据
+        let transcripts =
+            parse_response(b"HTTP/1.1 200 OK\r\ncontent-length: 2\r\n\r\n[]garbage").unwrap();
+        assert!(transcripts.is_empty());
+
+        assert!(parse_response(b"HTTP/1.1 200 OK").is_err());
+        assert!(parse_response(b"garbage\r\n\r\n[]").is_err());
+        assert!(
+            parse_response(b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n2\r\n[]")
+                .is_err()
+        );
+        assert!(parse_response(b"HTTP/1.1 200 OK\r\n\r\n{\"text\"").is_err());
+    }
+}
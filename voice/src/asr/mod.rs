@@ -0,0 +1,92 @@
+/*
+    语音识别（ASR）后端
+    唤醒后采集到的语音通过统一接口送入识别引擎，得到带时间戳的中间/最终识别结果：
+    1. subprocess：通过 stdin/stdout 与本地识别进程交互
+    2. http：调用本机 HTTP 识别服务
+*/
+
+pub mod http;
+pub mod subprocess;
+
+use crate::config::Settings;
+use http::HttpBackend;
+use serde::{Deserialize, Serialize};
+use subprocess::SubprocessBackend;
+
+/// 识别结果片段
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Transcript {
+    pub text: String,
+    #[serde(default, rename = "final")]
+    pub is_final: bool, // false 为中间结果，后续可能被修正
+    #[serde(default)]
+    pub start_ms: u64, // 相对语音起点的时间戳
+    #[serde(default)]
+    pub end_ms: u64,
+    #[serde(default)]
+    pub confidence: Option<f32>,
+}
+
+/// 语音识别后端接口（一次 start → feed* → finish 对应一条语音）
+pub trait AsrBackend: Send {
+    /// 开始一条新的语音
+    fn start(&mut self, sample_rate: u32) -> Result<(), anyhow::Error>;
+
+    /// 送入一段PCM音频，返回当前可用的中间结果
+    fn feed(&mut self, pcm: &[f32]) -> Result<Vec<Transcript>, anyhow::Error>;
+
+    /// 结束当前语音，返回剩余结果（包含最终结果）
+    fn finish(&mut self) -> Result<Vec<Transcript>, anyhow::Error>;
+
+    /// 一次性识别整段音频
+    fn transcribe(
+        &mut self,
+        pcm: &[f32],
+        sample_rate: u32,
+    ) -> Result<Vec<Transcript>, anyhow::Error> {
+        self.start(sample_rate)?;
+        let mut transcripts = self.feed(pcm)?;
+        transcripts.extend(self.finish()?);
+        Ok(transcripts)
+    }
+}
+
+/// 识别后端配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum AsrConfig {
+    /// 本地识别进程，参数中的 `{sample_rate}` 会被替换为实际采样率
+    Subprocess { command: String, args: Vec<String> },
+    /// 本机 HTTP 识别服务，如 `http://127.0.0.1:2700/asr`
+    Http {
+        url: String,
+        partial_interval_ms: Option<u64>,
+    },
+}
+
+impl AsrConfig {
+    /// 从语音配置加载（未配置识别后端时返回None）
+    pub fn load() -> Option<Self> {
+        Settings::load().asr
+    }
+
+    pub fn build(self) -> Result<Box<dyn AsrBackend>, anyhow::Error> {
+        Ok(match self {
+            AsrConfig::Subprocess { command, args } => {
+                Box::new(SubprocessBackend::new(command, args))
+            }
+            AsrConfig::Http {
+                url,
+                partial_interval_ms,
+            } => Box::new(HttpBackend::new(&url, partial_interval_ms)?),
+        })
+    }
+}
+
+/// f32 采样转 16位小端 PCM
+pub(crate) fn to_pcm16(samples: &[f32]) -> Vec<u8> {
+    samples
+        .iter()
+        .flat_map(|s| ((s.clamp(-1.0, 1.0) * 32767.0) as i16).to_le_bytes())
+        .collect()
+}
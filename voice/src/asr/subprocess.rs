@@ -0,0 +1,223 @@
+use super::{to_pcm16, AsrBackend, Transcript};
+use anyhow::{anyhow, Context};
+use crossbeam_channel::{unbounded, Receiver};
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+use tracing::warn;
+
+/// 等待识别进程输出最终结果的最长时间
+const FINISH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 本地识别进程适配器
+/// 每条语音启动一个进程：stdin 写入16位小端单声道PCM，
+/// stdout 每行输出一个 JSON 格式的 `Transcript`，stdin 关闭后进程输出最终结果并退出
+pub struct SubprocessBackend {
+    command: String,
+    args: Vec<String>,
+    session: Option<Session>,
+    timeout: Duration, // 等待最终结果的最长时间
+}
+
+struct Session {
+    child: Child,
+    stdin: Option<ChildStdin>,
+    results: Receiver<Transcript>,
+    reader: JoinHandle<()>,
+}
+
+impl SubprocessBackend {
+    pub fn new(command: String, args: Vec<String>) -> Self {
+        Self {
+            command,
+            args,
+            session: None,
+            timeout: FINISH_TIMEOUT,
+        }
+    }
+
+    /// 设置等待最终结果的最长时间
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+impl AsrBackend for SubprocessBackend {
+    fn start(&mut self, sample_rate: u32) -> Result<(), anyhow::Error> {
+        // 结束上一条未完成的语音
+        if let Some(mut session) = self.session.take() {
+            let _ = session.child.kill();
+            let _ = session.child.wait();
+        }
+
+        let args = self
+            .args
+            .iter()
+            .map(|arg| arg.replace("{sample_rate}", &sample_rate.to_string()));
+        let mut child = Command::new(&self.command)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .with_context(|| format!("failed to spawn recognizer: {}", self.command))?;
+
+        let stdin = child.stdin.take();
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| anyhow!("recognizer stdout unavailable"))?;
+
+        // 后台线程逐行解析识别结果
+        let (sender, results) = unbounded();
+        let reader = thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else { break };
+                if line.trim().is_empty() {
+                    continue;
+                }
+                match serde_json::from_str::<Transcript>(&line) {
+                    Ok(transcript) => {
+                        if sender.send(transcript).is_err() {
+                            break;
+                        }
+                    }
+                    Err(e) => warn!("invalid recognizer output {:?}: {}", line, e),
+                }
+            }
+        });
+
+        self.session = Some(Session {
+            child,
+            stdin,
+            results,
+            reader,
+        });
+        Ok(())
+    }
+
+    fn feed(&mut self, pcm: &[f32]) -> Result<Vec<Transcript>, anyhow::Error> {
+        let session = self
+            .session
+            .as_mut()
+            .ok_or_else(|| anyhow!("recognizer session not started"))?;
+        if let Some(stdin) = session.stdin.as_mut() {
+            stdin
+                .write_all(&to_pcm16(pcm))
+                .context("failed to write audio to recognizer")?;
+        }
+        Ok(session.results.try_iter().collect())
+    }
+
+    fn finish(&mut self) -> Result<Vec<Transcript>, anyhow::Error> {
+        let mut session = self
+            .session
+            .take()
+            .ok_or_else(|| anyhow!("recognizer session not started"))?;
+
+        // 关闭 stdin 通知进程语音结束
+        drop(session.stdin.take());
+
+        let mut transcripts = Vec::new();
+        loop {
+            match session.results.recv_timeout(self.timeout) {
+                Ok(transcript) => transcripts.push(transcript),
+                // 进程退出，输出已读完
+                Err(crossbeam_channel::RecvTimeoutError::Disconnected) => break,
+                Err(crossbeam_channel::RecvTimeoutError::Timeout) => {
+                    let _ = session.child.kill();
+                    break;
+                }
+            }
+        }
+        let _ = session.reader.join();
+        let status = session.child.wait()?;
+        if !status.success() && transcripts.is_empty() {
+            return Err(anyhow!("recognizer exited with {}", status));
+        }
+        Ok(transcripts)
+    }
+}
+
+impl Drop for SubprocessBackend {
+    fn drop(&mut self) {
+        if let Some(mut session) = self.session.take() {
+            let _ = session.child.kill();
+            let _ = session.child.wait();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shell(script: &str) -> SubprocessBackend {
+        SubprocessBackend::new(
+            "sh".to_string(),
+            vec![
+                "-c".to_string(),
+                script.to_string(),
+                "{sample_rate}".to_string(),
+            ],
+        )
+    }
+
+    #[test]
+    fn transcribes_with_sample_rate() {
+        // 读完音频后以采样率（$0）作为识别文本输出，无效行被忽略
+        let mut backend =
+            shell(r#"n=$(wc -c); echo "not json"; echo "{\"text\":\"$0 $n\",\"final\":true}""#);
+        let transcripts = backend.transcribe(&[0.0; 100], 44100).unwrap();
+        assert_eq!(transcripts.len(), 1);
+        assert_eq!(transcripts[0].text, "44100 200");
+        assert!(transcripts[0].is_final);
+    }
+
+    #[test]
+    fn partial_results_while_feeding() {
+        let mut backend = shell(
+            r#"echo '{"text":"partial"}'; cat > /dev/null; echo '{"text":"final","final":true}'"#,
+        );
+        backend.start(16000).unwrap();
+        // 中间结果由后台线程读取，等待其到达
+        let mut partial = Vec::new();
+        for _ in 0..100 {
+            partial.extend(backend.feed(&[0.0; 16]).unwrap());
+            if !partial.is_empty() {
+                break;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(partial[0].text, "partial");
+        let last = backend.finish().unwrap();
+        assert_eq!(last.len(), 1);
+        assert_eq!(last[0].text, "final");
+    }
+
+    #[test]
+    fn failed_process() {
+        let mut backend = shell("cat > /dev/null; exit 3");
+        let error = backend.transcribe(&[0.0; 10], 16000).unwrap_err();
+        assert!(error.to_string().starts_with("recognizer exited with"));
+    }
+
+    #[test]
+    fn missing_command() {
+        let mut backend = SubprocessBackend::new("/nonexistent/recognizer".to_string(), Vec::new());
+        assert!(backend.start(16000).is_err());
+        assert!(backend.feed(&[0.0; 10]).is_err());
+        assert!(backend.finish().is_err());
+    }
+
+    #[test]
+    fn finish_timeout_kills_process() {
+        let mut backend = shell("exec sleep 10").with_timeout(Duration::from_millis(100));
+        backend.start(16000).unwrap();
+        let started = std::time::Instant::now();
+        assert!(backend.finish().is_err());
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,137 @@
+use super::wav::read_wav;
+use crate::array::ArrayFrontEnd;
+use crate::config::Settings;
+use crate::event::wake_event::WakeEvent;
+use crate::privacy::PrivacySwitch;
+use crossbeam_channel::Sender;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// 每次送入的音频时长
+const CHUNK: Duration = Duration::from_millis(10);
+
+/// WAV 文件输入：按实时速度送入音频帧，代替麦克风（测试与复现）
+pub struct FileStream {
+    playing: Arc<AtomicBool>,
+    closed: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+    sample_rate: u32,
+    _sender: Sender<WakeEvent>, // 文件读完后事件通道仍保持打开，直到释放
+}
+
+impl FileStream {
+    pub fn new(
+        path: impl AsRef<Path>,
+        event_sender: Sender<WakeEvent>,
+        privacy: PrivacySwitch,
+    ) -> Result<Self, anyhow::Error> {
+        let path = path.as_ref().to_path_buf();
+        let wav = read_wav(&path)?;
+        let settings = Settings::load();
+        if wav.sample_rate != settings.sample_rate {
+            warn!(
+                file = wav.sample_rate,
+                configured = settings.sample_rate,
+                "Audio file sample rate differs from the configured rate"
+            );
+        }
+        info!(
+            path = %path.display(),
+            sample_rate = wav.sample_rate,
+            channels = wav.channels,
+            "Reading audio from file"
+        );
+
+        let sample_rate = wav.sample_rate;
+        let channels = wav.channels as usize;
+        let mut front_end = if channels > 1 {
+            Some(ArrayFrontEnd::new(
+                settings.array_config(wav.sample_rate),
+                channels,
+            )?)
+        } else {
+            None
+        };
+        let chunk = (wav.sample_rate as f64 * CHUNK.as_secs_f64()) as usize * channels;
+
+        let playing = Arc::new(AtomicBool::new(false));
+        let closed = Arc::new(AtomicBool::new(false));
+        let (is_playing, is_closed) = (playing.clone(), closed.clone());
+        let sender = event_sender.clone();
+        let thread = thread::Builder::new()
+            .name("audio-file".to_string())
+            .spawn(move || {
+                let mut deadline = Instant::now();
+                for data in wav.samples.chunks(chunk.max(channels)) {
+                    // 暂停时等待恢复
+                    while !is_playing.load(Ordering::SeqCst) {
+                        if is_closed.load(Ordering::SeqCst) {
+                            return;
+                        }
+                        thread::sleep(CHUNK);
+                        deadline = Instant::now();
+                    }
+                    if is_closed.load(Ordering::SeqCst) {
+                        return;
+                    }
+
+                    let sent = privacy.admit(|| {
+                        let frame = match front_end.as_mut() {
+                            Some(front_end) => {
+                                let (mono, direction) = front_end.process(data);
+                                if let Some(direction) = direction {
+                                    let _ = event_sender.send(WakeEvent::Direction(direction));
+                                }
+                                mono
+                            }
+                            None => data.to_vec(),
+                        };
+                        let _ = event_sender.send(WakeEvent::AudioFrame(frame));
+                    });
+                    if sent.is_none() {
+                        if let Some(front_end) = front_end.as_mut() {
+                            front_end.reset();
+                        }
+                    }
+
+                    deadline += CHUNK;
+                    thread::sleep(deadline.saturating_duration_since(Instant::now()));
+                }
+                info!("Audio file finished");
+            })?;
+
+        Ok(Self {
+            playing,
+            closed,
+            thread: Some(thread),
+            sample_rate,
+            _sender: sender,
+        })
+    }
+
+    /// 文件的采样率
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn start(&self) {
+        self.playing.store(true, Ordering::SeqCst);
+    }
+
+    pub fn stop(&self) {
+        self.playing.store(false, Ordering::SeqCst);
+    }
+}
+
+impl Drop for FileStream {
+    fn drop(&mut self) {
+        self.closed.store(true, Ordering::SeqCst);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
@@ -1 +1,79 @@
+pub(crate) mod file;
 pub(crate) mod stream;
+pub mod wav;
+
+use crate::config::Settings;
+use crate::event::wake_event::WakeEvent;
+use crate::privacy::PrivacySwitch;
+use anyhow::anyhow;
+use cpal::traits::{DeviceTrait, HostTrait};
+use crossbeam_channel::Sender;
+use file::FileStream;
+use stream::AudioStream;
+
+/// 麦克风采集的采样率（设备须支持），麦克风输入时唤醒检测与声纹按这一采样率构建；
+/// 文件输入时以文件的采样率为准
+pub const CAPTURE_RATE: u32 = 44100;
+
+/// 音频输入：麦克风（按名称或默认设备）或 WAV 文件
+pub(crate) enum AudioInput {
+    Device(AudioStream),
+    File(FileStream),
+}
+
+impl AudioInput {
+    /// 按配置打开输入，配置了音频文件时优先使用文件
+    pub fn open(
+        settings: &Settings,
+        event_sender: Sender<WakeEvent>,
+        privacy: PrivacySwitch,
+    ) -> Result<Self, anyhow::Error> {
+        if let Some(path) = &settings.audio_file {
+            return Ok(Self::File(FileStream::new(path, event_sender, privacy)?));
+        }
+        let device = input_device(settings.audio_device.as_deref())?;
+        Ok(Self::Device(AudioStream::new(
+            &device,
+            None,
+            // Some(&settings.audio_config()),
+            event_sender,
+            privacy,
+        )?))
+    }
+
+    /// 实际采集的采样率（设备协商的采样率或文件的采样率）
+    pub fn sample_rate(&self) -> u32 {
+        match self {
+            Self::Device(stream) => stream.sample_rate,
+            Self::File(stream) => stream.sample_rate(),
+        }
+    }
+
+    pub fn start(&self) {
+        match self {
+            Self::Device(stream) => stream.start(),
+            Self::File(stream) => stream.start(),
+        }
+    }
+
+    pub fn stop(&self) {
+        match self {
+            Self::Device(stream) => stream.stop(),
+            Self::File(stream) => stream.stop(),
+        }
+    }
+}
+
+/// 按名称查找输入设备，未指定名称时使用默认设备
+pub fn input_device(name: Option<&str>) -> Result<cpal::Device, anyhow::Error> {
+    let host = cpal::default_host();
+    match name {
+        Some(name) => host
+            .input_devices()?
+            .find(|device| device.name().is_ok_and(|n| n == name))
+            .ok_or_else(|| anyhow!("no audio input device named {}", name)),
+        None => host
+            .default_input_device()
+            .ok_or_else(|| anyhow!("no audio input device")),
+    }
+}
@@ -1,14 +1,21 @@
+use super::CAPTURE_RATE;
+use crate::array::ArrayFrontEnd;
+use crate::config::Settings;
 use crate::event::wake_event::WakeEvent;
+use crate::metrics::metrics;
+use crate::privacy::PrivacySwitch;
 use cpal::{
     traits::{DeviceTrait, StreamTrait},
-    Stream,
+    InputCallbackInfo, Stream, StreamInstant,
 };
 use std::time;
 // use tokio::sync::mpsc::Sender;
 use crossbeam_channel::Sender;
+use tracing::{debug, error};
 
 pub struct AudioStream {
     pub stream: Stream,
+    pub sample_rate: u32,
 }
 
 // async fn process_audio(sender: Sender<WakeEvent>, data: Vec<f32>) {
@@ -23,55 +30,127 @@ impl AudioStream {
         device: &cpal::Device,
         config: Option<&cpal::StreamConfig>,
         event_sender: Sender<WakeEvent>,
+        privacy: PrivacySwitch,
     ) -> Result<Self, anyhow::Error> {
-        list_supported_configs(&device);
+        list_supported_configs(device);
+        let settings = Settings::load();
         // Create audio input stream
         let stream_config = match config {
             Some(config) => config.clone(),
-            None => get_compatible_config(device)?,
+            None => get_compatible_config(device, settings.channels)?,
         };
 
+        // Multi-channel input goes through the array front end (beamforming + DOA)
+        let mut front_end = if stream_config.channels > 1 {
+            Some(ArrayFrontEnd::new(
+                settings.array_config(stream_config.sample_rate.0),
+                stream_config.channels as usize,
+            )?)
+        } else {
+            None
+        };
+
+        // Capture timestamp and length of the previous buffer, used to detect overruns
+        let mut previous: Option<(StreamInstant, time::Duration)> = None;
+        let channels = stream_config.channels as usize;
+        let stream_rate = stream_config.sample_rate.0;
+        let sample_rate = stream_rate as f64;
+
+        let error_sender = event_sender.clone();
         let stream = device.build_input_stream(
             &stream_config,
-            move |data: &[f32], _| {
-                // Send raw audio data to wake word detection module
-                event_sender
-                    .send(WakeEvent::AudioFrame(data.to_vec()))
-                    .unwrap();
+            move |data: &[f32], info: &InputCallbackInfo| {
+                metrics().audio_callbacks.inc();
+                let capture = info.timestamp().capture;
+                let length =
+                    time::Duration::from_secs_f64((data.len() / channels) as f64 / sample_rate);
+                if let Some((last, last_length)) = previous {
+                    // A gap well beyond the previous buffer means samples were dropped
+                    if capture
+                        .duration_since(&last)
+                        .is_some_and(|gap| gap > last_length * 3 / 2)
+                    {
+                        metrics().audio_overruns.inc();
+                    }
+                }
+                previous = Some((capture, length));
+
+                // Nothing leaves the callback while privacy mode is on; the switch
+                // cannot flip until the frame has been sent
+                let sent = privacy.admit(|| {
+                    let frame = match front_end.as_mut() {
+                        Some(front_end) => {
+                            let (mono, direction) = front_end.process(data);
+                            if let Some(direction) = direction {
+                                let _ = event_sender.send(WakeEvent::Direction(direction));
+                            }
+                            mono
+                        }
+                        None => data.to_vec(),
+                    };
+                    // Send raw audio data to wake word detection module
+                    // (the receiver is gone only while shutting down)
+                    let _ = event_sender.send(WakeEvent::AudioFrame(frame));
+                });
+                if sent.is_none() {
+                    // Drop samples held by the array front end as well, and don't
+                    // count the pause as an overrun
+                    if let Some(front_end) = front_end.as_mut() {
+                        front_end.reset();
+                    }
+                    previous = None;
+                }
+            },
+            move |err| {
+                error!("Audio stream error: {:?}", err);
+                let _ = error_sender.send(WakeEvent::StreamError(err.to_string()));
             },
-            |err| eprintln!("Audio stream error: {:?}", err),
             Some(time::Duration::from_secs(5)),
         )?;
 
-        Ok(Self { stream })
+        Ok(Self {
+            stream,
+            sample_rate: stream_rate,
+        })
     }
 
     pub fn start(&self) {
         self.stream.play().unwrap();
     }
+
+    pub fn stop(&self) {
+        if let Err(e) = self.stream.pause() {
+            error!("Failed to pause audio stream: {:?}", e);
+        }
+    }
 }
 
 fn list_supported_configs(device: &cpal::Device) {
-    println!("Supported configurations:");
+    debug!("Supported configurations:");
     let configs = device.supported_input_configs().unwrap();
     for config in configs {
-        println!("{:?}", config);
+        debug!("{:?}", config);
     }
 }
 
-fn get_compatible_config(device: &cpal::Device) -> Result<cpal::StreamConfig, anyhow::Error> {
+fn get_compatible_config(
+    device: &cpal::Device,
+    channels: u16,
+) -> Result<cpal::StreamConfig, anyhow::Error> {
     let mut configs = device.supported_input_configs()?;
 
-    // Prefer f32 format, 16kHz sample rate, mono channel
+    // Prefer f32 format, 16kHz sample rate, configured channel count
     let preferred_config = configs.find(|c| {
         c.sample_format() == cpal::SampleFormat::F32
-            && c.min_sample_rate() <= cpal::SampleRate(44100)
-            && c.max_sample_rate() >= cpal::SampleRate(44100)
-            && c.channels() == 1
+            && c.min_sample_rate() <= cpal::SampleRate(CAPTURE_RATE)
+            && c.max_sample_rate() >= cpal::SampleRate(CAPTURE_RATE)
+            && c.channels() == channels
     });
 
     match preferred_config {
-        Some(config) => Ok(config.with_sample_rate(cpal::SampleRate(44100)).into()),
+        Some(config) => Ok(config
+            .with_sample_rate(cpal::SampleRate(CAPTURE_RATE))
+            .into()),
         None => Err(anyhow::anyhow!("No compatible audio configuration found")),
     }
 }
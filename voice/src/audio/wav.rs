@@ -0,0 +1,233 @@
+/*
+    WAV 文件读写（PCM 16位 / IEEE float 32位，支持多声道交织采样）
+    用于录音落盘、离线评估以及多声道测试样本
+*/
+
+use anyhow::{anyhow, Context};
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+const FORMAT_PCM: u16 = 1;
+const FORMAT_IEEE_FLOAT: u16 = 3;
+
+/// 解码后的音频数据（多声道时为交织排列）
+#[derive(Debug, Clone)]
+pub struct WavData {
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub samples: Vec<f32>,
+}
+
+impl WavData {
+    /// 每个声道的采样点数
+    pub fn frames(&self) -> usize {
+        self.samples.len() / self.channels.max(1) as usize
+    }
+
+    /// 提取单个声道
+    pub fn channel(&self, index: usize) -> Vec<f32> {
+        self.samples
+            .iter()
+            .skip(index)
+            .step_by(self.channels as usize)
+            .copied()
+            .collect()
+    }
+}
+
+/// 读取WAV文件
+pub fn read_wav(path: impl AsRef<Path>) -> Result<WavData, anyhow::Error> {
+    let path = path.as_ref();
+    let file =
+        File::open(path).with_context(|| format!("failed to open wav file: {}", path.display()))?;
+    decode_wav(BufReader::new(file))
+        .with_context(|| format!("invalid wav file: {}", path.display()))
+}
+
+/// 解码WAV数据（如合成引擎的标准输出）
+/// 流式输出的数据块长度不可信，按实际读到的数据为准
+pub fn decode_wav(mut reader: impl Read + Seek) -> Result<WavData, anyhow::Error> {
+    let mut tag = [0u8; 4];
+    reader.read_exact(&mut tag)?;
+    if &tag != b"RIFF" {
+        return Err(anyhow!("not a RIFF file"));
+    }
+    reader.read_u32::<LittleEndian>()?;
+    reader.read_exact(&mut tag)?;
+    if &tag != b"WAVE" {
+        return Err(anyhow!("not a WAVE file"));
+    }
+
+    let mut format = None;
+    loop {
+        if reader.read_exact(&mut tag).is_err() {
+            return Err(anyhow!("missing data chunk"));
+        }
+        let size = reader.read_u32::<LittleEndian>()?;
+
+        match &tag {
+            b"fmt " => {
+                let audio_format = reader.read_u16::<LittleEndian>()?;
+                let channels = reader.read_u16::<LittleEndian>()?;
+                let sample_rate = reader.read_u32::<LittleEndian>()?;
+                reader.read_u32::<LittleEndian>()?; // byte rate
+                reader.read_u16::<LittleEndian>()?; // block align
+                let bits = reader.read_u16::<LittleEndian>()?;
+                // 跳过扩展字段
+                reader.seek(SeekFrom::Current(size as i64 - 16))?;
+                format = Some((audio_format, channels, sample_rate, bits));
+            }
+            b"data" => {
+                let (audio_format, channels, sample_rate, bits) =
+                    format.ok_or_else(|| anyhow!("data chunk before fmt chunk"))?;
+                let mut data = Vec::new();
+                reader.by_ref().take(size as u64).read_to_end(&mut data)?;
+                let mut cursor = &data[..];
+
+                let samples = match (audio_format, bits) {
+                    (FORMAT_PCM, 16) => (0..data.len() / 2)
+                        .map(|_| Ok(cursor.read_i16::<LittleEndian>()? as f32 / 32768.0))
+                        .collect::<Result<Vec<_>, std::io::Error>>()?,
+                    (FORMAT_IEEE_FLOAT, 32) => (0..data.len() / 4)
+                        .map(|_| cursor.read_f32::<LittleEndian>())
+                        .collect::<Result<Vec<_>, std::io::Error>>()?,
+                    _ => {
+                        return Err(anyhow!(
+                            "unsupported wav format {} with {} bits",
+                            audio_format,
+                            bits
+                        ))
+                    }
+                };
+
+                return Ok(WavData {
+                    sample_rate,
+                    channels,
+                    samples,
+                });
+            }
+            _ => {
+                // 跳过无关的块（块大小按偶数对齐）
+                reader.seek(SeekFrom::Current((size + size % 2) as i64))?;
+            }
+        }
+    }
+}
+
+/// 写入16位PCM格式的WAV文件
+pub fn write_wav(path: impl AsRef<Path>, wav: &WavData) -> Result<(), anyhow::Error> {
+    let path = path.as_ref();
+    let file = File::create(path)
+        .with_context(|| format!("failed to create wav file: {}", path.display()))?;
+    let mut writer = BufWriter::new(file);
+
+    let data_size = (wav.samples.len() * 2) as u32;
+    let block_align = wav.channels * 2;
+
+    writer.write_all(b"RIFF")?;
+    writer.write_u32::<LittleEndian>(36 + data_size)?;
+    writer.write_all(b"WAVE")?;
+
+    writer.write_all(b"fmt ")?;
+    writer.write_u32::<LittleEndian>(16)?;
+    writer.write_u16::<LittleEndian>(FORMAT_PCM)?;
+    writer.write_u16::<LittleEndian>(wav.channels)?;
+    writer.write_u32::<LittleEndian>(wav.sample_rate)?;
+    writer.write_u32::<LittleEndian>(wav.sample_rate * block_align as u32)?;
+    writer.write_u16::<LittleEndian>(block_align)?;
+    writer.write_u16::<LittleEndian>(16)?;
+
+    writer.write_all(b"data")?;
+    writer.write_u32::<LittleEndian>(data_size)?;
+    for sample in &wav.samples {
+        writer.write_i16::<LittleEndian>((sample.clamp(-1.0, 1.0) * 32767.0) as i16)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    /// 按块拼出 WAV 数据
+    fn riff(chunks: &[(&[u8; 4], u32, Vec<u8>)]) -> Vec<u8> {
+        let mut bytes = b"RIFF\xff\xff\xff\xffWAVE".to_vec();
+        for (tag, size, data) in chunks {
+            bytes.extend_from_slice(*tag);
+            bytes.extend_from_slice(&size.to_le_bytes());
+            bytes.extend_from_slice(data);
+        }
+        bytes
+    }
+
+    fn fmt(audio_format: u16, channels: u16, bits: u16) -> (&'static [u8; 4], u32, Vec<u8>) {
+        let mut data = Vec::new();
+        data.write_u16::<LittleEndian>(audio_format).unwrap();
+        data.write_u16::<LittleEndian>(channels).unwrap();
+        data.write_u32::<LittleEndian>(22050).unwrap();
+        data.write_u32::<LittleEndian>(0).unwrap();
+        data.write_u16::<LittleEndian>(0).unwrap();
+        data.write_u16::<LittleEndian>(bits).unwrap();
+        (b"fmt ", 16, data)
+    }
+
+    #[test]
+    fn round_trips_pcm_files() {
+        let path = std::env::temp_dir().join(format!("wav-test-{}.wav", std::process::id()));
+        let wav = WavData {
+            sample_rate: 16000,
+            channels: 2,
+            samples: vec![0.0, 0.5, -0.5, 1.0, 2.0, -2.0],
+        };
+        write_wav(&path, &wav).unwrap();
+        let read = read_wav(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            (read.sample_rate, read.channels, read.frames()),
+            (16000, 2, 3)
+        );
+        let expected = [0.0, 0.5, -0.5, 1.0, 1.0, -1.0];
+        for (a, b) in read.samples.iter().zip(expected) {
+            assert!((a - b).abs() < 1e-4, "{:?}", read.samples);
+        }
+        assert_eq!(read.channel(1).len(), 3);
+        assert!(read_wav(&path).is_err());
+    }
+
+    #[test]
+    fn decodes_streamed_float_output() {
+        // 流式输出：数据块长度未知，前面有无关的块（奇数长度按偶数对齐）
+        let samples: Vec<u8> = [0.25f32, -0.75]
+            .iter()
+            .flat_map(|s| s.to_le_bytes())
+            .collect();
+        let bytes = riff(&[
+            (b"LIST", 3, vec![1, 2, 3, 0]),
+            fmt(FORMAT_IEEE_FLOAT, 1, 32),
+            (b"data", u32::MAX, samples),
+        ]);
+        let wav = decode_wav(Cursor::new(bytes)).unwrap();
+        assert_eq!((wav.sample_rate, wav.channels), (22050, 1));
+        assert_eq!(wav.samples, [0.25, -0.75]);
+    }
+
+    #[test]
+    fn rejects_invalid_data() {
+        let error = |bytes: Vec<u8>| decode_wav(Cursor::new(bytes)).unwrap_err().to_string();
+        assert_eq!(error(b"RIFX\0\0\0\0WAVE".to_vec()), "not a RIFF file");
+        assert_eq!(error(riff(&[fmt(FORMAT_PCM, 1, 16)])), "missing data chunk");
+        assert_eq!(
+            error(riff(&[(b"data", 0, Vec::new())])),
+            "data chunk before fmt chunk"
+        );
+        assert_eq!(
+            error(riff(&[fmt(FORMAT_PCM, 1, 8), (b"data", 0, Vec::new())])),
+            "unsupported wav format 1 with 8 bits"
+        );
+    }
+}
@@ -1,22 +1,90 @@
+use crate::array::ArrayConfig;
+use crate::asr::AsrConfig;
+use crate::features::{FeatureConfig, FeatureKind};
+use crate::recorder::RecorderConfig;
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// 日志输出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogFormat {
+    Text, // 便于阅读的文本
+    Json, // 每行一个 JSON 对象，便于采集分析
+}
+
+/// 运行配置，配置文件（TOML）中未指定的项使用默认值
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct Settings {
     pub channels: u16,
     pub sample_rate: u32,
     pub buffer_size: u32,
     pub wake_threshold: f32,
     pub wakeword_path: String,
+    pub audio_device: Option<String>, // 输入设备名称，为空时使用默认设备
+    pub audio_file: Option<String>,   // 以 WAV 文件代替麦克风输入（按实时速度送入）
+    pub wakeword_config: Option<String>, // 唤醒词配置文件（JSON：template、threshold、reject_threshold），覆盖默认的模版与阈值
+    pub wakeword_reload_secs: Option<f32>, // 检查唤醒词配置与模版是否修改的周期，为空时不重新加载
+    pub speaker_threshold: f32,
+    pub restricted_intents: Vec<String>, // 仅限已验证的说话人触发的意图（如车窗）
+    pub mic_positions: Vec<f32>,
+    pub reject_threshold: f32,
+    pub recorder_dir: Option<String>,
+    pub recorder_seconds: f32,
+    pub recorder_max_files: usize,
+    pub feature_kind: FeatureKind,
+    pub asr: Option<AsrConfig>,
+    pub can_interface: Option<String>, // 为空时使用模拟总线
+    pub dbc_path: Option<String>,      // 为空时使用内置报文定义
+    pub obd_port: Option<String>,      // ELM327 适配器串口，为空时不启用诊断
+    pub obd_baud_rate: u32,
+    pub skills: Vec<String>,         // 启动时注册的技能
+    pub grammar_dir: Option<String>, // 额外的意图语法目录（技能自带语法）
+    pub ipc_socket: Option<String>,  // 本地 IPC 套接字路径，为空时不启用
+    pub mqtt_broker: Option<String>, // MQTT 代理地址 host[:port]，为空时不启用
+    pub mqtt_client_id: String,
+    pub mqtt_topic_prefix: String, // 主题前缀：<prefix>/wake、<prefix>/intent、<prefix>/health、<prefix>/command
+    pub mqtt_health_secs: f32,     // 健康状态发布周期
+    pub event_log_dir: Option<String>, // 事件日志目录（用于回放复现问题），为空时不记录
+    pub log_format: LogFormat,
+    pub log_level: String,                // 默认日志级别，可被 RUST_LOG 覆盖
+    pub metrics_addr: Option<String>,     // Prometheus /metrics 监听地址，为空时不启用
+    pub profile_path: Option<String>,     // 用户档案文件，为空时不保存（重启后丢失）
+    pub timer_path: Option<String>,       // 定时提醒文件，为空时不保存（重启后丢失）
+    pub low_power_threshold: Option<f32>, // 驻车低功耗监听的活动能量阈值（均方根），为空时不启用
+    pub low_power_hold_secs: f32,         // 检测到活动后保持完整唤醒检测的时长
+    pub tts_command: Option<String>, // 离线语音合成命令（文本从标准输入读取，WAV 输出到标准输出），为空时只播报关键提示
+    pub speech_output: bool,         // 是否经默认音频输出设备播报
 }
 
+/// 启动时设置的配置（命令行指定的配置文件与选项）
+static SETTINGS: OnceLock<Settings> = OnceLock::new();
+
 impl Settings {
+    /// 当前配置（未设置时为默认配置）
     pub fn load() -> Self {
-        Self {
-            channels: 1,
-            sample_rate: 96000,
-            wake_threshold: 0.0,
-            buffer_size: 0,
-            wakeword_path: "".into(),
+        SETTINGS.get().cloned().unwrap_or_default()
+    }
+
+    /// 设置进程的配置，只在启动时调用一次
+    pub fn init(settings: Settings) {
+        if SETTINGS.set(settings).is_err() {
+            panic!("settings already initialized");
         }
     }
 
+    /// 读取配置文件
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, anyhow::Error> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("failed to read config: {}", path.display()))?;
+        toml::from_str(&text).with_context(|| format!("invalid config: {}", path.display()))
+    }
+
     pub fn audio_config(&self) -> cpal::StreamConfig {
         cpal::StreamConfig {
             channels: self.channels,
@@ -24,4 +92,87 @@ impl Settings {
             buffer_size: cpal::BufferSize::Fixed(self.buffer_size),
         }
     }
+
+    /// 唤醒词模版对应的特征配置（采样率以实际音频流为准）
+    pub fn feature_config(&self, sample_rate: u32) -> FeatureConfig {
+        FeatureConfig::new(sample_rate, 512, 256)
+    }
+
+    /// 多麦克风阵列配置（采样率以实际音频流为准）
+    pub fn array_config(&self, sample_rate: u32) -> ArrayConfig {
+        ArrayConfig {
+            sample_rate,
+            mic_positions: self.mic_positions.clone(),
+            window: sample_rate as usize / 20,
+            energy_gate: 1e-5,
+            seat_margin_deg: 10.0,
+        }
+    }
+
+    /// 黑匣子录音配置（未配置目录时不启用），采样率与音频流一致
+    pub fn recorder_config(&self, sample_rate: u32) -> Option<RecorderConfig> {
+        self.recorder_dir.as_ref().map(|dir| RecorderConfig {
+            directory: PathBuf::from(dir),
+            sample_rate,
+            pre_roll_secs: self.recorder_seconds,
+            post_roll_secs: 1.0,
+            max_files: self.recorder_max_files,
+            cooldown_secs: 5.0,
+        })
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            channels: 1,
+            sample_rate: 96000,
+            wake_threshold: 0.0,
+            buffer_size: 0,
+            wakeword_path: "".into(),
+            audio_device: None,
+            audio_file: None,
+            wakeword_config: None,
+            wakeword_reload_secs: Some(2.0),
+            speaker_threshold: 0.85,
+            restricted_intents: vec!["window".into()],
+            mic_positions: vec![0.0],
+            reject_threshold: 0.0,
+            recorder_dir: None,
+            recorder_seconds: 10.0,
+            recorder_max_files: 50,
+            feature_kind: FeatureKind::Mfcc,
+            asr: None,
+            can_interface: None,
+            dbc_path: None,
+            obd_port: None,
+            obd_baud_rate: 38400,
+            skills: [
+                "climate",
+                "window",
+                "media",
+                "vehicle_status",
+                "timer",
+                "privacy",
+            ]
+            .map(String::from)
+            .to_vec(),
+            grammar_dir: None,
+            ipc_socket: None,
+            mqtt_broker: None,
+            mqtt_client_id: "voice-assistant".into(),
+            mqtt_topic_prefix: "vehicle/voice-assistant".into(),
+            mqtt_health_secs: 30.0,
+            event_log_dir: None,
+            log_format: LogFormat::Text,
+            log_level: "info".into(),
+            metrics_addr: None,
+            profile_path: None,
+            timer_path: None,
+            low_power_threshold: Some(0.02),
+            low_power_hold_secs: 3.0,
+            tts_command: Some("espeak-ng --stdout".into()),
+            speech_output: true,
+        }
+    }
 }
@@ -1,7 +1,20 @@
+use crate::array::{DirectionOfArrival, Seat};
+use crate::speaker::Verification;
+use serde::{Deserialize, Serialize};
+
 #[derive(Debug, Clone)]
 pub enum WakeEvent {
     AudioFrame(Vec<f32>),
-    WakeDetected,
+    WakeDetected(WakeInfo),
+    Direction(DirectionOfArrival),
+    StreamError(String),
+}
+
+/// 唤醒事件附带的信息
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WakeInfo {
+    pub speaker: Option<Verification>, // 已验证的说话人（未注册或验证失败时为空）
+    pub seat: Option<Seat>,            // 麦克风阵列判定的说话座位
 }
 
 // pub async fn event_loop(mut rx: mpsc::Receiver<WakeEvent>, mut detector: WakeDetector) {
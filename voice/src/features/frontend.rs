@@ -0,0 +1,157 @@
+use super::FeatureConfig;
+use ndarray::{s, Array1, Array2, ArrayView1, Axis};
+use rustfft::{num_complex::Complex, Fft, FftPlanner};
+use std::f32::consts::PI;
+use std::sync::Arc;
+
+/// 特征前端：预加重 + 分帧 + 加窗 (Hamming) + FFT功率谱
+pub struct SpectralFrontEnd {
+    frame_length: usize,
+    frame_shift: usize,
+    pre_emphasis: Option<f32>,
+    window: Array1<f32>, // 预计算的 Hamming 窗
+    fft: Arc<dyn Fft<f32>>,
+}
+
+impl SpectralFrontEnd {
+    pub fn new(config: &FeatureConfig) -> Self {
+        let frame_length = config.frame_length;
+        let window = (0..frame_length)
+            .map(|i| 0.54 - 0.46 * (2.0 * PI * i as f32 / (frame_length - 1) as f32).cos())
+            .collect();
+        let fft = FftPlanner::new().plan_fft_forward(frame_length);
+
+        Self {
+            frame_length,
+            frame_shift: config.frame_shift,
+            pre_emphasis: config.pre_emphasis,
+            window,
+            fft,
+        }
+    }
+
+    pub fn frame_length(&self) -> usize {
+        self.frame_length
+    }
+
+    /// 功率谱点数（仅保留对称部分前一半）
+    pub fn spectrum_size(&self) -> usize {
+        self.frame_length / 2 + 1
+    }
+
+    /// 计算逐帧功率谱，返回 (帧数, 帧长/2+1) 矩阵
+    pub fn power_spectrum(&self, audio: &[f32]) -> Array2<f32> {
+        let frames = match self.pre_emphasis {
+            Some(alpha) => self.frame_and_window(pre_emphasize(audio, alpha).view()),
+            None => self.frame_and_window(ArrayView1::from(audio)),
+        };
+        self.fft_power_spectrum(&frames)
+    }
+
+    fn frame_and_window(&self, audio: ArrayView1<f32>) -> Array2<f32> {
+        // 不足一帧时返回 0 帧
+        let frame_num = match audio.len().checked_sub(self.frame_length) {
+            Some(rest) => 1 + rest / self.frame_shift,
+            None => 0,
+        };
+        let mut frames = Array2::zeros((frame_num, self.frame_length));
+
+        // 分帧并加窗
+        for (frame_idx, mut frame) in frames.axis_iter_mut(Axis(0)).enumerate() {
+            let start = frame_idx * self.frame_shift;
+            let end = start + self.frame_length;
+
+            if end > audio.len() {
+                break;
+            }
+
+            let frame_slices = audio.slice(s![start..end]);
+
+            frame.assign(&(&frame_slices * &self.window));
+        }
+
+        frames
+    }
+
+    fn fft_power_spectrum(&self, frames: &Array2<f32>) -> Array2<f32> {
+        let fft_size = self.frame_length;
+        let spectrum_size = self.spectrum_size();
+        let mut power_spectrum = Array2::zeros((frames.nrows(), spectrum_size));
+
+        for (i, frame) in frames.axis_iter(Axis(0)).enumerate() {
+            // 转换为复数输入
+            let mut buffer: Vec<Complex<f32>> =
+                frame.iter().map(|x| Complex::new(*x, 0.0)).collect();
+
+            // 执行FFT
+            self.fft.process(&mut buffer);
+
+            // 计算功率谱（取模平方）
+            for (j, bin) in buffer.iter().take(spectrum_size).enumerate() {
+                power_spectrum[[i, j]] = bin.norm_sqr() / fft_size as f32;
+            }
+        }
+        power_spectrum
+    }
+}
+
+/// 预加重（高频增强）
+fn pre_emphasize(audio: &[f32], alpha: f32) -> Array1<f32> {
+    let mut output = Array1::zeros(audio.len());
+    if audio.is_empty() {
+        return output;
+    }
+    output[0] = audio[0];
+
+    for i in 1..audio.len() {
+        output[i] = audio[i] - alpha * audio[i - 1];
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn power_spectrum_peaks_at_tone() {
+        let mut config = FeatureConfig::new(16000, 512, 160);
+        config.pre_emphasis = None;
+        let front_end = SpectralFrontEnd::new(&config);
+        assert_eq!(front_end.spectrum_size(), 257);
+
+        // 1000Hz 落在第 32 个频点（16000 / 512 = 31.25Hz 每点）
+        let audio: Vec<f32> = (0..1600)
+            .map(|i| (2.0 * PI * 1000.0 * i as f32 / 16000.0).sin())
+            .collect();
+        let spectrum = front_end.power_spectrum(&audio);
+        assert_eq!(spectrum.nrows(), 1 + (1600 - 512) / 160);
+        for row in spectrum.rows() {
+            let peak = row
+                .iter()
+                .enumerate()
+                .max_by(|a, b| a.1.total_cmp(b.1))
+                .unwrap()
+                .0;
+            assert_eq!(peak, 32);
+        }
+    }
+
+    #[test]
+    fn pre_emphasis_removes_dc() {
+        let output = pre_emphasize(&[1.0; 4], 0.97);
+        assert_eq!(output[0], 1.0);
+        assert!(output.iter().skip(1).all(|v| (v - 0.03).abs() < 1e-6));
+    }
+
+    #[test]
+    fn short_input_has_no_frames() {
+        let front_end = SpectralFrontEnd::new(&FeatureConfig::new(16000, 512, 160));
+        for len in [0, 1, 511] {
+            let spectrum = front_end.power_spectrum(&vec![0.5; len]);
+            assert_eq!(spectrum.dim(), (0, 257));
+        }
+        assert_eq!(front_end.power_spectrum(&[0.5; 512]).nrows(), 1);
+    }
+}
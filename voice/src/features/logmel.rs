@@ -0,0 +1,41 @@
+use super::frontend::SpectralFrontEnd;
+use super::mel::MelFilterBank;
+use super::{FeatureConfig, FeatureExtractor};
+use ndarray::Array2;
+
+/// 对数能量下限，避免 log(0)
+const LOG_FLOOR: f32 = 1e-10;
+
+/// 对数梅尔滤波器组特征（常用于神经网络声学模型）
+pub struct LogMelExtractor {
+    front_end: SpectralFrontEnd,
+    mel_filters: MelFilterBank,
+    dim: usize,
+}
+
+impl LogMelExtractor {
+    pub fn new(config: FeatureConfig) -> Self {
+        Self {
+            front_end: SpectralFrontEnd::new(&config),
+            mel_filters: MelFilterBank::new(&config),
+            dim: config.mel_filter_num,
+        }
+    }
+}
+
+impl FeatureExtractor for LogMelExtractor {
+    fn compute_frames(&self, audio: &[f32]) -> Array2<f32> {
+        let power_spectrum = self.front_end.power_spectrum(audio);
+        self.mel_filters
+            .apply(&power_spectrum)
+            .mapv(|x| x.max(LOG_FLOOR).ln())
+    }
+
+    fn dim(&self) -> usize {
+        self.dim
+    }
+
+    fn frame_length(&self) -> usize {
+        self.front_end.frame_length()
+    }
+}
@@ -0,0 +1,135 @@
+use super::FeatureConfig;
+use ndarray::{Array1, Array2};
+use serde::{Deserialize, Serialize};
+
+/// 梅尔刻度公式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MelScale {
+    /// HTK：2595 * log10(1 + f / 700)
+    Htk,
+    /// Slaney（Auditory Toolbox）：1kHz 以下线性，以上对数
+    Slaney,
+}
+
+impl MelScale {
+    /// 频率转梅尔刻度
+    pub fn hz_to_mel(self, hz: f32) -> f32 {
+        match self {
+            MelScale::Htk => 2595.0 * (1.0 + hz / 700.0).log10(),
+            MelScale::Slaney => {
+                if hz < 1000.0 {
+                    hz * 3.0 / 200.0
+                } else {
+                    15.0 + (hz / 1000.0).ln() * 27.0 / 6.4f32.ln()
+                }
+            }
+        }
+    }
+
+    /// 梅尔刻度转频率
+    pub fn mel_to_hz(self, mel: f32) -> f32 {
+        match self {
+            MelScale::Htk => 700.0 * (10.0f32.powf(mel / 2595.0) - 1.0),
+            MelScale::Slaney => {
+                if mel < 15.0 {
+                    mel * 200.0 / 3.0
+                } else {
+                    1000.0 * ((mel - 15.0) * 6.4f32.ln() / 27.0).exp()
+                }
+            }
+        }
+    }
+}
+
+/// 梅尔滤波器组
+pub struct MelFilterBank {
+    filters: Array2<f32>,   // (滤波器数量, 帧长/2+1)
+    center_hz: Array1<f32>, // 各滤波器中心频率
+}
+
+impl MelFilterBank {
+    /// 生成梅尔滤波器组（三角滤波器，在梅尔刻度上均匀分布于 fmin~fmax）
+    pub fn new(config: &FeatureConfig) -> Self {
+        let nyquist = config.sample_rate as f32 / 2.0;
+        let fft_size = config.frame_length;
+        let filter_num = config.mel_filter_num;
+        let scale = config.mel_scale;
+
+        let mel_low = scale.hz_to_mel(config.fmin);
+        let mel_high = scale.hz_to_mel(config.fmax().min(nyquist));
+        // 在梅尔刻度上均匀分布的点
+        let mel_points = Array1::linspace(mel_low, mel_high, filter_num + 2);
+        let hz_points = mel_points.mapv(|m| scale.mel_to_hz(m));
+
+        // 转换为FFT bin索引
+        let bin_indices = hz_points.mapv(|hz| (hz / nyquist) * (fft_size / 2) as f32);
+
+        // 构建滤波器组
+        let mut filters = Array2::zeros((filter_num, fft_size / 2 + 1));
+
+        for i in 0..filter_num {
+            let (left, center, right) = (bin_indices[i], bin_indices[i + 1], bin_indices[i + 2]);
+            // 三角形取上升与下降坡度的较小值；边界取整后落在三角形外的频点权重为 0
+            for bin in left as usize..=(right as usize).min(fft_size / 2) {
+                let rise = (bin as f32 - left) / (center - left);
+                let fall = (right - bin as f32) / (right - center);
+                filters[[i, bin]] = rise.min(fall).max(0.0);
+            }
+        }
+
+        Self {
+            filters,
+            center_hz: hz_points.slice(ndarray::s![1..filter_num + 1]).to_owned(),
+        }
+    }
+
+    /// 应用梅尔滤波器组 （矩阵乘法）
+    pub fn apply(&self, power_spectrum: &Array2<f32>) -> Array2<f32> {
+        power_spectrum.dot(&self.filters.t())
+    }
+
+    pub fn center_hz(&self) -> &Array1<f32> {
+        &self.center_hz
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scales_round_trip() {
+        for scale in [MelScale::Htk, MelScale::Slaney] {
+            for hz in [0.0, 100.0, 999.0, 1000.0, 4000.0, 8000.0] {
+                let back = scale.mel_to_hz(scale.hz_to_mel(hz));
+                assert!((back - hz).abs() < 0.05, "{:?} {} -> {}", scale, hz, back);
+            }
+        }
+        assert!((MelScale::Htk.hz_to_mel(1000.0) - 1000.0).abs() < 0.1);
+        assert_eq!(MelScale::Slaney.hz_to_mel(600.0), 9.0);
+    }
+
+    #[test]
+    fn filters_cover_the_band() {
+        let config = FeatureConfig {
+            fmin: 100.0,
+            fmax: Some(4000.0),
+            ..FeatureConfig::new(16000, 512, 256)
+        };
+        let bank = MelFilterBank::new(&config);
+        assert_eq!(bank.filters.dim(), (26, 257));
+
+        let centers = bank.center_hz();
+        assert!(centers.windows(2).into_iter().all(|w| w[0] < w[1]));
+        assert!(centers[0] > 100.0 && centers[25] < 4000.0);
+        // 三角滤波器的峰值不超过 1，且高于 fmax 的频点没有权重
+        assert!(bank.filters.iter().all(|&w| (0.0..=1.0).contains(&w)));
+        let cutoff = (4000.0 / 8000.0 * 256.0) as usize + 1;
+        assert!(bank
+            .filters
+            .slice(ndarray::s![.., cutoff..])
+            .iter()
+            .all(|&w| w == 0.0));
+    }
+}
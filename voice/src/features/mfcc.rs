@@ -0,0 +1,103 @@
+/*
+    MFCC（梅尔频率倒谱系数）工具函数
+*/
+
+use super::frontend::SpectralFrontEnd;
+use super::mel::MelFilterBank;
+use super::{FeatureConfig, FeatureExtractor};
+use ndarray::{Array1, Array2};
+use std::f32::consts::PI;
+
+/// MFCC 计算器（预配置参数）
+pub struct MfccExtractor {
+    front_end: SpectralFrontEnd,
+    mel_filters: MelFilterBank, // 预计算的梅尔滤波器组
+    dct_matrix: Array2<f32>,    // DCT变换矩阵
+    lifter: Option<Array1<f32>>,
+}
+
+impl MfccExtractor {
+    /// 创建 MFCC 计算器
+    pub fn new(
+        sample_rate: u32,
+        frame_length: usize,
+        frame_shift: usize,
+        mel_filter_num: usize,
+        cepstrum_num: usize,
+    ) -> Self {
+        Self::with_config(FeatureConfig {
+            mel_filter_num,
+            cepstrum_num,
+            ..FeatureConfig::new(sample_rate, frame_length, frame_shift)
+        })
+    }
+
+    pub fn with_config(config: FeatureConfig) -> Self {
+        Self {
+            front_end: SpectralFrontEnd::new(&config),
+            // 预生成梅尔滤波器组
+            mel_filters: MelFilterBank::new(&config),
+            // 预生成DCT矩阵（Type-II 离散余弦变换）
+            dct_matrix: create_dct_matrix(config.mel_filter_num, config.cepstrum_num),
+            lifter: config.lifter.map(|l| create_lifter(config.cepstrum_num, l)),
+        }
+    }
+
+    /// 对数压缩 + DCT 得到倒谱系数
+    fn log_and_dct(&self, mel_energies: &Array2<f32>) -> Array2<f32> {
+        // 对数能量（加1避免log(0)）
+        let log_energies = mel_energies.mapv(|x| (x + 1.0).log10());
+
+        // DCT-II 变换 (取前n_cepstrum系数)
+        log_energies.dot(&self.dct_matrix)
+    }
+}
+
+impl FeatureExtractor for MfccExtractor {
+    /// 逐帧计算MFCC，返回 (帧数, 倒谱系数数量) 矩阵
+    fn compute_frames(&self, audio: &[f32]) -> Array2<f32> {
+        // 预加重 + 分帧 + 加窗 + 功率谱
+        let power_spectrum = self.front_end.power_spectrum(audio);
+
+        // 应用梅尔滤波器组
+        let mel_energies = self.mel_filters.apply(&power_spectrum);
+
+        // 对数压缩 + DCT
+        let cepstrum = self.log_and_dct(&mel_energies);
+
+        // 倒谱提升
+        match &self.lifter {
+            Some(lifter) => cepstrum * lifter,
+            None => cepstrum,
+        }
+    }
+
+    fn dim(&self) -> usize {
+        self.dct_matrix.ncols()
+    }
+
+    fn frame_length(&self) -> usize {
+        self.front_end.frame_length()
+    }
+}
+
+/// 生成DCT矩阵（Type-II）
+fn create_dct_matrix(mel_filter_num: usize, cepstrum_num: usize) -> Array2<f32> {
+    let mut dct = Array2::zeros((mel_filter_num, cepstrum_num));
+    let scale = (2.0 / mel_filter_num as f32).sqrt();
+
+    for i in 0..cepstrum_num {
+        for j in 0..mel_filter_num {
+            dct[[j, i]] = scale * ((PI / mel_filter_num as f32) * (i as f32 + 0.5) * j as f32).cos()
+        }
+    }
+
+    dct
+}
+
+/// 生成倒谱提升窗：1 + (L/2) * sin(pi * n / L)
+pub(crate) fn create_lifter(cepstrum_num: usize, lifter: f32) -> Array1<f32> {
+    (0..cepstrum_num)
+        .map(|n| 1.0 + lifter / 2.0 * (PI * n as f32 / lifter).sin())
+        .collect()
+}
@@ -0,0 +1,163 @@
+/*
+    声学特征提取
+    所有特征共享同一套前端（预加重、分帧、加窗、FFT功率谱）与梅尔滤波器组：
+    1. mfcc：梅尔频率倒谱系数
+    2. logmel：对数梅尔滤波器组能量
+    3. plp：感知线性预测倒谱系数
+    不同的检测器/识别器可以按训练时使用的特征选择对应实现
+*/
+
+pub mod frontend;
+pub mod logmel;
+pub mod mel;
+pub mod mfcc;
+pub mod plp;
+
+use logmel::LogMelExtractor;
+use mel::MelScale;
+use mfcc::MfccExtractor;
+use ndarray::{Array1, Array2};
+use plp::PlpExtractor;
+use serde::{Deserialize, Serialize};
+
+/// 特征提取器公共接口
+pub trait FeatureExtractor: Send {
+    /// 逐帧计算特征，返回 (帧数, 特征维度) 矩阵
+    fn compute_frames(&self, audio: &[f32]) -> Array2<f32>;
+
+    /// 每帧特征维度
+    fn dim(&self) -> usize;
+
+    /// 单帧所需的最少采样点数
+    fn frame_length(&self) -> usize;
+
+    /// 取第一帧特征（单帧输入）
+    fn compute(&self, audio: &[f32]) -> Array1<f32> {
+        self.compute_frames(audio).row(0).to_owned()
+    }
+}
+
+/// 特征类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FeatureKind {
+    Mfcc,
+    LogMel,
+    Plp,
+}
+
+/// 特征前端公共配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeatureConfig {
+    pub sample_rate: u32,
+    pub frame_length: usize,       // 帧长（采样点数）
+    pub frame_shift: usize,        // 帧移（采样点数）
+    pub mel_filter_num: usize,     // 梅尔滤波器数量
+    pub fmin: f32,                 // 滤波器组最低频率（Hz）
+    pub fmax: Option<f32>,         // 滤波器组最高频率（Hz），默认奈奎斯特频率
+    pub mel_scale: MelScale,       // 梅尔刻度公式
+    pub pre_emphasis: Option<f32>, // 预加重系数
+    pub cepstrum_num: usize,       // 倒谱系数数量（MFCC/PLP）
+    pub lifter: Option<f32>,       // 倒谱提升系数（MFCC/PLP）
+    pub lpc_order: usize,          // 线性预测阶数（PLP）
+}
+
+impl FeatureConfig {
+    pub fn new(sample_rate: u32, frame_length: usize, frame_shift: usize) -> Self {
+        Self {
+            sample_rate,
+            frame_length,
+            frame_shift,
+            mel_filter_num: 26,
+            fmin: 0.0,
+            fmax: None,
+            mel_scale: MelScale::Htk,
+            pre_emphasis: Some(0.97),
+            cepstrum_num: 13,
+            lifter: None,
+            lpc_order: 12,
+        }
+    }
+
+    /// 滤波器组最高频率
+    pub fn fmax(&self) -> f32 {
+        self.fmax.unwrap_or(self.sample_rate as f32 / 2.0)
+    }
+}
+
+/// 按特征类型创建提取器
+pub fn build(kind: FeatureKind, config: FeatureConfig) -> Box<dyn FeatureExtractor> {
+    match kind {
+        FeatureKind::Mfcc => Box::new(MfccExtractor::with_config(config)),
+        FeatureKind::LogMel => Box::new(LogMelExtractor::new(config)),
+        FeatureKind::Plp => Box::new(PlpExtractor::new(config)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::PI;
+
+    const SAMPLE_RATE: u32 = 16000;
+
+    fn config() -> FeatureConfig {
+        FeatureConfig::new(SAMPLE_RATE, 512, 256)
+    }
+
+    fn tone(hz: f32, len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| 0.5 * (2.0 * PI * hz * i as f32 / SAMPLE_RATE as f32).sin())
+            .collect()
+    }
+
+    #[test]
+    fn extractors_share_frame_layout() {
+        let audio = tone(440.0, SAMPLE_RATE as usize);
+        for (kind, dim) in [
+            (FeatureKind::Mfcc, 13),
+            (FeatureKind::LogMel, 26),
+            (FeatureKind::Plp, 13),
+        ] {
+            let extractor = build(kind, config());
+            assert_eq!(extractor.dim(), dim, "{:?}", kind);
+            assert_eq!(extractor.frame_length(), 512);
+
+            let frames = extractor.compute_frames(&audio);
+            assert_eq!(frames.dim(), (1 + (16000 - 512) / 256, dim), "{:?}", kind);
+            assert!(frames.iter().all(|v| v.is_finite()), "{:?}", kind);
+            assert_eq!(extractor.compute(&audio), frames.row(0));
+
+            // 静音也得到有限值
+            let silence = extractor.compute(&[0.0; 512]);
+            assert!(silence.iter().all(|v| v.is_finite()), "{:?}", kind);
+        }
+    }
+
+    #[test]
+    fn features_separate_different_sounds() {
+        for kind in [FeatureKind::Mfcc, FeatureKind::LogMel, FeatureKind::Plp] {
+            let extractor = build(kind, config());
+            let low = extractor.compute(&tone(300.0, 512));
+            let low_again = extractor.compute(&tone(300.0, 512));
+            let high = extractor.compute(&tone(3000.0, 512));
+            let distance = |a: &Array1<f32>, b: &Array1<f32>| (a - b).mapv(|d| d * d).sum();
+            assert_eq!(distance(&low, &low_again), 0.0);
+            assert!(distance(&low, &high) > 1e-3, "{:?}", kind);
+        }
+    }
+
+    #[test]
+    fn kinds_from_config() {
+        #[derive(Deserialize)]
+        struct Kinds {
+            kinds: Vec<FeatureKind>,
+        }
+        let parsed: Kinds = toml::from_str(r#"kinds = ["mfcc", "logmel", "plp"]"#).unwrap();
+        assert_eq!(
+            parsed.kinds,
+            [FeatureKind::Mfcc, FeatureKind::LogMel, FeatureKind::Plp]
+        );
+        assert_eq!(config().fmax(), 8000.0);
+    }
+}
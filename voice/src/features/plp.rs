@@ -0,0 +1,165 @@
+use super::frontend::SpectralFrontEnd;
+use super::mel::MelFilterBank;
+use super::mfcc::create_lifter;
+use super::{FeatureConfig, FeatureExtractor};
+use ndarray::{Array1, Array2};
+use std::f32::consts::PI;
+
+/// 感知线性预测（PLP）倒谱特征
+/// 临界带积分（梅尔滤波器组）→ 等响度预加重 → 强度-响度立方根压缩
+/// → 逆DFT得到自相关 → Levinson-Durbin 求 LPC → LPC 倒谱
+pub struct PlpExtractor {
+    front_end: SpectralFrontEnd,
+    mel_filters: MelFilterBank,
+    equal_loudness: Array1<f32>, // 各滤波器中心频率处的等响度权重
+    idft: Array2<f32>,           // 压缩谱 → 自相关的余弦变换矩阵
+    lpc_order: usize,
+    cepstrum_num: usize,
+    lifter: Option<Array1<f32>>,
+}
+
+impl PlpExtractor {
+    pub fn new(config: FeatureConfig) -> Self {
+        let mel_filters = MelFilterBank::new(&config);
+        let equal_loudness = mel_filters.center_hz().mapv(equal_loudness);
+        let idft = create_idft_matrix(config.mel_filter_num, config.lpc_order);
+
+        Self {
+            front_end: SpectralFrontEnd::new(&config),
+            mel_filters,
+            equal_loudness,
+            idft,
+            lpc_order: config.lpc_order,
+            cepstrum_num: config.cepstrum_num,
+            lifter: config.lifter.map(|l| create_lifter(config.cepstrum_num, l)),
+        }
+    }
+
+    /// 单帧自相关 → LPC 倒谱
+    fn frame_cepstrum(&self, autocorrelation: &[f32]) -> Array1<f32> {
+        let (lpc, gain) = levinson_durbin(autocorrelation, self.lpc_order);
+
+        // LPC 转倒谱（A(z) = 1 + Σ a_k z^-k）
+        let mut cepstrum = Array1::zeros(self.cepstrum_num);
+        cepstrum[0] = gain.max(f32::EPSILON).ln();
+        for n in 1..self.cepstrum_num {
+            let a_n = if n <= self.lpc_order { lpc[n] } else { 0.0 };
+            let sum: f32 = (1..n)
+                .filter(|k| n - k <= self.lpc_order)
+                .map(|k| k as f32 / n as f32 * cepstrum[k] * lpc[n - k])
+                .sum();
+            cepstrum[n] = -a_n - sum;
+        }
+
+        match &self.lifter {
+            Some(lifter) => cepstrum * lifter,
+            None => cepstrum,
+        }
+    }
+}
+
+impl FeatureExtractor for PlpExtractor {
+    fn compute_frames(&self, audio: &[f32]) -> Array2<f32> {
+        let power_spectrum = self.front_end.power_spectrum(audio);
+
+        // 临界带积分 + 等响度加权 + 立方根压缩
+        let bands = self.mel_filters.apply(&power_spectrum) * &self.equal_loudness;
+        let compressed = bands.mapv(|x| x.max(0.0).cbrt());
+
+        let autocorrelation = compressed.dot(&self.idft);
+        let mut features = Array2::zeros((autocorrelation.nrows(), self.cepstrum_num));
+        for (i, row) in autocorrelation.rows().into_iter().enumerate() {
+            features
+                .row_mut(i)
+                .assign(&self.frame_cepstrum(&row.to_vec()));
+        }
+        features
+    }
+
+    fn dim(&self) -> usize {
+        self.cepstrum_num
+    }
+
+    fn frame_length(&self) -> usize {
+        self.front_end.frame_length()
+    }
+}
+
+/// 等响度曲线（Hermansky 1990）
+fn equal_loudness(hz: f32) -> f32 {
+    let w2 = (2.0 * PI * hz).powi(2);
+    (w2 + 56.8e6) * w2 * w2 / ((w2 + 6.3e6).powi(2) * (w2 + 0.38e9))
+}
+
+/// 压缩谱视为对称功率谱（首尾各补一个点），逆DFT得到前 order+1 个自相关系数
+fn create_idft_matrix(band_num: usize, order: usize) -> Array2<f32> {
+    let points = band_num + 1;
+    let mut idft = Array2::zeros((band_num, order + 1));
+    for k in 0..=order {
+        for j in 0..band_num {
+            // 滤波器 j 对应频点 j+1；首尾补点复制相邻滤波器，权重并入端点
+            let freq = (j + 1) as f32;
+            let mut weight = 2.0 * (PI * k as f32 * freq / points as f32).cos();
+            if j == 0 {
+                weight += 1.0;
+            }
+            if j == band_num - 1 {
+                weight += (PI * k as f32).cos();
+            }
+            idft[[j, k]] = weight / (2 * points) as f32;
+        }
+    }
+    idft
+}
+
+/// Levinson-Durbin 递推，返回 LPC 系数（a[0]=1）与预测误差
+fn levinson_durbin(r: &[f32], order: usize) -> (Vec<f32>, f32) {
+    let mut a = vec![0.0; order + 1];
+    a[0] = 1.0;
+    let mut error = r[0];
+    if error <= 0.0 {
+        return (a, 0.0);
+    }
+
+    for i in 1..=order {
+        let acc: f32 = (1..i).map(|j| a[j] * r[i - j]).sum();
+        let k = -(r[i] + acc) / error;
+
+        let previous = a.clone();
+        for j in 1..i {
+            a[j] = previous[j] + k * previous[i - j];
+        }
+        a[i] = k;
+
+        error *= 1.0 - k * k;
+        if error <= 0.0 {
+            break;
+        }
+    }
+    (a, error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levinson_recovers_ar_process() {
+        // AR(1)：x[n] = 0.5 x[n-1] + e[n]，自相关 r[k] = 0.5^k
+        let (lpc, error) = levinson_durbin(&[1.0, 0.5, 0.25, 0.125], 3);
+        let expected = [1.0, -0.5, 0.0, 0.0];
+        for (a, b) in lpc.iter().zip(expected) {
+            assert!((a - b).abs() < 1e-6, "{:?}", lpc);
+        }
+        assert!((error - 0.75).abs() < 1e-6);
+
+        let (lpc, error) = levinson_durbin(&[0.0, 0.0, 0.0], 2);
+        assert_eq!((lpc, error), (vec![1.0, 0.0, 0.0], 0.0));
+    }
+
+    #[test]
+    fn equal_loudness_emphasizes_speech_band() {
+        assert!(equal_loudness(1000.0) > equal_loudness(100.0));
+        assert!(equal_loudness(3000.0) > equal_loudness(200.0));
+    }
+}
@@ -1,35 +1,94 @@
-use anyhow::Ok;
-use cpal::traits::HostTrait;
-use crossbeam_channel::Sender;
+use crossbeam_channel::{bounded, select, Sender};
 use event::wake_event::WakeEvent;
+use privacy::PrivacySwitch;
+use std::thread::{self, JoinHandle};
 
-use audio::stream::AudioStream;
+use audio::AudioInput;
 
-mod audio;
-mod config;
+pub mod array;
+pub mod asr;
+pub mod audio;
+pub mod config;
 pub mod event;
+pub mod features;
+pub mod metrics;
+pub mod power;
+pub mod privacy;
+pub mod recorder;
+pub mod sound;
+pub mod speaker;
 mod utils;
+pub mod vad;
 pub mod wakeword;
 
+/// 语音服务：音频流运行在专用线程中（音频流不能跨线程传递），随隐私模式暂停/恢复
+/// 释放时停止采集并关闭音频流，事件通道随之关闭
 pub struct VoiceServer {
-    pub audio_stream: AudioStream,
+    stop: Sender<()>,
+    thread: Option<JoinHandle<()>>,
+    sample_rate: u32,
 }
 
 impl VoiceServer {
-    pub fn new(audio_sender: Sender<WakeEvent>) -> Result<Self, anyhow::Error> {
-        // 初始化配置
-        // let settings = config::Settings::load();
-
-        // 启动音频采集
-        let stream = audio::stream::AudioStream::new(
-            &cpal::default_host().default_input_device().unwrap(),
-            None,
-            // Some(&settings.audio_config()),
-            audio_sender,
-        )?;
+    pub fn start(
+        audio_sender: Sender<WakeEvent>,
+        privacy: PrivacySwitch,
+    ) -> Result<Self, anyhow::Error> {
+        let settings = config::Settings::load();
 
+        let (ready_sender, ready) = bounded(1);
+        let (stop, stopped) = bounded(1);
+        let thread = thread::Builder::new()
+            .name("audio".to_string())
+            .spawn(move || {
+                let changes = privacy.watch();
+                // 启动音频采集
+                let stream = match AudioInput::open(&settings, audio_sender, privacy.clone()) {
+                    Ok(stream) => stream,
+                    Err(e) => {
+                        let _ = ready_sender.send(Err(e));
+                        return;
+                    }
+                };
+                if !privacy.is_muted() {
+                    stream.start();
+                }
+                let _ = ready_sender.send(Ok(stream.sample_rate()));
+
+                loop {
+                    select! {
+                        recv(changes) -> muted => match muted {
+                            Ok(true) => stream.stop(),
+                            Ok(false) => stream.start(),
+                            Err(_) => break,
+                        },
+                        recv(stopped) -> _ => break,
+                    }
+                }
+                stream.stop();
+            })?;
+
+        let sample_rate = ready
+            .recv()
+            .map_err(|_| anyhow::anyhow!("audio thread exited"))??;
         Ok(Self {
-            audio_stream: stream,
+            stop,
+            thread: Some(thread),
+            sample_rate,
         })
     }
+
+    /// 音频帧的实际采样率，下游的缓冲与时长都应以此为准（而非配置的采样率）
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+}
+
+impl Drop for VoiceServer {
+    fn drop(&mut self) {
+        let _ = self.stop.send(());
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
 }
@@ -0,0 +1,73 @@
+/*
+    运行指标
+    注册到 prometheus 默认注册表，由 core 的 /metrics 接口统一导出
+*/
+
+use prometheus::{
+    linear_buckets, register_histogram, register_int_counter, register_int_counter_vec,
+    register_int_gauge, Histogram, IntCounter, IntCounterVec, IntGauge,
+};
+use std::sync::LazyLock;
+
+/// 音频采集与唤醒检测指标
+pub struct VoiceMetrics {
+    pub audio_callbacks: IntCounter, // 音频回调次数（求速率即回调频率）
+    pub audio_overruns: IntCounter,  // 采集时间戳不连续（丢帧）的次数
+    pub detector_seconds: Histogram, // 每个检测窗口的计算耗时（墙钟时间）
+    pub wakes: IntCounterVec,        // 按唤醒词统计的唤醒次数
+    pub wake_scores: Histogram,      // 检测得分分布
+    pub wake_reloads: IntCounterVec, // 唤醒词模型重新加载次数（按结果）
+    pub low_power: IntGauge,         // 是否处于低功耗监听（1 为是）
+    pub low_power_activations: IntCounter, // 低功耗监听中因声音活动启动完整检测的次数
+}
+
+static METRICS: LazyLock<VoiceMetrics> = LazyLock::new(|| VoiceMetrics {
+    audio_callbacks: register_int_counter!(
+        "voice_audio_callbacks_total",
+        "Number of audio input callbacks"
+    )
+    .unwrap(),
+    audio_overruns: register_int_counter!(
+        "voice_audio_overruns_total",
+        "Number of gaps in captured audio (input overruns)"
+    )
+    .unwrap(),
+    detector_seconds: register_histogram!(
+        "voice_detector_window_seconds",
+        "Wall-clock time spent scoring one wake word window",
+        vec![0.0005, 0.001, 0.002, 0.005, 0.01, 0.02, 0.05, 0.1, 0.2]
+    )
+    .unwrap(),
+    wakes: register_int_counter_vec!(
+        "voice_wake_total",
+        "Number of wake word detections",
+        &["keyword"]
+    )
+    .unwrap(),
+    wake_scores: register_histogram!(
+        "voice_wake_score",
+        "Wake word similarity score per window",
+        linear_buckets(-0.2, 0.1, 13).unwrap()
+    )
+    .unwrap(),
+    wake_reloads: register_int_counter_vec!(
+        "voice_wake_reloads_total",
+        "Number of wake word model reloads",
+        &["result"]
+    )
+    .unwrap(),
+    low_power: register_int_gauge!(
+        "voice_low_power",
+        "Whether duty-cycled low-power listening is active"
+    )
+    .unwrap(),
+    low_power_activations: register_int_counter!(
+        "voice_low_power_activations_total",
+        "Number of times activity woke the full detector in low-power mode"
+    )
+    .unwrap(),
+});
+
+pub fn metrics() -> &'static VoiceMetrics {
+    &METRICS
+}
@@ -0,0 +1,149 @@
+/*
+    功耗模式
+    驻车（点火关闭）时进入低功耗监听：每帧只做能量检测，检测到声音活动后的一段时间内才运行完整的唤醒词检测，
+    其余时间唤醒检测器只缓存音频，活动开始时唤醒词的开头仍在缓存中
+*/
+
+use crate::config::Settings;
+use crate::metrics::metrics;
+use crate::vad::EnergyVad;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+/// 功耗模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PowerMode {
+    Normal,   // 持续运行完整检测
+    LowPower, // 能量门控，检测到活动时才运行完整检测
+}
+
+/// 低功耗监听的占空控制
+pub struct DutyCycle {
+    mode: PowerMode,
+    vad: Option<EnergyVad>, // 活动检测，为空时不启用低功耗模式
+    hold: usize,            // 检测到活动后保持完整检测的采样点数
+    remaining: usize,       // 剩余的完整检测采样点数
+}
+
+impl DutyCycle {
+    /// 保持时长按音频流的实际采样率换算为采样点数
+    pub fn new(settings: &Settings, sample_rate: u32) -> Self {
+        Self {
+            mode: PowerMode::Normal,
+            vad: settings.low_power_threshold.map(EnergyVad::new),
+            hold: (sample_rate as f32 * settings.low_power_hold_secs) as usize,
+            remaining: 0,
+        }
+    }
+
+    pub fn mode(&self) -> PowerMode {
+        self.mode
+    }
+
+    /// 切换模式，返回是否改变（未启用低功耗模式时保持 Normal）
+    pub fn set_mode(&mut self, mode: PowerMode) -> bool {
+        let mode = if self.vad.is_some() {
+            mode
+        } else {
+            PowerMode::Normal
+        };
+        if mode == self.mode {
+            return false;
+        }
+        self.mode = mode;
+        self.remaining = 0;
+        metrics()
+            .low_power
+            .set((mode == PowerMode::LowPower) as i64);
+        true
+    }
+
+    /// 输入一帧音频，返回本帧是否需要运行完整检测
+    pub fn process(&mut self, frame: &[f32]) -> bool {
+        let Some(vad) = self.vad.as_ref() else {
+            return true;
+        };
+        if self.mode == PowerMode::Normal {
+            return true;
+        }
+        if vad.is_speech(frame) {
+            if self.remaining == 0 {
+                debug!("Activity detected, running full wake word detection");
+                metrics().low_power_activations.inc();
+            }
+            self.remaining = self.hold;
+        } else {
+            self.remaining = self.remaining.saturating_sub(frame.len());
+        }
+        self.remaining > 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const QUIET: [f32; 100] = [0.0; 100];
+    const LOUD: [f32; 100] = [0.5; 100];
+
+    fn duty_cycle(threshold: Option<f32>) -> DutyCycle {
+        let settings = Settings {
+            low_power_threshold: threshold,
+            low_power_hold_secs: 0.5,
+            ..Settings::default()
+        };
+        DutyCycle::new(&settings, 1000)
+    }
+
+    #[test]
+    fn normal_mode_always_active() {
+        let mut duty = duty_cycle(Some(0.1));
+        assert_eq!(duty.mode(), PowerMode::Normal);
+        assert!(duty.process(&QUIET));
+        assert!(duty.process(&LOUD));
+    }
+
+    #[test]
+    fn hold_counts_down_in_samples() {
+        let mut duty = duty_cycle(Some(0.1));
+        assert!(duty.set_mode(PowerMode::LowPower));
+        assert!(!duty.set_mode(PowerMode::LowPower));
+        assert!(!duty.process(&QUIET));
+
+        // 1000Hz下保持0.5秒即500个采样点
+        assert!(duty.process(&LOUD));
+        for _ in 0..4 {
+            assert!(duty.process(&QUIET));
+        }
+        assert!(!duty.process(&QUIET));
+
+        // 活动重新开始保持计时
+        assert!(duty.process(&LOUD));
+        assert!(duty.process(&QUIET));
+        assert!(duty.process(&LOUD));
+        for _ in 0..4 {
+            assert!(duty.process(&QUIET));
+        }
+        assert!(!duty.process(&QUIET));
+    }
+
+    #[test]
+    fn switching_mode_resets_hold() {
+        let mut duty = duty_cycle(Some(0.1));
+        duty.set_mode(PowerMode::LowPower);
+        assert!(duty.process(&LOUD));
+        assert!(duty.set_mode(PowerMode::Normal));
+        assert!(duty.process(&QUIET));
+        assert!(duty.set_mode(PowerMode::LowPower));
+        assert!(!duty.process(&QUIET));
+    }
+
+    #[test]
+    fn disabled_without_threshold() {
+        let mut duty = duty_cycle(None);
+        assert!(!duty.set_mode(PowerMode::LowPower));
+        assert_eq!(duty.mode(), PowerMode::Normal);
+        assert!(duty.process(&QUIET));
+    }
+}
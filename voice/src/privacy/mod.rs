@@ -0,0 +1,104 @@
+/*
+    隐私模式
+    开启后麦克风数据在音频回调中直接丢弃，任何消费者都收不到音频帧：
+    1. 回调持有开关的锁完成处理与发送，开启隐私模式返回后不会再有新的音频帧进入事件通道
+    2. 音频线程收到切换通知后暂停/恢复音频流
+    3. 已缓存的音频（唤醒检测、声音分类、录音等）由事件处理在切换时清空
+*/
+
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+
+/// 隐私模式开关（可跨线程共享）
+#[derive(Clone, Default)]
+pub struct PrivacySwitch {
+    inner: Arc<Mutex<Inner>>,
+}
+
+#[derive(Default)]
+struct Inner {
+    muted: bool,
+    watchers: Vec<Sender<bool>>,
+}
+
+impl PrivacySwitch {
+    pub fn is_muted(&self) -> bool {
+        self.inner.lock().unwrap().muted
+    }
+
+    /// 切换隐私模式，返回状态是否改变
+    /// 返回时正在进行的音频回调已经结束，之后的回调都会看到新状态
+    pub fn set(&self, muted: bool) -> bool {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.muted == muted {
+            return false;
+        }
+        inner.muted = muted;
+        inner.watchers.retain(|watcher| watcher.send(muted).is_ok());
+        true
+    }
+
+    /// 订阅状态切换
+    pub fn watch(&self) -> Receiver<bool> {
+        let (sender, receiver) = unbounded();
+        self.inner.lock().unwrap().watchers.push(sender);
+        receiver
+    }
+
+    /// 未开启隐私模式时执行 f，执行期间不能切换状态
+    pub fn admit<T>(&self, f: impl FnOnce() -> T) -> Option<T> {
+        let inner = self.inner.lock().unwrap();
+        (!inner.muted).then(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn notifies_watchers_on_change() {
+        let switch = PrivacySwitch::default();
+        let watcher = switch.watch();
+        let dropped = switch.watch();
+        drop(dropped);
+
+        assert!(!switch.is_muted());
+        assert!(switch.set(true));
+        assert!(!switch.set(true));
+        assert!(switch.clone().set(false));
+        assert_eq!(watcher.try_iter().collect::<Vec<_>>(), [true, false]);
+        assert_eq!(switch.inner.lock().unwrap().watchers.len(), 1);
+    }
+
+    #[test]
+    fn admits_audio_only_when_unmuted() {
+        let switch = PrivacySwitch::default();
+        assert_eq!(switch.admit(|| 1), Some(1));
+        switch.set(true);
+        assert_eq!(switch.admit(|| 1), None);
+    }
+
+    #[test]
+    fn set_waits_for_running_callback() {
+        let switch = PrivacySwitch::default();
+        let (started, ready) = unbounded();
+        let callback = {
+            let switch = switch.clone();
+            thread::spawn(move || {
+                switch.admit(|| {
+                    started.send(()).unwrap();
+                    thread::sleep(Duration::from_millis(100));
+                    switch.inner.try_lock().is_err()
+                })
+            })
+        };
+        ready.recv().unwrap();
+        // 回调结束前无法切换，返回后不会再有音频进入
+        assert!(switch.set(true));
+        assert_eq!(callback.join().unwrap(), Some(true));
+        assert_eq!(switch.admit(|| ()), None);
+    }
+}
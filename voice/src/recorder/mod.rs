@@ -0,0 +1,318 @@
+/*
+    黑匣子录音器
+    用循环缓冲区持续保留最近N秒音频，在唤醒、拒识或出错时
+    将前后音频写入WAV文件，并附带记录得分与配置的JSON文件，
+    输出目录按文件数量滚动删除最旧的记录，用于现场排查误唤醒
+*/
+
+use crate::audio::wav::{write_wav, WavData};
+use crate::config::Settings;
+use crate::utils::circular_buffer::CircularBuffer;
+use anyhow::Context;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// 录音器配置
+#[derive(Debug, Clone, Serialize)]
+pub struct RecorderConfig {
+    pub directory: PathBuf,
+    pub sample_rate: u32,
+    pub pre_roll_secs: f32,  // 触发前保留的音频时长
+    pub post_roll_secs: f32, // 触发后继续录制的时长
+    pub max_files: usize,    // 目录中最多保留的记录条数
+    pub cooldown_secs: f32,  // 两次记录之间的最短间隔
+}
+
+impl RecorderConfig {
+    /// 从语音配置加载（未配置录音目录时返回None），采样率为音频流的实际采样率
+    pub fn load(sample_rate: u32) -> Option<Self> {
+        Settings::load().recorder_config(sample_rate)
+    }
+}
+
+/// 触发录音的原因
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Trigger {
+    Wake,
+    Reject,
+    Error(String),
+}
+
+impl Trigger {
+    fn label(&self) -> &'static str {
+        match self {
+            Trigger::Wake => "wake",
+            Trigger::Reject => "reject",
+            Trigger::Error(_) => "error",
+        }
+    }
+}
+
+/// 触发时刻的检测得分
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CaptureScores {
+    pub wake_score: Option<f32>,
+    pub wake_threshold: f32,
+    pub speaker: Option<String>,
+    pub speaker_score: Option<f32>,
+}
+
+/// JSON附带文件内容
+#[derive(Serialize)]
+struct Sidecar<'a> {
+    trigger: &'a Trigger,
+    timestamp_ms: u128,
+    scores: &'a CaptureScores,
+    pre_roll_samples: usize,
+    post_roll_samples: usize,
+    recorder: &'a RecorderConfig,
+    settings: &'a Settings,
+}
+
+/// 已触发、正在录制后续音频的记录
+struct PendingCapture {
+    trigger: Trigger,
+    scores: CaptureScores,
+    timestamp_ms: u128,
+    pre_roll: usize,
+    audio: Vec<f32>,
+    remaining: usize,
+}
+
+pub struct Recorder {
+    config: RecorderConfig,
+    settings: Settings,
+    buffer: CircularBuffer<f32>,
+    pending: Option<PendingCapture>,
+    last_capture: Option<Instant>,
+}
+
+impl Recorder {
+    pub fn new(config: RecorderConfig) -> Result<Self, anyhow::Error> {
+        fs::create_dir_all(&config.directory).with_context(|| {
+            format!(
+                "failed to create recorder directory: {}",
+                config.directory.display()
+            )
+        })?;
+        let capacity = (config.sample_rate as f32 * config.pre_roll_secs) as usize;
+
+        Ok(Self {
+            buffer: CircularBuffer::new(capacity.max(1)),
+            settings: Settings::load(),
+            pending: None,
+            last_capture: None,
+            config,
+        })
+    }
+
+    /// 写入音频帧；触发后的录制完成时落盘，返回WAV文件路径
+    pub fn push(&mut self, frame: &[f32]) -> Result<Option<PathBuf>, anyhow::Error> {
+        self.buffer.push_slice(frame);
+
+        let Some(pending) = self.pending.as_mut() else {
+            return Ok(None);
+        };
+        let take = pending.remaining.min(frame.len());
+        pending.audio.extend_from_slice(&frame[..take]);
+        pending.remaining -= take;
+
+        if pending.remaining > 0 {
+            return Ok(None);
+        }
+        let pending = self.pending.take().unwrap();
+        self.write(pending).map(Some)
+    }
+
+    /// 触发一次记录：取出缓冲区中的历史音频，继续录制后续音频
+    /// 冷却期内或已有记录在录制时忽略本次触发
+    pub fn trigger(&mut self, trigger: Trigger, scores: CaptureScores) -> bool {
+        let cooling = self
+            .last_capture
+            .is_some_and(|t| t.elapsed().as_secs_f32() < self.config.cooldown_secs);
+        if self.pending.is_some() || cooling {
+            return false;
+        }
+
+        let (first_slice, second_slice) = self.buffer.slices();
+        let audio = [first_slice, second_slice].concat();
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis())
+            .unwrap_or_default();
+
+        self.last_capture = Some(Instant::now());
+        self.pending = Some(PendingCapture {
+            trigger,
+            scores,
+            timestamp_ms,
+            pre_roll: audio.len(),
+            audio,
+            remaining: (self.config.sample_rate as f32 * self.config.post_roll_secs) as usize,
+        });
+        true
+    }
+
+    /// 丢弃所有缓存的音频（包括正在录制的记录）
+    pub fn clear(&mut self) {
+        self.buffer.clear();
+        self.pending = None;
+    }
+
+    fn write(&self, capture: PendingCapture) -> Result<PathBuf, anyhow::Error> {
+        let stem = format!("{}-{}", capture.timestamp_ms, capture.trigger.label());
+        let wav_path = self.config.directory.join(format!("{}.wav", stem));
+        let json_path = self.config.directory.join(format!("{}.json", stem));
+
+        let post_roll = capture.audio.len() - capture.pre_roll;
+        let sidecar = Sidecar {
+            trigger: &capture.trigger,
+            timestamp_ms: capture.timestamp_ms,
+            scores: &capture.scores,
+            pre_roll_samples: capture.pre_roll,
+            post_roll_samples: post_roll,
+            recorder: &self.config,
+            settings: &self.settings,
+        };
+
+        write_wav(
+            &wav_path,
+            &WavData {
+                sample_rate: self.config.sample_rate,
+                channels: 1,
+                samples: capture.audio,
+            },
+        )?;
+        fs::write(&json_path, serde_json::to_vec_pretty(&sidecar)?)
+            .with_context(|| format!("failed to write sidecar: {}", json_path.display()))?;
+
+        self.rotate()?;
+        Ok(wav_path)
+    }
+
+    /// 超出最大记录数时按时间顺序删除最旧的记录
+    /// 只处理录音器自己写出的文件，目录中的其他 WAV 不受影响
+    fn rotate(&self) -> Result<(), anyhow::Error> {
+        let mut captures: Vec<(u128, PathBuf)> = fs::read_dir(&self.config.directory)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter_map(|path| capture_timestamp(&path).map(|ts| (ts, path)))
+            .collect();
+        if captures.len() <= self.config.max_files {
+            return Ok(());
+        }
+
+        captures.sort();
+        for (_, wav) in &captures[..captures.len() - self.config.max_files] {
+            fs::remove_file(wav)?;
+            let _ = fs::remove_file(wav.with_extension("json"));
+        }
+        Ok(())
+    }
+}
+
+/// 录音文件名为 `<毫秒时间戳>-<触发原因>.wav`，匹配时返回时间戳
+fn capture_timestamp(path: &Path) -> Option<u128> {
+    if path.extension()? != "wav" {
+        return None;
+    }
+    let (timestamp, label) = path.file_stem()?.to_str()?.split_once('-')?;
+    if !["wake", "reject", "error"].contains(&label) {
+        return None;
+    }
+    if timestamp.is_empty() || !timestamp.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    timestamp.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::wav::read_wav;
+
+    fn config(name: &str, sample_rate: u32) -> RecorderConfig {
+        let directory =
+            std::env::temp_dir().join(format!("recorder-test-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        RecorderConfig {
+            directory,
+            sample_rate,
+            pre_roll_secs: 0.1,
+            post_roll_secs: 0.05,
+            max_files: 2,
+            cooldown_secs: 0.0,
+        }
+    }
+
+    #[test]
+    fn capture_uses_stream_rate() {
+        let config = config("rate", 44100);
+        let directory = config.directory.clone();
+        let mut recorder = Recorder::new(config).unwrap();
+
+        // 推入0.2秒音频后触发，只保留0.1秒历史
+        for _ in 0..20 {
+            assert!(recorder.push(&[0.1; 441]).unwrap().is_none());
+        }
+        assert!(recorder.trigger(Trigger::Wake, CaptureScores::default()));
+        assert!(!recorder.trigger(Trigger::Reject, CaptureScores::default()));
+
+        let mut written = None;
+        for _ in 0..10 {
+            if let Some(path) = recorder.push(&[0.2; 441]).unwrap() {
+                written = Some(path);
+                break;
+            }
+        }
+        let wav = read_wav(written.expect("capture was not written")).unwrap();
+        assert_eq!(wav.sample_rate, 44100);
+        assert_eq!(wav.samples.len(), 4410 + 2205);
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn rotates_oldest_captures() {
+        let config = config("rotate", 8000);
+        let directory = config.directory.clone();
+        let mut recorder = Recorder::new(config).unwrap();
+
+        for _ in 0..3 {
+            recorder.push(&[0.0; 800]).unwrap();
+            assert!(recorder.trigger(Trigger::Wake, CaptureScores::default()));
+            assert!(recorder.push(&[0.0; 400]).unwrap().is_some());
+            // 文件名精确到毫秒
+            std::thread::sleep(std::time::Duration::from_millis(2));
+        }
+        let files = fs::read_dir(&directory).unwrap().count();
+        assert_eq!(files, 4); // 两条记录，各一个WAV与JSON
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn rotation_keeps_other_files() {
+        let config = config("foreign", 8000);
+        let directory = config.directory.clone();
+        let mut recorder = Recorder::new(config).unwrap();
+
+        // 目录中已有的其他 WAV（包括文件名相近的）不算作记录
+        let foreign = ["0-note.wav", "reference.wav", "12x-wake.wav", "1-wake.txt"];
+        for name in foreign {
+            fs::write(directory.join(name), b"keep").unwrap();
+        }
+        for _ in 0..3 {
+            recorder.push(&[0.0; 800]).unwrap();
+            assert!(recorder.trigger(Trigger::Wake, CaptureScores::default()));
+            assert!(recorder.push(&[0.0; 400]).unwrap().is_some());
+            std::thread::sleep(std::time::Duration::from_millis(2));
+        }
+        for name in foreign {
+            assert!(directory.join(name).exists(), "{} was deleted", name);
+        }
+        let files = fs::read_dir(&directory).unwrap().count();
+        assert_eq!(files, foreign.len() + 4);
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -0,0 +1,293 @@
+use super::{SoundEvent, SoundKind};
+use crate::features::frontend::SpectralFrontEnd;
+use crate::features::FeatureConfig;
+use crate::utils::circular_buffer::CircularBuffer;
+use ndarray::Array1;
+use std::collections::HashMap;
+
+/// 谱峰与带内均值之比超过该值的帧视为纯音帧
+const TONAL_PEAKINESS: f32 = 12.0;
+/// 高于该频率的能量视为高频成分（Hz）
+const HIGH_BAND_HZ: f32 = 4000.0;
+
+/// 单帧频谱特征
+struct FrameFeatures {
+    energy: f32,
+    dominant_hz: f32, // 300~4000Hz 内的主峰频率
+    tonal: bool,
+    high_ratio: f32, // 高频能量占比
+}
+
+/// 声音事件分类器（基于频谱特征的规则打分）
+pub struct SoundClassifier {
+    buffer: CircularBuffer<f32>,
+    front_end: SpectralFrontEnd,
+    sample_rate: u32,
+    hop: usize,      // 两次分析之间的新增采样点数
+    pending: usize,  // 距上次分析累积的采样点数
+    min_energy: f32, // 分析窗口的最小平均能量
+    threshold: f32,  // 置信度阈值
+    cooldown: usize, // 同类事件两次上报之间至少间隔的分析次数
+    last_reported: HashMap<SoundKind, usize>,
+    analyses: usize,
+}
+
+impl SoundClassifier {
+    /// 采样率为音频流的实际采样率，频带按其换算到频谱上
+    pub fn new(sample_rate: u32) -> Self {
+        // 约32ms帧长，50%帧移
+        let frame_length = (sample_rate as usize * 32 / 1000).next_power_of_two();
+
+        Self {
+            buffer: CircularBuffer::new(sample_rate as usize),
+            front_end: SpectralFrontEnd::new(&FeatureConfig {
+                pre_emphasis: None,
+                ..FeatureConfig::new(sample_rate, frame_length, frame_length / 2)
+            }),
+            sample_rate,
+            hop: sample_rate as usize / 2,
+            pending: 0,
+            min_energy: 1e-4,
+            threshold: 0.6,
+            cooldown: 4,
+            last_reported: HashMap::new(),
+            analyses: 0,
+        }
+    }
+
+    /// 输入一段音频帧，每累积半秒对最近一秒音频做一次分析
+    pub fn process(&mut self, frame: &[f32]) -> Vec<SoundEvent> {
+        self.buffer.push_slice(frame);
+        self.pending += frame.len();
+
+        if self.buffer.len() < self.buffer.capacity() || self.pending < self.hop {
+            return Vec::new();
+        }
+        self.pending = 0;
+        self.analyses += 1;
+
+        let (first_slice, second_slice) = self.buffer.slices();
+        let audio = [first_slice, second_slice].concat();
+
+        let events: Vec<SoundEvent> = self
+            .classify(&audio)
+            .into_iter()
+            .filter(|event| event.confidence >= self.threshold)
+            .collect();
+
+        // 冷却期内不重复上报同类事件
+        let analyses = self.analyses;
+        let cooldown = self.cooldown;
+        events
+            .into_iter()
+            .filter(|event| {
+                let last = self.last_reported.get(&event.kind).copied();
+                if last.is_some_and(|last| analyses - last < cooldown) {
+                    return false;
+                }
+                self.last_reported.insert(event.kind, analyses);
+                true
+            })
+            .collect()
+    }
+
+    /// 丢弃缓存的音频
+    pub fn reset(&mut self) {
+        self.buffer.clear();
+        self.pending = 0;
+    }
+
+    /// 对一段音频打分，返回各类事件的置信度
+    pub fn classify(&self, audio: &[f32]) -> Vec<SoundEvent> {
+        let features = self.frame_features(audio);
+        if features.is_empty() {
+            return Vec::new();
+        }
+
+        let energies: Array1<f32> = features.iter().map(|f| f.energy).collect();
+        if energies.mean().unwrap_or(0.0) < self.min_energy {
+            return Vec::new();
+        }
+
+        let tonal: Vec<&FrameFeatures> = features.iter().filter(|f| f.tonal).collect();
+        let tonal_ratio = tonal.len() as f32 / features.len() as f32;
+        let pitches: Array1<f32> = tonal.iter().map(|f| f.dominant_hz).collect();
+        let (pitch_mean, pitch_std) = if pitches.is_empty() {
+            (0.0, 0.0)
+        } else {
+            (pitches.mean().unwrap_or(0.0), pitches.std(0.0))
+        };
+        // 纯音帧与非纯音帧之间的切换次数（间歇蜂鸣）
+        let toggles = features
+            .windows(2)
+            .filter(|pair| pair[0].tonal != pair[1].tonal)
+            .count();
+
+        let mut events = Vec::new();
+
+        // 警笛：持续纯音，500~1800Hz 内明显扫频
+        if (500.0..1800.0).contains(&pitch_mean) {
+            let sweep = (pitch_std / 150.0).min(1.0);
+            events.push(SoundEvent {
+                kind: SoundKind::Siren,
+                confidence: tonal_ratio * sweep,
+            });
+        }
+
+        // 喇叭：持续纯音，300~800Hz，音高稳定
+        if (300.0..800.0).contains(&pitch_mean) {
+            let steadiness = (1.0 - pitch_std / 60.0).max(0.0);
+            events.push(SoundEvent {
+                kind: SoundKind::Horn,
+                confidence: tonal_ratio * steadiness,
+            });
+        }
+
+        // 警报：1000~4000Hz 纯音间歇出现
+        if (1000.0..4000.0).contains(&pitch_mean) {
+            let intermittent = (toggles as f32 / 6.0).min(1.0);
+            let duty = 1.0 - (tonal_ratio - 0.5).abs() * 2.0;
+            events.push(SoundEvent {
+                kind: SoundKind::Alarm,
+                confidence: intermittent * duty.max(0.0),
+            });
+        }
+
+        // 玻璃破碎：能量突增且峰值帧以高频成分为主
+        let (peak_index, peak_energy) = energies.iter().enumerate().fold(
+            (0, 0.0f32),
+            |best, (i, &e)| if e > best.1 { (i, e) } else { best },
+        );
+        let mut sorted = energies.to_vec();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        let median = sorted[sorted.len() / 2].max(f32::EPSILON);
+        let transient = ((peak_energy / median).log10() / 2.0).clamp(0.0, 1.0);
+        events.push(SoundEvent {
+            kind: SoundKind::GlassBreak,
+            confidence: transient * (features[peak_index].high_ratio * 1.5).min(1.0),
+        });
+
+        events
+    }
+
+    /// 逐帧计算能量、主峰频率、纯音性与高频占比
+    fn frame_features(&self, audio: &[f32]) -> Vec<FrameFeatures> {
+        if audio.len() < self.front_end.frame_length() {
+            return Vec::new();
+        }
+
+        let spectrum = self.front_end.power_spectrum(audio);
+        let bins = spectrum.ncols();
+        let hz_per_bin = self.sample_rate as f32 / self.front_end.frame_length() as f32;
+        let to_bin = |hz: f32| ((hz / hz_per_bin) as usize).min(bins - 1);
+        let (band_low, band_high, high_band) =
+            (to_bin(300.0), to_bin(4000.0), to_bin(HIGH_BAND_HZ));
+
+        spectrum
+            .rows()
+            .into_iter()
+            .map(|row| {
+                let energy = row.sum();
+                let band = row.slice(ndarray::s![band_low..=band_high]);
+                let (peak_bin, peak) =
+                    band.iter().enumerate().fold((0, 0.0f32), |best, (i, &p)| {
+                        if p > best.1 {
+                            (i, p)
+                        } else {
+                            best
+                        }
+                    });
+                let band_mean = band.mean().unwrap_or(0.0).max(f32::EPSILON);
+                let high = row.slice(ndarray::s![high_band..]).sum();
+
+                FrameFeatures {
+                    energy,
+                    dominant_hz: (band_low + peak_bin) as f32 * hz_per_bin,
+                    tonal: peak / band_mean > TONAL_PEAKINESS,
+                    high_ratio: if energy > 0.0 { high / energy } else { 0.0 },
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::PI;
+
+    /// 按瞬时频率生成正弦信号
+    fn tone(sample_rate: u32, seconds: f32, frequency: impl Fn(f32) -> f32) -> Vec<f32> {
+        let mut phase = 0.0;
+        (0..(sample_rate as f32 * seconds) as usize)
+            .map(|i| {
+                phase += 2.0 * PI * frequency(i as f32 / sample_rate as f32) / sample_rate as f32;
+                0.5 * phase.sin()
+            })
+            .collect()
+    }
+
+    fn confidence(events: &[SoundEvent], kind: SoundKind) -> f32 {
+        events
+            .iter()
+            .find(|e| e.kind == kind)
+            .map_or(0.0, |e| e.confidence)
+    }
+
+    #[test]
+    fn dominant_frequency_follows_sample_rate() {
+        for sample_rate in [16000, 44100, 48000] {
+            let classifier = SoundClassifier::new(sample_rate);
+            let audio = tone(sample_rate, 0.5, |_| 1000.0);
+            let features = classifier.frame_features(&audio);
+            let hz_per_bin = sample_rate as f32 / classifier.front_end.frame_length() as f32;
+            for frame in &features {
+                assert!(
+                    (frame.dominant_hz - 1000.0).abs() <= hz_per_bin,
+                    "{} Hz: dominant {}",
+                    sample_rate,
+                    frame.dominant_hz
+                );
+                assert!(frame.tonal);
+            }
+        }
+    }
+
+    #[test]
+    fn siren_sweep() {
+        let classifier = SoundClassifier::new(44100);
+        // 600~1400Hz，每秒往返一次
+        let audio = tone(44100, 1.0, |t| 1000.0 + 400.0 * (2.0 * PI * t).sin());
+        let events = classifier.classify(&audio);
+        assert!(confidence(&events, SoundKind::Siren) > 0.6);
+        assert!(confidence(&events, SoundKind::Horn) < 0.6);
+    }
+
+    #[test]
+    fn steady_horn() {
+        let classifier = SoundClassifier::new(44100);
+        let audio = tone(44100, 1.0, |_| 420.0);
+        let events = classifier.classify(&audio);
+        assert!(confidence(&events, SoundKind::Horn) > 0.6);
+        assert!(confidence(&events, SoundKind::Siren) < 0.6);
+    }
+
+    #[test]
+    fn silence_has_no_events() {
+        let classifier = SoundClassifier::new(44100);
+        assert!(classifier.classify(&vec![0.0; 44100]).is_empty());
+    }
+
+    #[test]
+    fn reports_once_per_cooldown() {
+        let mut classifier = SoundClassifier::new(16000);
+        let audio = tone(16000, 6.0, |_| 420.0);
+        let reported: Vec<SoundEvent> = audio
+            .chunks(160)
+            .flat_map(|frame| classifier.process(frame))
+            .filter(|e| e.kind == SoundKind::Horn)
+            .collect();
+        // 首次分析在1秒后，之后每半秒分析一次，每4次分析最多上报一次
+        assert_eq!(reported.len(), 3);
+    }
+}
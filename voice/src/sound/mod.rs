@@ -0,0 +1,39 @@
+/*
+    车内非语音声音事件识别（警笛、喇叭、警报、玻璃破碎）
+    作为音频帧流的第二个消费者，与唤醒词检测并行运行，
+    识别结果可用于降低媒体音量或提醒驾驶员
+*/
+
+pub mod classifier;
+
+use serde::Serialize;
+use std::fmt;
+
+/// 声音事件类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SoundKind {
+    Siren,      // 紧急车辆警笛（音调周期性扫频）
+    Horn,       // 汽车喇叭（稳定的低频谐波音）
+    Alarm,      // 警报蜂鸣（间歇的高频纯音）
+    GlassBreak, // 玻璃破碎（宽带高频瞬态）
+}
+
+impl fmt::Display for SoundKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            SoundKind::Siren => "siren",
+            SoundKind::Horn => "horn",
+            SoundKind::Alarm => "alarm",
+            SoundKind::GlassBreak => "glass_break",
+        };
+        f.write_str(name)
+    }
+}
+
+/// 识别出的声音事件
+#[derive(Debug, Clone)]
+pub struct SoundEvent {
+    pub kind: SoundKind,
+    pub confidence: f32, // 0~1
+}
@@ -0,0 +1,52 @@
+use crate::features::mfcc::MfccExtractor;
+use crate::features::FeatureExtractor;
+use anyhow::anyhow;
+use ndarray::{concatenate, Array1, Axis};
+
+/// 说话人特征提取器
+/// 对语音逐帧计算MFCC，挑选能量较高的语音帧，
+/// 取倒谱系数的均值（一阶统计量）与标准差（二阶统计量）拼接后归一化
+pub struct SpeakerEmbedder {
+    mfcc_extractor: MfccExtractor,
+}
+
+impl SpeakerEmbedder {
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            mfcc_extractor: MfccExtractor::new(sample_rate, 512, 256, 26, 20),
+        }
+    }
+
+    /// 计算一段语音的说话人特征向量（L2归一化）
+    pub fn embed(&self, utterance: &[f32]) -> Result<Array1<f32>, anyhow::Error> {
+        if utterance.len() < self.mfcc_extractor.frame_length() * 2 {
+            return Err(anyhow!(
+                "utterance too short for speaker embedding: {} samples",
+                utterance.len()
+            ));
+        }
+
+        let frames = self.mfcc_extractor.compute_frames(utterance);
+
+        // 以第0维倒谱（对数能量）的中位数为界，丢弃静音帧
+        let mut energies: Vec<f32> = frames.column(0).to_vec();
+        energies.sort_by(|a, b| a.total_cmp(b));
+        let median = energies[energies.len() / 2];
+        let voiced: Vec<usize> = (0..frames.nrows())
+            .filter(|&i| frames[[i, 0]] >= median)
+            .collect();
+        let voiced = frames.select(Axis(0), &voiced);
+
+        let mean = voiced
+            .mean_axis(Axis(0))
+            .ok_or_else(|| anyhow!("no voiced frames in utterance"))?;
+        let std = voiced.std_axis(Axis(0), 0.0);
+
+        let embedding = concatenate![Axis(0), mean, std];
+        let norm = embedding.dot(&embedding).sqrt();
+        if norm == 0.0 {
+            return Err(anyhow!("utterance has no energy"));
+        }
+        Ok(embedding / norm)
+    }
+}
@@ -0,0 +1,37 @@
+/*
+    声纹识别（说话人验证）
+    只有已注册的驾驶员才能触发车控指令：
+    1. embedding：基于逐帧MFCC的一阶/二阶统计量（i-vector 风格）计算说话人特征向量
+    2. verifier：注册用户、对采集到的语音与已注册档案打分
+    3. policy：限制敏感指令只能由已验证的说话人触发
+*/
+
+pub mod embedding;
+pub mod policy;
+pub mod verifier;
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// 已注册说话人的唯一标识
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SpeakerId(pub String);
+
+impl fmt::Display for SpeakerId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<&str> for SpeakerId {
+    fn from(id: &str) -> Self {
+        Self(id.to_string())
+    }
+}
+
+/// 一次验证通过的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Verification {
+    pub speaker: SpeakerId,
+    pub score: f32, // 与档案的余弦相似度
+}
@@ -0,0 +1,51 @@
+use super::Verification;
+use std::collections::HashSet;
+
+/// 指令授权策略
+/// 被标记为敏感的指令只能由已验证的说话人触发，其余指令对所有人开放
+#[derive(Debug, Clone, Default)]
+pub struct SpeakerPolicy {
+    restricted: HashSet<String>,
+}
+
+impl SpeakerPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 将指令标记为敏感指令
+    pub fn restrict(mut self, command: impl Into<String>) -> Self {
+        self.restricted.insert(command.into());
+        self
+    }
+
+    pub fn is_restricted(&self, command: &str) -> bool {
+        self.restricted.contains(command)
+    }
+
+    /// 判断说话人是否有权执行该指令
+    pub fn authorize(&self, command: &str, speaker: Option<&Verification>) -> bool {
+        !self.is_restricted(command) || speaker.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::speaker::SpeakerId;
+
+    #[test]
+    fn restricted_commands_need_a_verified_speaker() {
+        let policy = SpeakerPolicy::new().restrict("window").restrict("unlock");
+        let driver = Verification {
+            speaker: SpeakerId::from("alice"),
+            score: 0.9,
+        };
+
+        assert!(policy.is_restricted("unlock"));
+        assert!(!policy.authorize("window", None));
+        assert!(policy.authorize("window", Some(&driver)));
+        assert!(policy.authorize("media", None));
+        assert!(SpeakerPolicy::new().authorize("window", None));
+    }
+}
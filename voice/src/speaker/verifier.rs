@@ -0,0 +1,172 @@
+use super::embedding::SpeakerEmbedder;
+use super::{SpeakerId, Verification};
+use crate::config::Settings;
+use crate::utils::similarity::cosine_similarity;
+use anyhow::anyhow;
+use ndarray::Array1;
+
+/// 已注册说话人的声纹档案
+#[derive(Debug, Clone)]
+pub struct SpeakerProfile {
+    pub id: SpeakerId,
+    pub centroid: Array1<f32>, // 所有注册语音特征向量的均值
+    pub enrollments: usize,    // 注册语音条数
+}
+
+impl SpeakerProfile {
+    pub fn new(id: SpeakerId, centroid: Vec<f32>, enrollments: usize) -> Self {
+        Self {
+            id,
+            centroid: Array1::from_vec(centroid),
+            enrollments,
+        }
+    }
+}
+
+/// 说话人验证器
+pub struct SpeakerVerifier {
+    embedder: SpeakerEmbedder,
+    profiles: Vec<SpeakerProfile>,
+    threshold: f32,
+}
+
+impl SpeakerVerifier {
+    /// sample_rate 为送入语音的实际采样率（音频流的采样率）
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            embedder: SpeakerEmbedder::new(sample_rate),
+            profiles: Vec::new(),
+            threshold: Settings::load().speaker_threshold,
+        }
+    }
+
+    /// 注册一条语音，多次注册同一用户时累计求均值
+    pub fn enroll(&mut self, id: SpeakerId, utterance: &[f32]) -> Result<(), anyhow::Error> {
+        let embedding = self.embedder.embed(utterance)?;
+
+        match self.profiles.iter_mut().find(|p| p.id == id) {
+            Some(profile) => {
+                let n = profile.enrollments as f32;
+                profile.centroid = (&profile.centroid * n + &embedding) / (n + 1.0);
+                profile.enrollments += 1;
+            }
+            None => self.profiles.push(SpeakerProfile {
+                id,
+                centroid: embedding,
+                enrollments: 1,
+            }),
+        }
+        Ok(())
+    }
+
+    /// 导入已有的声纹档案
+    pub fn insert_profile(&mut self, profile: SpeakerProfile) {
+        self.profiles.retain(|p| p.id != profile.id);
+        self.profiles.push(profile);
+    }
+
+    /// 删除已注册用户
+    pub fn remove(&mut self, id: &SpeakerId) -> bool {
+        let before = self.profiles.len();
+        self.profiles.retain(|p| &p.id != id);
+        self.profiles.len() != before
+    }
+
+    pub fn profiles(&self) -> &[SpeakerProfile] {
+        &self.profiles
+    }
+
+    /// 对语音与所有档案打分（按得分降序）
+    pub fn score(&self, utterance: &[f32]) -> Result<Vec<(SpeakerId, f32)>, anyhow::Error> {
+        if self.profiles.is_empty() {
+            return Err(anyhow!("no enrolled speakers"));
+        }
+        let embedding = self.embedder.embed(utterance)?;
+
+        let mut scores: Vec<(SpeakerId, f32)> = self
+            .profiles
+            .iter()
+            .map(|p| (p.id.clone(), cosine_similarity(&p.centroid, &embedding)))
+            .collect();
+        scores.sort_by(|a, b| b.1.total_cmp(&a.1));
+        Ok(scores)
+    }
+
+    /// 验证说话人：得分最高且超过阈值的档案即为验证结果
+    pub fn verify(&self, utterance: &[f32]) -> Option<Verification> {
+        let scores = self.score(utterance).ok()?;
+        scores
+            .into_iter()
+            .next()
+            .filter(|(_, score)| *score >= self.threshold)
+            .map(|(speaker, score)| Verification { speaker, score })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::PI;
+
+    const SAMPLE_RATE: u32 = 16000;
+
+    /// 模拟说话人：基频与共振峰不同的谐波，seed 改变相位与噪声
+    fn voice(pitch: f32, formant: f32, seed: u32) -> Vec<f32> {
+        let mut noise = seed.wrapping_mul(2654435761).max(1);
+        (0..SAMPLE_RATE as usize)
+            .map(|i| {
+                let t = i as f32 / SAMPLE_RATE as f32;
+                let harmonics: f32 = (1..20)
+                    .map(|k| {
+                        let frequency = pitch * k as f32;
+                        let gain = (-((frequency - formant) / 400.0).powi(2)).exp();
+                        gain * (2.0 * PI * frequency * t + seed as f32 * k as f32).sin()
+                    })
+                    .sum();
+                noise ^= noise << 13;
+                noise ^= noise >> 17;
+                noise ^= noise << 5;
+                0.3 * harmonics + 0.01 * (noise as f32 / u32::MAX as f32 - 0.5)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn verifies_enrolled_speakers_only() {
+        let mut verifier = SpeakerVerifier::new(SAMPLE_RATE);
+        assert!(verifier.verify(&voice(120.0, 700.0, 1)).is_none());
+
+        verifier
+            .enroll(SpeakerId::from("anna"), &voice(120.0, 700.0, 1))
+            .unwrap();
+        verifier
+            .enroll(SpeakerId::from("anna"), &voice(120.0, 700.0, 2))
+            .unwrap();
+        assert_eq!(verifier.profiles()[0].enrollments, 2);
+
+        let verification = verifier.verify(&voice(120.0, 700.0, 3)).unwrap();
+        assert_eq!(verification.speaker, SpeakerId::from("anna"));
+        assert!(verification.score >= 0.85, "{}", verification.score);
+
+        // 未注册的说话人不通过验证
+        let stranger = voice(210.0, 2200.0, 4);
+        let score = verifier.score(&stranger).unwrap()[0].1;
+        assert!(score < 0.85, "{}", score);
+        assert!(verifier.verify(&stranger).is_none());
+
+        assert!(verifier.remove(&SpeakerId::from("anna")));
+        assert!(verifier.score(&stranger).is_err());
+    }
+
+    #[test]
+    fn rejects_short_utterances() {
+        let mut verifier = SpeakerVerifier::new(SAMPLE_RATE);
+        assert!(verifier
+            .enroll(SpeakerId::from("anna"), &[0.1; 600])
+            .is_err());
+        assert!(verifier
+            .enroll(SpeakerId::from("anna"), &[0.0; 4000])
+            .is_err());
+        assert!(verifier.profiles().is_empty());
+    }
+}
@@ -7,7 +7,7 @@
     2. 读取数据（可能读取最近N个样本）
     3. 清空缓冲区
     4. 获取当前缓冲区的数据长度（len）
-    5. 获取缓冲区的容量（capacity）或者是否已满（is_full）
+    5. 获取缓冲区的容量（capacity）
 */
 
 use std::{
@@ -21,7 +21,6 @@ pub(crate) struct CircularBuffer<T> {
     capacity: usize,
     read_index: AtomicUsize,  // 原子读指针（消费者维护）
     write_index: AtomicUsize, // 原子写指针（生产者维护）
-    is_full: bool,
 }
 
 // 明确标记为线程安全（确保单生产者单消费者模型）
@@ -41,7 +40,6 @@ where
             capacity,
             read_index: AtomicUsize::new(0),
             write_index: AtomicUsize::new(0),
-            is_full: false,
         }
     }
 
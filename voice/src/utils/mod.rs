@@ -1,2 +1,2 @@
 pub(crate) mod circular_buffer;
-pub(crate) mod mfcc;
+pub(crate) mod similarity;
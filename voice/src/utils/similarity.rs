@@ -0,0 +1,10 @@
+use ndarray::Array1;
+
+// 余弦相似度计算
+pub(crate) fn cosine_similarity(a: &Array1<f32>, b: &Array1<f32>) -> f32 {
+    let dot_product = a.dot(b);
+    let norm_a = a.dot(a).sqrt();
+    let norm_b = b.dot(b).sqrt();
+
+    dot_product / (norm_a * norm_b)
+}
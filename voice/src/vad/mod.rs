@@ -0,0 +1,165 @@
+/*
+    基于能量的语音活动检测（VAD）与端点检测
+    唤醒后用于判断用户何时开始说话、何时说完
+*/
+
+/// 能量检测器：帧均方根超过阈值即视为语音
+#[derive(Debug, Clone)]
+pub struct EnergyVad {
+    threshold: f32, // 均方根阈值
+}
+
+impl EnergyVad {
+    pub fn new(threshold: f32) -> Self {
+        Self { threshold }
+    }
+
+    /// 帧均方根能量
+    pub fn rms(frame: &[f32]) -> f32 {
+        if frame.is_empty() {
+            return 0.0;
+        }
+        (frame.iter().map(|x| x * x).sum::<f32>() / frame.len() as f32).sqrt()
+    }
+
+    pub fn is_speech(&self, frame: &[f32]) -> bool {
+        Self::rms(frame) > self.threshold
+    }
+}
+
+/// 端点检测状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endpoint {
+    Waiting,  // 尚未检测到语音
+    Speaking, // 语音进行中
+    Ended,    // 语音结束（尾部静音足够长或达到最大时长）
+    NoSpeech, // 等待超时仍未检测到语音
+}
+
+/// 端点检测器（按采样点计时）
+#[derive(Debug, Clone)]
+pub struct Endpointer {
+    vad: EnergyVad,
+    sample_rate: u32,
+    leading_timeout: usize,  // 开始说话前的最长等待（采样点）
+    trailing_silence: usize, // 判定说完所需的尾部静音（采样点）
+    max_length: usize,       // 单条语音最大长度（采样点）
+    elapsed: usize,
+    speech: usize,
+    silence: usize,
+    state: Endpoint,
+}
+
+impl Endpointer {
+    pub fn new(
+        vad: EnergyVad,
+        sample_rate: u32,
+        leading_timeout_ms: u32,
+        trailing_silence_ms: u32,
+        max_length_ms: u32,
+    ) -> Self {
+        let samples = |ms: u32| (sample_rate as u64 * ms as u64 / 1000) as usize;
+        Self {
+            vad,
+            sample_rate,
+            leading_timeout: samples(leading_timeout_ms),
+            trailing_silence: samples(trailing_silence_ms),
+            max_length: samples(max_length_ms),
+            elapsed: 0,
+            speech: 0,
+            silence: 0,
+            state: Endpoint::Waiting,
+        }
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// 重新开始一条语音的端点检测
+    pub fn reset(&mut self) {
+        self.elapsed = 0;
+        self.speech = 0;
+        self.silence = 0;
+        self.state = Endpoint::Waiting;
+    }
+
+    /// 输入一帧音频，返回当前端点状态
+    pub fn push(&mut self, frame: &[f32]) -> Endpoint {
+        if matches!(self.state, Endpoint::Ended | Endpoint::NoSpeech) {
+            return self.state;
+        }
+        self.elapsed += frame.len();
+
+        if self.vad.is_speech(frame) {
+            self.speech += frame.len();
+            self.silence = 0;
+            self.state = Endpoint::Speaking;
+        } else if self.state == Endpoint::Speaking {
+            self.silence += frame.len();
+        }
+
+        self.state = match self.state {
+            Endpoint::Waiting if self.elapsed >= self.leading_timeout => Endpoint::NoSpeech,
+            Endpoint::Speaking
+                if self.silence >= self.trailing_silence || self.elapsed >= self.max_length =>
+            {
+                Endpoint::Ended
+            }
+            state => state,
+        };
+        self.state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRAME: usize = 160; // 16kHz 下 10 毫秒
+
+    fn endpointer() -> Endpointer {
+        // 等待 100 毫秒、尾部静音 50 毫秒、最长 300 毫秒
+        Endpointer::new(EnergyVad::new(0.1), 16000, 100, 50, 300)
+    }
+
+    fn push(endpointer: &mut Endpointer, level: f32, frames: usize) -> Endpoint {
+        let frame = [level; FRAME];
+        (0..frames).fold(Endpoint::Waiting, |_, _| endpointer.push(&frame))
+    }
+
+    #[test]
+    fn energy_threshold() {
+        assert_eq!(EnergyVad::rms(&[]), 0.0);
+        assert!((EnergyVad::rms(&[0.3, -0.3]) - 0.3).abs() < 1e-6);
+        let vad = EnergyVad::new(0.1);
+        assert!(vad.is_speech(&[0.2; 4]));
+        assert!(!vad.is_speech(&[0.1; 4]));
+    }
+
+    #[test]
+    fn ends_after_trailing_silence() {
+        let mut endpointer = endpointer();
+        assert_eq!(push(&mut endpointer, 0.0, 9), Endpoint::Waiting);
+        assert_eq!(push(&mut endpointer, 0.5, 5), Endpoint::Speaking);
+        // 短暂停顿不算说完
+        assert_eq!(push(&mut endpointer, 0.0, 4), Endpoint::Speaking);
+        assert_eq!(push(&mut endpointer, 0.5, 1), Endpoint::Speaking);
+        assert_eq!(push(&mut endpointer, 0.0, 5), Endpoint::Ended);
+        // 结束后保持状态，直到重置
+        assert_eq!(push(&mut endpointer, 0.5, 1), Endpoint::Ended);
+        endpointer.reset();
+        assert_eq!(push(&mut endpointer, 0.5, 1), Endpoint::Speaking);
+    }
+
+    #[test]
+    fn times_out_and_caps_length() {
+        let mut endpointer = endpointer();
+        assert_eq!(push(&mut endpointer, 0.0, 10), Endpoint::NoSpeech);
+
+        endpointer.reset();
+        assert_eq!(push(&mut endpointer, 0.5, 29), Endpoint::Speaking);
+        assert_eq!(push(&mut endpointer, 0.5, 1), Endpoint::Ended);
+        assert_eq!(endpointer.sample_rate(), 16000);
+    }
+}
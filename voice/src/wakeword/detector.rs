@@ -1,81 +1,206 @@
-use std::fs::File;
-use std::io::{Cursor, Read};
-
+use super::model::{self, WakeModel};
 use crate::config::Settings;
+use crate::features::{self, FeatureExtractor};
+use crate::metrics::metrics;
 use crate::utils::circular_buffer::CircularBuffer;
-use crate::utils::mfcc::MfccExtractor;
-use anyhow::{Context, Result};
-use byteorder::{LittleEndian, ReadBytesExt};
-use ndarray::Array1;
+use crate::utils::similarity::cosine_similarity;
+use anyhow::Result;
+use crossbeam_channel::Receiver;
+use std::time::Instant;
+use tracing::{debug, info, trace};
 
 pub struct WakeDetector {
     buffer: CircularBuffer<f32>,
-    threshold: f32,
-    mfcc_weight: Array1<f32>, // 预训练的唤醒词MFCC模版
-    mfcc_extractor: MfccExtractor,
+    model: WakeModel,
+    reloads: Option<Receiver<WakeModel>>, // 重新加载的模型，为空时不监视模型文件
+    threshold: Option<f32>,               // 档案中的唤醒阈值，为空时使用模型的阈值
+    extractor: Box<dyn FeatureExtractor>, // 与模版训练时一致的特征提取器
+    last_window: Vec<f32>,                // 最近一次触发唤醒的音频窗口（供声纹验证使用）
+    last_score: Option<f32>,              // 本次输入的检测得分（未进行检测时为空）
 }
 
 impl WakeDetector {
-    pub fn new() -> Self {
+    /// 按配置加载唤醒词模型，模版无效时报错
+    /// sample_rate 为送入音频的实际采样率（音频流的采样率）
+    pub fn new(sample_rate: u32) -> Result<Self> {
         let settings = Settings::load();
-        let mfcc_extractor = MfccExtractor::new(settings.sample_rate, 512, 256, 26, 13);
-        let mfcc_weight = load_mfcc_template(&settings.wakeword_path).unwrap();
+        let model = WakeModel::load(&settings, sample_rate)?;
+        let reloads = model::watch(settings.clone(), sample_rate, &model);
+        Ok(Self::with_model(&settings, sample_rate, model, reloads))
+    }
+
+    fn with_model(
+        settings: &Settings,
+        sample_rate: u32,
+        model: WakeModel,
+        reloads: Option<Receiver<WakeModel>>,
+    ) -> Self {
         Self {
-            buffer: CircularBuffer::new(settings.sample_rate as usize * 2),
-            threshold: settings.wake_threshold,
-            mfcc_weight,
-            mfcc_extractor,
+            buffer: CircularBuffer::new(sample_rate as usize * 2),
+            model,
+            reloads,
+            threshold: None,
+            extractor: features::build(settings.feature_kind, settings.feature_config(sample_rate)),
+            last_window: Vec::new(),
+            last_score: None,
         }
     }
 
-    pub fn process(&mut self, frame: &[f32]) -> bool {
+    /// 只缓存音频，不做检测（低功耗监听时）
+    pub fn push(&mut self, frame: &[f32]) {
+        self.reload();
         self.buffer.push_slice(frame);
+        self.last_score = None;
+    }
+
+    /// 换用后台重新加载的模型（在两帧之间替换，缓存的音频保留）
+    fn reload(&mut self) {
+        let Some(model) = self.reloads.as_ref().and_then(|r| r.try_recv().ok()) else {
+            return;
+        };
+        info!(keyword = %model.keyword, "Wake word model swapped");
+        self.model = model;
+    }
+
+    pub fn process(&mut self, frame: &[f32]) -> bool {
+        self.push(frame);
 
-        // 每500ms进行一次检测
+        // 缓存满 2 秒后每帧检测一次
         if self.buffer.len() >= self.buffer.capacity() {
             let (first_slice, second_slice) = self.buffer.slices();
             let audio = [first_slice, second_slice].concat();
-            let mfcc = self.mfcc_extractor.compute(&audio); // 计算当前音频MFCC特征
-            println!("current mfcc: {:?}", mfcc);
-            let similarity = cosine_similarity(&self.mfcc_weight, &mfcc);
-            println!("similariity indicator: {}", similarity);
-            if similarity > self.threshold {
+            let started = Instant::now();
+            let mfcc = self.extractor.compute(&audio); // 计算当前音频MFCC特征
+            trace!(?mfcc, "wake word features");
+            let similarity = cosine_similarity(&self.model.template, &mfcc);
+            metrics()
+                .detector_seconds
+                .observe(started.elapsed().as_secs_f64());
+            if similarity.is_finite() {
+                // 静音窗口的得分为 NaN，不计入分布
+                metrics().wake_scores.observe(similarity as f64);
+            }
+            let threshold = self.threshold();
+            debug!(similarity, threshold, "wake word score");
+            self.last_score = Some(similarity);
+            if similarity > threshold {
                 self.buffer.clear(); // 清空缓存避免重复触发
+                self.last_window = audio;
+                metrics()
+                    .wakes
+                    .with_label_values(&[&self.model.keyword])
+                    .inc();
                 return true;
             }
         }
         false
     }
-}
 
-// 余弦相似度计算
-fn cosine_similarity(a: &Array1<f32>, b: &Array1<f32>) -> f32 {
-    println!("factor a: {:?}, factor b: {:?}", a, b);
-    let dot_product = a.dot(b);
-    let norm_a = a.dot(a).sqrt();
-    let norm_b = b.dot(b).sqrt();
+    /// 本次输入的检测得分
+    pub fn last_score(&self) -> Option<f32> {
+        self.last_score
+    }
+
+    pub fn threshold(&self) -> f32 {
+        self.threshold.unwrap_or(self.model.threshold)
+    }
+
+    /// 调整唤醒阈值（用户档案中的灵敏度），为空时恢复模型的阈值
+    pub fn set_threshold(&mut self, threshold: Option<f32>) {
+        self.threshold = threshold;
+    }
+
+    /// 本次检测得分接近阈值但未唤醒（拒识）
+    pub fn is_rejected(&self) -> bool {
+        self.last_score
+            .is_some_and(|score| score > self.model.reject_threshold && score <= self.threshold())
+    }
+
+    /// 最近一次触发唤醒的音频窗口
+    pub fn last_window(&self) -> &[f32] {
+        &self.last_window
+    }
 
-    dot_product / (norm_a * norm_b)
+    /// 丢弃缓存的音频（包括最近一次唤醒的窗口）
+    pub fn reset(&mut self) {
+        self.buffer.clear();
+        self.last_window = Vec::new();
+        self.last_score = None;
+    }
 }
 
-/// 从二进制文件加载预训练的MFCC模版
-fn load_mfcc_template(path: &str) -> Result<Array1<f32>, anyhow::Error> {
-    let mut file =
-        File::open(path).with_context(|| format!("failed to open MFCC template file: {}", path))?;
-
-    let mut buffer = Vec::new();
-    file.read_to_end(&mut buffer)
-        .with_context(|| "failed to read binary file")?;
-
-    // 每4字节转换为一个f32（控制为小端序）
-    let mfcc_data: Vec<f32> = buffer
-        .chunks_exact(4)
-        .map(|chunk| {
-            let mut reader = Cursor::new(chunk);
-            reader
-                .read_f32::<LittleEndian>()
-                .with_context(|| "failed to parse")
-        })
-        .collect::<Result<_>>()?;
-    Ok(Array1::from_vec(mfcc_data))
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossbeam_channel::bounded;
+
+    const SAMPLE_RATE: u32 = 16000;
+
+    /// 以给定音频的特征为模版的模型（同一段音频得分为 1）
+    fn model(audio: &[f32], threshold: f32, reject_threshold: f32) -> WakeModel {
+        let settings = Settings::default();
+        let extractor =
+            features::build(settings.feature_kind, settings.feature_config(SAMPLE_RATE));
+        WakeModel {
+            template: extractor.compute(audio),
+            threshold,
+            reject_threshold,
+            path: "test.bin".into(),
+            keyword: "test".into(),
+        }
+    }
+
+    fn tone() -> Vec<f32> {
+        (0..SAMPLE_RATE as usize * 2)
+            .map(|i| (i as f32 * 0.05).sin() * 0.5 + (i as f32 * 0.31).sin() * 0.2)
+            .collect()
+    }
+
+    #[test]
+    fn swaps_reloaded_model_between_frames() {
+        let audio = tone();
+        let (reloads, receiver) = bounded(1);
+        let mut detector = WakeDetector::with_model(
+            &Settings::default(),
+            SAMPLE_RATE,
+            model(&audio, 2.0, 0.5),
+            Some(receiver),
+        );
+
+        // 阈值高于最高得分：拒识而不唤醒
+        assert!(!detector.process(&audio));
+        let score = detector.last_score().unwrap();
+        assert!((score - 1.0).abs() < 1e-3, "score {}", score);
+        assert!(detector.is_rejected());
+
+        // 新模型在下一帧检测前换上，缓存的音频保留
+        let (cached, frame) = audio.split_at(audio.len() - SAMPLE_RATE as usize / 100);
+        detector.reset();
+        detector.push(cached);
+        assert_eq!(detector.threshold(), 2.0);
+        reloads.send(model(&audio, 0.9, 0.5)).unwrap();
+        assert!(detector.process(frame));
+        assert_eq!(detector.threshold(), 0.9);
+        assert_eq!(detector.last_window(), audio.as_slice());
+    }
+
+    #[test]
+    fn profile_threshold_overrides_model() {
+        let audio = tone();
+        let mut detector = WakeDetector::with_model(
+            &Settings::default(),
+            SAMPLE_RATE,
+            model(&audio, 2.0, 2.0),
+            None,
+        );
+        detector.set_threshold(Some(0.9));
+        assert!(detector.process(&audio));
+        assert!(!detector.is_rejected());
+
+        // 恢复模型的阈值，拒识阈值以上也不唤醒
+        detector.set_threshold(None);
+        assert!(!detector.process(&audio));
+        assert!(!detector.is_rejected());
+        assert_eq!(detector.threshold(), 2.0);
+    }
 }
@@ -1 +1,2 @@
 pub mod detector;
+pub mod model;
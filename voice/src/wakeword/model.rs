@@ -0,0 +1,256 @@
+use crate::config::Settings;
+use crate::features;
+use crate::metrics::metrics;
+use anyhow::{anyhow, Context, Result};
+use byteorder::{LittleEndian, ReadBytesExt};
+use crossbeam_channel::{bounded, Receiver, Sender};
+use ndarray::Array1;
+use serde::Deserialize;
+use std::fs::{self, File};
+use std::io::{Cursor, Read};
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, SystemTime};
+use tracing::{error, info};
+
+/// 唤醒词模型：模版与阈值
+pub struct WakeModel {
+    pub template: Array1<f32>, // 预训练的唤醒词MFCC模版
+    pub threshold: f32,
+    pub reject_threshold: f32, // 得分高于该值但未唤醒时视为拒识
+    pub path: String,          // 模版文件路径
+    pub keyword: String,       // 唤醒词名称（取模版文件名，用于指标统计）
+}
+
+/// 唤醒词配置文件（JSON），未指定的项使用默认配置
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ModelConfig {
+    template: Option<String>,
+    threshold: Option<f32>,
+    reject_threshold: Option<f32>,
+}
+
+impl WakeModel {
+    /// 按配置加载模型，并检查模版与该采样率下特征提取器的输出一致
+    pub fn load(settings: &Settings, sample_rate: u32) -> Result<Self> {
+        let config = match &settings.wakeword_config {
+            Some(path) => {
+                let text = fs::read_to_string(path)
+                    .with_context(|| format!("failed to read wake word config: {}", path))?;
+                serde_json::from_str(&text)
+                    .with_context(|| format!("invalid wake word config: {}", path))?
+            }
+            None => ModelConfig::default(),
+        };
+        let path = config
+            .template
+            .unwrap_or_else(|| settings.wakeword_path.clone());
+        let template = load_mfcc_template(&path)?;
+
+        // 模版长度与检测窗口的特征长度不一致时无法比较
+        let extractor =
+            features::build(settings.feature_kind, settings.feature_config(sample_rate));
+        let expected = extractor
+            .compute(&vec![0.0; sample_rate as usize * 2])
+            .len();
+        if template.len() != expected {
+            return Err(anyhow!(
+                "MFCC template {} has {} values, expected {}",
+                path,
+                template.len(),
+                expected
+            ));
+        }
+
+        let keyword = Path::new(&path)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        Ok(Self {
+            template,
+            threshold: config.threshold.unwrap_or(settings.wake_threshold),
+            reject_threshold: config.reject_threshold.unwrap_or(settings.reject_threshold),
+            path,
+            keyword,
+        })
+    }
+}
+
+/// 监视配置文件与模版文件，变化时在后台重新加载模型
+/// 加载失败时保留当前模型；检测器释放（接收端关闭）后线程在下一次加载完成时退出
+pub fn watch(
+    settings: Settings,
+    sample_rate: u32,
+    current: &WakeModel,
+) -> Option<Receiver<WakeModel>> {
+    let interval = Duration::from_secs_f32(settings.wakeword_reload_secs?);
+    let (sender, receiver) = bounded(1);
+    let path = current.path.clone();
+    let result = thread::Builder::new()
+        .name("wakeword-reload".to_string())
+        .spawn(move || watch_files(settings, sample_rate, path, interval, sender));
+    if let Err(e) = result {
+        error!("failed to start wake word reload: {:?}", e);
+        return None;
+    }
+    Some(receiver)
+}
+
+fn watch_files(
+    settings: Settings,
+    sample_rate: u32,
+    mut path: String,
+    interval: Duration,
+    models: Sender<WakeModel>,
+) {
+    let files = |path: &str| {
+        let mut files = vec![path.to_string()];
+        files.extend(settings.wakeword_config.clone());
+        files
+    };
+    let mut stamps = stamps_of(&files(&path));
+    loop {
+        thread::sleep(interval);
+        let current = stamps_of(&files(&path));
+        if current == stamps {
+            continue;
+        }
+        stamps = current;
+        match WakeModel::load(&settings, sample_rate) {
+            Ok(model) => {
+                info!(
+                    template = %model.path,
+                    threshold = model.threshold,
+                    reject_threshold = model.reject_threshold,
+                    "Wake word model reloaded"
+                );
+                metrics().wake_reloads.with_label_values(&["ok"]).inc();
+                path = model.path.clone();
+                // 换用新的模版文件后以其修改时间为准
+                stamps = stamps_of(&files(&path));
+                if models.send(model).is_err() {
+                    return;
+                }
+            }
+            Err(e) => {
+                error!(
+                    "failed to reload wake word model, keeping the current one: {:?}",
+                    e
+                );
+                metrics().wake_reloads.with_label_values(&["error"]).inc();
+            }
+        }
+    }
+}
+
+/// 文件的修改时间与大小（不存在时为空）
+fn stamps_of(files: &[String]) -> Vec<Option<(SystemTime, u64)>> {
+    files
+        .iter()
+        .map(|file| {
+            let metadata = fs::metadata(file).ok()?;
+            Some((metadata.modified().ok()?, metadata.len()))
+        })
+        .collect()
+}
+
+/// 从二进制文件加载预训练的MFCC模版
+fn load_mfcc_template(path: &str) -> Result<Array1<f32>> {
+    let mut file =
+        File::open(path).with_context(|| format!("failed to open MFCC template file: {}", path))?;
+
+    let mut buffer = Vec::new();
+    file.read_to_end(&mut buffer)
+        .with_context(|| "failed to read binary file")?;
+    if buffer.len() % 4 != 0 {
+        return Err(anyhow!("truncated MFCC template file: {}", path));
+    }
+
+    // 每4字节转换为一个f32（控制为小端序）
+    let mfcc_data: Vec<f32> = buffer
+        .chunks_exact(4)
+        .map(|chunk| {
+            let mut reader = Cursor::new(chunk);
+            reader
+                .read_f32::<LittleEndian>()
+                .with_context(|| "failed to parse")
+        })
+        .collect::<Result<_>>()?;
+    Ok(Array1::from_vec(mfcc_data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 测试专用的临时目录
+    fn temp_dir(name: &str) -> std::path::PathBuf {
+        let directory =
+            std::env::temp_dir().join(format!("wake-model-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&directory);
+        fs::create_dir_all(&directory).unwrap();
+        directory
+    }
+
+    fn write_template(path: &Path, values: &[f32]) {
+        let bytes: Vec<u8> = values.iter().flat_map(|v| v.to_le_bytes()).collect();
+        fs::write(path, bytes).unwrap();
+    }
+
+    fn settings(directory: &Path) -> Settings {
+        Settings {
+            wakeword_path: directory.join("hey.bin").to_string_lossy().into_owned(),
+            wake_threshold: 0.8,
+            reject_threshold: 0.5,
+            ..Settings::default()
+        }
+    }
+
+    #[test]
+    fn config_overrides_template_and_thresholds() {
+        let directory = temp_dir("config");
+        let settings = settings(&directory);
+        write_template(Path::new(&settings.wakeword_path), &[1.0; 13]);
+
+        let model = WakeModel::load(&settings, 16000).unwrap();
+        assert_eq!(model.keyword, "hey");
+        assert_eq!((model.threshold, model.reject_threshold), (0.8, 0.5));
+
+        let other = directory.join("hello.bin");
+        write_template(&other, &[0.5; 13]);
+        let config = directory.join("wake.json");
+        let json = format!(r#"{{"template": "{}", "threshold": 0.9}}"#, other.display());
+        fs::write(&config, json).unwrap();
+        let settings = Settings {
+            wakeword_config: Some(config.to_string_lossy().into_owned()),
+            ..settings
+        };
+        let model = WakeModel::load(&settings, 16000).unwrap();
+        assert_eq!(model.keyword, "hello");
+        assert_eq!(model.template[0], 0.5);
+        assert_eq!((model.threshold, model.reject_threshold), (0.9, 0.5));
+    }
+
+    #[test]
+    fn rejects_invalid_templates() {
+        let directory = temp_dir("invalid");
+        let settings = settings(&directory);
+        let error =
+            |settings: &Settings| format!("{:#}", WakeModel::load(settings, 16000).err().unwrap());
+
+        assert!(error(&settings).starts_with("failed to open MFCC template file"));
+        write_template(Path::new(&settings.wakeword_path), &[1.0; 12]);
+        assert!(error(&settings).ends_with("has 12 values, expected 13"));
+        fs::write(&settings.wakeword_path, [0u8; 6]).unwrap();
+        assert!(error(&settings).starts_with("truncated MFCC template file"));
+
+        let config = directory.join("wake.json");
+        fs::write(&config, "{").unwrap();
+        let settings = Settings {
+            wakeword_config: Some(config.to_string_lossy().into_owned()),
+            ..settings
+        };
+        assert!(error(&settings).starts_with("invalid wake word config"));
+    }
+}